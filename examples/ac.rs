@@ -13,6 +13,7 @@ fn main() -> ! {
 
     // Constrain a few peripherals into our HAL types
     let clkctrl = dp.CLKCTRL.constrain();
+    let portmux = dp.PORTMUX.constrain();
 
     // Configure our clocks
     let _clocks = clkctrl.freeze();
@@ -27,6 +28,7 @@ fn main() -> ! {
     // Grab the AC output pin and disable its pullup
     let mut acout = a.pa5.into_stateless_push_pull_output();
     acout.internal_pull_up(Toggle::Off);
+    let acout = IntoMuxedPinset::<pac::AC0>::mux(acout, &portmux);
 
     // Create a comparator
     let ac = dp.AC0.comparator(
@@ -37,7 +39,7 @@ fn main() -> ! {
             ..Default::default()
         },
     );
-    ac.output_pin(acout);
+    ac.output_pin(&acout);
     let _ac = ac.enable();
 
     loop {}
@@ -18,6 +18,7 @@ fn main() -> ! {
 
     // Constrain a few peripherals into our HAL types
     let clkctrl = dp.CLKCTRL.constrain();
+    let portmux = dp.PORTMUX.constrain();
 
     // Configure our clocks
     let clocks = clkctrl.freeze();
@@ -30,28 +31,31 @@ fn main() -> ! {
     let mut led = b.pb6.into_push_pull_output();
 
     // Setup VREF for DAC to 2.5V
-    let mut vref = dp.VREF.constrain();
-    let dacref = vref.dac0(ReferenceVoltage::_2V50);
+    let mut vref = dp.VREF.split();
+    let dacref = vref
+        .dac0
+        .with_voltage(&mut vref.vref, ReferenceVoltage::_2V50);
 
     // Setup the DAC
     let mut dac = dp.DAC0.constrain(dacref);
     dac.dac_set_value(0);
-    let dac = dac.enable();
 
-    // Lock the DAC into an enabled state, now it cannot be disabled anymore
-    // but we can also get output objects that can be passed into other
-    // peripherals like the negative AC0 pin
-    let mut dac = dac.lock_enable();
+    // Enable the DAC without its output pin and lock it into an enabled
+    // state, now it cannot be disabled anymore but we can also get output
+    // objects that can be passed into other peripherals like the negative
+    // AC0 pin
+    let mut dac = dac.enable_internal();
 
     // Grab AINP0
     let ainp0 = a.pa7.into_analog_input();
 
     // Grab the DAC as AINN0
-    let ainn0 = dac.dac_get_ac0_input();
+    let ainn0 = dac.dac_get_ac_input();
 
     // Grab the AC output pin and disable its pullup
     let mut acout = a.pa5.into_stateless_push_pull_output();
     acout.internal_pull_up(Toggle::Off);
+    let acout = IntoMuxedPinset::<pac::AC0>::mux(acout, &portmux);
 
     // Create a comparator
     let ac = dp.AC0.comparator(
@@ -62,7 +66,7 @@ fn main() -> ! {
             ..Default::default()
         },
     );
-    ac.output_pin(acout);
+    ac.output_pin(&acout);
     let _ac = ac.enable();
 
     // Create a delay timer
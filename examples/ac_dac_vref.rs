@@ -14,6 +14,7 @@ fn main() -> ! {
 
     // Constrain a few peripherals into our HAL types
     let clkctrl = dp.CLKCTRL.constrain();
+    let portmux = dp.PORTMUX.constrain();
 
     // Configure our clocks
     let _clocks = clkctrl.freeze();
@@ -24,8 +25,10 @@ fn main() -> ! {
     // Setup VREF for DAC to 2.5V
     // This reference voltage can be passed into the AC negative input to
     // compare against
-    let mut vref = dp.VREF.constrain();
-    let dacref = vref.dac0(ReferenceVoltage::_2V50);
+    let mut vref = dp.VREF.split();
+    let dacref = vref
+        .dac0
+        .with_voltage(&mut vref.vref, ReferenceVoltage::_2V50);
 
     // Grab AINP0
     let ainp0 = a.pa7.into_analog_input();
@@ -33,6 +36,7 @@ fn main() -> ! {
     // Grab the AC output pin and disable its pullup
     let mut acout = a.pa5.into_stateless_push_pull_output();
     acout.internal_pull_up(Toggle::Off);
+    let acout = IntoMuxedPinset::<pac::AC0>::mux(acout, &portmux);
 
     // Create a comparator
     let ac = dp.AC0.comparator(
@@ -43,7 +47,7 @@ fn main() -> ! {
             ..Default::default()
         },
     );
-    ac.output_pin(acout);
+    ac.output_pin(&acout);
     let _ac = ac.enable();
 
     loop {}
@@ -0,0 +1,41 @@
+#![no_std]
+#![no_main]
+
+use panic_halt as _;
+
+use atxtiny_hal::adc::Vdd;
+use atxtiny_hal::pac;
+use atxtiny_hal::prelude::*;
+use atxtiny_hal::serial::Serial;
+
+#[avr_device::entry]
+fn main() -> ! {
+    let dp = pac::Peripherals::take().unwrap();
+
+    // Constrain a few peripherals into our HAL types
+    let clkctrl = dp.CLKCTRL.constrain();
+    let portmux = dp.PORTMUX.constrain();
+
+    // Configure our clocks
+    let clocks = clkctrl.freeze();
+
+    // Split the PORTA peripheral into its pins
+    let a = dp.PORTA.split();
+
+    // Create a serial port to print the results
+    let rxpin = a.pa2.into_peripheral::<pac::USART0>();
+    let txpin = a.pa1.into_peripheral::<pac::USART0>();
+    let usart_pair = (rxpin, txpin).mux(&portmux);
+    let mut s = Serial::new(dp.USART0, usart_pair, 115200u32.bps(), clocks);
+
+    // AIN7 is on PA7
+    let ain7 = a.pa7.into_analog_input();
+
+    // Configure the ADC to use VDD as its reference
+    let mut adc = dp.ADC0.constrain(Vdd).configure(clocks).unwrap();
+
+    loop {
+        let value = adc.read(&ain7);
+        ufmt::uwriteln!(s, "AIN7: {}\r", value).unwrap();
+    }
+}
@@ -0,0 +1,49 @@
+#![no_std]
+#![no_main]
+
+use core::fmt::Write;
+use panic_halt as _;
+
+use atxtiny_hal::gpio::{porta::*, portb::*, Input, Output, Stateful, Stateless};
+use atxtiny_hal::pac;
+use atxtiny_hal::prelude::*;
+use atxtiny_hal::serial::{Serial, UartPinset};
+
+// Describe the whole board in one place
+// Using a pin twice or handing out a peripheral twice is a compile error
+atxtiny_hal::board! {
+    pub struct Board {
+        use(dp, clocks, portmux, a, b, c);
+
+        clocks = |clkctrl| clkctrl.freeze();
+
+        pub led: PB6<Output<Stateful>> = b.pb6.into_push_pull_output(),
+        pub button: PB7<Input> = b.pb7.into_pull_up_input(),
+        pub serial: Serial<pac::USART0, UartPinset<pac::USART0, PA2<Input>, PA1<Output<Stateless>>>> =
+            Serial::new(
+                dp.USART0,
+                (
+                    a.pa2.into_peripheral::<pac::USART0>(),
+                    a.pa1.into_peripheral::<pac::USART0>(),
+                )
+                    .mux(&portmux),
+                115200u32.bps(),
+                clocks,
+            ),
+    }
+}
+
+#[avr_device::entry]
+fn main() -> ! {
+    let mut board = Board::take().unwrap();
+
+    board.serial.write_str("Hello World\r\n").unwrap();
+
+    loop {
+        if board.button.is_low().unwrap() {
+            board.led.set_high().unwrap();
+        } else {
+            board.led.set_low().unwrap();
+        }
+    }
+}
@@ -14,6 +14,7 @@ fn main() -> ! {
 
     // Constrain a few peripherals into our HAL types
     let clkctrl = dp.CLKCTRL.constrain();
+    let portmux = dp.PORTMUX.constrain();
 
     // Configure our clocks
     let _clocks = clkctrl.freeze();
@@ -24,15 +25,18 @@ fn main() -> ! {
     // Grab the DAC output pin and disable its pullup
     let mut dacout = a.pa6.into_stateless_push_pull_output();
     dacout.internal_pull_up(Toggle::Off);
+    let dacout = IntoMuxedPinset::<pac::DAC0>::mux(dacout, &portmux);
 
     // Set up the reference voltage
     // Note: the configured VREF can be cloned to pass it into the DAC and AC
     //       at the same time if needed
-    let mut vref = dp.VREF.constrain();
-    let dacref = vref.dac0(ReferenceVoltage::_4V34);
+    let mut vref = dp.VREF.split();
+    let dacref = vref
+        .dac0
+        .with_voltage(&mut vref.vref, ReferenceVoltage::_4V34);
 
     let mut dac = dp.DAC0.constrain(dacref);
-    dac.output_pin(dacout);
+    dac.output_pin(&dacout);
     dac.dac_set_value(128);
     let _dac = dac.enable();
 
@@ -53,22 +53,13 @@ fn main() -> ! {
     // Grab the event system channels
     let evsys = dp.EVSYS.split();
 
-    // AC event -> EVOUT0 (PA2)
-    let async_ch0 = evsys.channel_async0;
-    let async_ch0 = ac.connect_event_generator(async_ch0, ());
-    let _async_ch0_evout0 = async_ch0.connect_event_user(&evout0);
-    // FIXME: This should work somehow
-    //        Problem is, we somehow need to track which users we
-    //        assigned the channel to to free them later. This requires macro magic
-    //        Right now one channel can only have one user an event is delivered to
-    //        A possible workaround is to poke the registers directly (ugh, I know)
-    //        or assign a second channel to the generator and the user.
-    //let _async_ch0_evout1 = async_ch0.connect_event_user(&evout1);
-
-    // AC event -> EVOUT1 (PB2)
-    let async_ch2 = evsys.channel_async2;
-    let async_ch2 = ac.connect_event_generator(async_ch2, ());
-    let _async_ch2 = async_ch2.connect_event_user(&evout1);
+    // AC event -> EVOUT0 (PA2) and EVOUT1 (PB2)
+    let _async_ch0 = evsys
+        .channel_async0
+        .route(&mut ac, ())
+        .to(&evout0)
+        .to(&evout1)
+        .apply();
 
     let _ac = ac.enable();
 
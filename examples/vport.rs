@@ -0,0 +1,34 @@
+#![no_std]
+#![no_main]
+
+//! Bit-banging through the VPORT registers
+//!
+//! Pins with a compile time defined port and pin number are set, cleared and
+//! toggled with single `sbi`/`cbi` instructions on the VPORT registers. This
+//! is verified by `scripts/check-vport.sh`, which builds this example and
+//! fails if the disassembly of `main` doesn't contain `sbi 0x05, 6`,
+//! `cbi 0x05, 6` and `sbi 0x06, 5` or still accesses the output registers of
+//! PORTB.
+
+use panic_halt as _;
+
+use atxtiny_hal::pac;
+use atxtiny_hal::prelude::*;
+
+#[avr_device::entry]
+fn main() -> ! {
+    let dp = pac::Peripherals::take().unwrap();
+    let clkctrl = dp.CLKCTRL.constrain();
+
+    let _clocks = clkctrl.freeze();
+
+    let b = dp.PORTB.split();
+    let mut data = b.pb6.into_stateless_push_pull_output();
+    let mut clock = b.pb5.into_push_pull_output();
+
+    loop {
+        data.set_high().unwrap();
+        data.set_low().unwrap();
+        clock.toggle().unwrap();
+    }
+}
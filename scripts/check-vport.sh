@@ -0,0 +1,34 @@
+#!/bin/sh
+# Checks that the vport example sets, clears and toggles its pins with single
+# sbi/cbi instructions on the VPORTB registers instead of accessing PORTB.
+#
+# Exits with a non-zero status if any of the expected instructions is missing
+# or main writes the output registers of PORTB. The setup of the pins only
+# touches DIRSET and PINnCTRL, so any access to OUT, OUTSET, OUTCLR or OUTTGL
+# comes from the loop.
+
+set -eu
+
+ELF=target/avr-attiny817/release/examples/vport.elf
+
+cargo build --release --example vport
+
+DISASM=$(avr-objdump -d --disassemble=main "$ELF")
+
+fail=0
+for insn in 'sbi[[:space:]]+0x05, 6' 'cbi[[:space:]]+0x05, 6' 'sbi[[:space:]]+0x06, 5'; do
+    if ! echo "$DISASM" | grep -Eq "$insn"; then
+        echo "missing instruction: $insn" >&2
+        fail=1
+    fi
+done
+
+# PORTB.OUT, OUTSET, OUTCLR and OUTTGL are located at 0x0424..0x0427
+PORTB_OUT='(lds|sts)[[:space:]]+0x042[4-7]\b'
+if echo "$DISASM" | grep -Eq "$PORTB_OUT"; then
+    echo "main accesses the output registers of PORTB:" >&2
+    echo "$DISASM" | grep -E "$PORTB_OUT" >&2
+    fail=1
+fi
+
+exit $fail
@@ -2,11 +2,16 @@
 
 use crate::{
     dac::DACOutputToAC,
-    gpio::{Analog, Output, Stateless},
+    gpio::{Analog, Output, Release, Stateless},
     pac::AC0,
 };
 use core::marker::PhantomData;
 
+#[cfg(feature = "enumset")]
+use enumset::{EnumSet, EnumSetType};
+
+use crate::Toggle;
+
 /// Enabled Comparator (type state)
 pub struct Enabled;
 
@@ -17,11 +22,17 @@ pub trait ED {}
 impl ED for Enabled {}
 impl ED for Disabled {}
 
+/// Comparator configuration
 #[derive(Copy, Clone, Eq, PartialEq)]
 pub struct Config {
+    /// Hysteresis of the comparator
     pub hysteresis: Hysteresis,
+    /// Reduce the power consumption at the cost of a longer propagation delay
     pub low_power_mode: bool,
+    /// Invert the output of the comparator
     pub inverted: bool,
+    /// Keep the comparator running in standby sleep mode
+    pub run_in_standby: bool,
 }
 
 impl Default for Config {
@@ -30,16 +41,24 @@ impl Default for Config {
             hysteresis: Hysteresis::Off,
             low_power_mode: false,
             inverted: false,
+            run_in_standby: false,
         }
     }
 }
 
 impl Config {
+    /// Set the hysteresis of the comparator
     pub fn hysteresis(mut self, hysteresis: Hysteresis) -> Self {
         self.hysteresis = hysteresis;
         self
     }
 
+    /// Enable the low power mode
+    ///
+    /// The comparator draws less current, but its propagation delay gets
+    /// considerably longer, see the electrical characteristics of the device.
+    /// Fast signals and PWM generation triggered by the comparator suffer
+    /// from the added delay, slow voltage supervision does not.
     pub fn low_power_mode(mut self) -> Self {
         self.low_power_mode = true;
         self
@@ -54,23 +73,64 @@ impl Config {
         self.inverted = inverted;
         self
     }
+
+    /// Keep the comparator running in standby sleep mode
+    ///
+    /// Combined with the [`low_power_mode`](Config::low_power_mode), this
+    /// allows to supervise a voltage in standby at a reduced current and to
+    /// wake up the CPU by the comparator interrupt.
+    pub fn run_in_standby(mut self, enable: impl Into<Toggle>) -> Self {
+        let enable: Toggle = enable.into();
+        self.run_in_standby = enable.into();
+        self
+    }
 }
 
-#[derive(Copy, Clone, Eq, PartialEq)]
+/// Hysteresis of the comparator
+///
+/// The output only toggles once the difference of the inputs exceeds the
+/// hysteresis, which prevents chatter of the output with noisy inputs.
+#[derive(ufmt::derive::uDebug, Debug, Copy, Clone, Eq, PartialEq)]
 pub enum Hysteresis {
+    /// No hysteresis
     Off = 0,
+    /// 10mV hysteresis
     _10mV = 1,
+    /// 25mV hysteresis
     _25mV = 2,
+    /// 50mV hysteresis
     _50mV = 3,
 }
 
-#[derive(Copy, Clone, Eq, PartialEq)]
+/// Edges of the comparator output raising the interrupt
+#[derive(ufmt::derive::uDebug, Debug, Copy, Clone, Eq, PartialEq)]
 pub enum InterruptMode {
+    /// Rising and falling edges
     BothEdges = 0,
+    /// Falling edges
     NegativeEdge = 2,
+    /// Rising edges
     PositiveEdge = 3,
 }
 
+/// Interrupts for AC
+#[derive(ufmt::derive::uDebug, Debug)]
+#[cfg_attr(feature = "enumset", derive(EnumSetType))]
+#[cfg_attr(not(feature = "enumset"), derive(Copy, Clone, PartialEq, Eq))]
+pub enum Interrupt {
+    /// The output toggled according to the [`InterruptMode`]
+    Compare,
+}
+
+/// Status events for AC
+#[derive(ufmt::derive::uDebug, Debug)]
+#[cfg_attr(feature = "enumset", derive(EnumSetType))]
+#[cfg_attr(not(feature = "enumset"), derive(Copy, Clone, PartialEq, Eq))]
+pub enum Event {
+    /// The output toggled according to the [`InterruptMode`]
+    Compare,
+}
+
 pub struct Comparator<AC, ED> {
     regs: AC,
     _enabled: PhantomData<ED>,
@@ -100,6 +160,8 @@ macro_rules! impl_comparator {
                         .bits(config.hysteresis as u8)
                         .lpmode()
                         .bit(config.low_power_mode)
+                        .runstdby()
+                        .bit(config.run_in_standby)
                 });
 
                 self.muxctrla()
@@ -135,6 +197,7 @@ macro_rules! impl_comparator {
             }
 
             /// Enables raising the comparator interrupt at the specified output signal edge
+            #[deprecated(note = "use `set_interrupt_mode` and `configure_interrupt` instead")]
             #[inline]
             pub fn listen(&self, mode: InterruptMode) {
                 self.regs
@@ -142,6 +205,14 @@ macro_rules! impl_comparator {
                     .modify(|_, w| unsafe { w.intmode().bits(mode as u8) });
                 self.regs.intctrl().write(|w| w.cmp().set_bit());
             }
+
+            /// Select the edges of the output setting the [`Event::Compare`] flag
+            #[inline]
+            pub fn set_interrupt_mode(&mut self, mode: InterruptMode) {
+                self.regs
+                    .ctrla()
+                    .modify(|_, w| unsafe { w.intmode().bits(mode as u8) });
+            }
         }
 
         impl Comparator<$COMP, Enabled> {
@@ -163,32 +234,153 @@ macro_rules! impl_comparator {
 
         impl<ED> Comparator<$COMP, ED> {
             /// Disables raising interrupts for the output signal
+            #[deprecated(note = "use `configure_interrupt` instead")]
             #[inline]
             pub fn unlisten(&self) {
                 self.regs.intctrl().modify(|_, w| w.cmp().clear_bit());
             }
 
             /// Returns `true` if the output signal interrupt is pending
+            #[deprecated(note = "use `is_event_triggered` instead")]
             #[inline]
             pub fn is_pending(&self) -> bool {
-                self.regs.status().read().cmp().bit_is_set()
+                self.is_event_triggered(Event::Compare)
             }
 
             /// Unpends the output signal interrupt
+            #[deprecated(note = "use `clear_event` instead")]
             #[inline]
             pub fn unpend(&self) {
                 self.regs.status().modify(|_, w| w.cmp().set_bit());
             }
 
-            /// Configures a GPIO pin to output the signal of the comparator
+            /// Enables or disables an interrupt
+            #[inline]
+            pub fn configure_interrupt(&mut self, interrupt: Interrupt, enable: impl Into<Toggle>) {
+                let enable: Toggle = enable.into();
+                let enable: bool = enable.into();
+                match interrupt {
+                    Interrupt::Compare => self.regs.intctrl().modify(|_, w| w.cmp().bit(enable)),
+                }
+            }
+
+            /// Enable or disable interrupt for the specified [`Interrupt`]s.
+            ///
+            /// Like [`Comparator::configure_interrupt`], but instead using an enumset. The
+            /// corresponding interrupt for every [`Interrupt`] in the set will be enabled, every
+            /// other interrupt will be **disabled**.
+            #[cfg(feature = "enumset")]
+            #[cfg_attr(docsrs, doc(cfg(feature = "enumset")))]
+            #[inline]
+            pub fn configure_interrupts(&mut self, interrupts: EnumSet<Interrupt>) {
+                for interrupt in interrupts.complement().iter() {
+                    self.configure_interrupt(interrupt, false);
+                }
+                for interrupt in interrupts.iter() {
+                    self.configure_interrupt(interrupt, true);
+                }
+            }
+
+            /// Check if an interrupt is enabled
+            #[inline]
+            pub fn is_interrupt_configured(&self, interrupt: Interrupt) -> bool {
+                match interrupt {
+                    Interrupt::Compare => self.regs.intctrl().read().cmp().bit(),
+                }
+            }
+
+            /// Check which interrupts are enabled
+            #[cfg(feature = "enumset")]
+            #[cfg_attr(docsrs, doc(cfg(feature = "enumset")))]
+            #[inline]
+            pub fn configured_interrupts(&mut self) -> EnumSet<Interrupt> {
+                let mut interrupts = EnumSet::new();
+
+                for interrupt in EnumSet::<Interrupt>::all().iter() {
+                    if self.is_interrupt_configured(interrupt) {
+                        interrupts |= interrupt;
+                    }
+                }
+
+                interrupts
+            }
+
+            /// Check if an event flag is set
+            #[inline]
+            pub fn is_event_triggered(&self, event: Event) -> bool {
+                match event {
+                    Event::Compare => self.regs.status().read().cmp().bit(),
+                }
+            }
+
+            /// Get an [`EnumSet`] of all set event flags
+            #[cfg(feature = "enumset")]
+            #[cfg_attr(docsrs, doc(cfg(feature = "enumset")))]
+            #[inline]
+            pub fn triggered_events(&self) -> EnumSet<Event> {
+                let mut events = EnumSet::new();
+
+                for event in EnumSet::<Event>::all().iter() {
+                    if self.is_event_triggered(event) {
+                        events |= event;
+                    }
+                }
+
+                events
+            }
+
+            /// Clear an event flag
+            #[inline]
+            pub fn clear_event(&mut self, event: Event) {
+                // Writing a one clears a flag, so the other flags must not be written back
+                match event {
+                    Event::Compare => self.regs.status().write(|w| w.cmp().set_bit()),
+                }
+            }
+
+            /// Clear **all** event flags
+            #[inline]
+            pub fn clear_events(&mut self) {
+                self.regs.status().write(|w| w.cmp().set_bit());
+            }
+
+            /// Drive the output pin of the `pinset` with the signal of the comparator
+            #[inline]
+            pub fn output_pin<P: ComparatorOutput<$COMP>>(
+                &self,
+                pinset: &AcOutputPinset<$COMP, P>,
+            ) {
+                pinset.out.setup(&self.regs);
+            }
+
+            /// Stop driving the output pin with the signal of the comparator
             #[inline]
-            pub fn output_pin<P: ComparatorOutput<$COMP>>(&self, pin: P) {
-                pin.setup(&self.regs);
+            pub fn disable_output_pin(&self) {
+                self.regs.ctrla().modify(|_, w| w.outen().clear_bit());
             }
         }
     };
 }
 
+/// Negative input of a comparator
+///
+/// Besides the pins, the comparator can compare against internal voltages,
+/// which allows programmable thresholds without an external divider:
+///
+/// * the output of the DAC, see [`Dac::dac_get_ac_input`](crate::dac::Dac::dac_get_ac_input)
+/// * the voltage reference shared with DAC0, see [`DACReferenceVoltage`]
+///
+/// ```
+/// let mut vref = dp.VREF.split();
+/// let dac_ref = vref.dac0.with_voltage(&mut vref.vref, ReferenceVoltage::_1V10);
+///
+/// // Compare against the 1.1V reference itself
+/// let ac = dp.AC0.comparator(ainp0, dac_ref, Config::default());
+///
+/// // Or against a fraction of it set by the DAC, which takes the token instead
+/// let dac = dp.DAC0.constrain(dac_ref).enable().lock_enable();
+/// let ac = dp.AC0.comparator(ainp0, dac.dac_get_ac_input(), Config::default());
+/// ```
 pub trait NegativeInput<AC>: crate::private::Sealed {
     fn setup(&self, comp: &AC);
 }
@@ -199,6 +391,40 @@ pub trait ComparatorOutput<AC>: crate::private::Sealed {
     fn setup(&self, comp: &AC);
 }
 
+/// Pin set for the port multiplexer
+pub struct AcOutputPinset<AC, Out: ComparatorOutput<AC>> {
+    _ac: PhantomData<AC>,
+    out: Out,
+}
+
+impl<AC, Out> AcOutputPinset<AC, Out>
+where
+    Out: ComparatorOutput<AC>,
+{
+    pub(crate) fn new(out: Out) -> Self {
+        AcOutputPinset {
+            _ac: PhantomData,
+            out,
+        }
+    }
+
+    /// Returns the pin in the mode used by the peripheral
+    pub fn free(self) -> Out {
+        self.out
+    }
+
+    /// Releases the pin back to GPIO
+    ///
+    /// The pin is converted back into a floating input, so it can be reused
+    /// after the peripheral has been shut down.
+    pub fn release(self) -> Out::Released
+    where
+        Out: Release,
+    {
+        self.out.release()
+    }
+}
+
 macro_rules! positive_input_pin {
     ($COMP:ident, $pin:ty, $variant:expr) => {
         impl PositiveInput<$COMP> for $pin {
@@ -286,8 +512,15 @@ refint_input!(
 use crate::evsys::ChannelConfigurator;
 use crate::evsys::{Channel, EventGenerator, GeneratorAssigned, Unconfigured};
 
-impl<Evsys, Index, AC> EventGenerator<Evsys, crate::evsys::Async, Index>
-    for Comparator<AC, Disabled>
+/// The output of the comparator is available as a generator on all
+/// asynchronous event channels, e.g. to capture a timer or to trip a TCD
+/// fault without CPU involvement.
+///
+/// ```
+/// let mut ac = dp.AC0.comparator(ainp0, ainn0, Config::default()).enable();
+/// let _channel = evsys.channel_async0.route(&mut ac, ()).to(&evout0).apply();
+/// ```
+impl<Evsys, Index, ED> EventGenerator<Evsys, crate::evsys::Async, Index> for Comparator<AC0, ED>
 where
     Evsys: crate::evsys::marker::Evsys,
     Index: crate::evsys::marker::Index,
@@ -299,6 +532,7 @@ where
         mut channel: Channel<Evsys, crate::evsys::Async, Index, Unconfigured>,
         _source: (),
     ) -> Channel<Evsys, crate::evsys::Async, Index, GeneratorAssigned> {
+        // AC0_OUT
         channel.set_generator(0x03);
         channel.into_state()
     }
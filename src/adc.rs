@@ -0,0 +1,1142 @@
+//! # Analog to Digital Converter
+//!
+//! The ADC is constrained into a [`AdcConfigurator`] first. After setting
+//! the desired options using the builder methods, the
+//! [configuration is applied](AdcConfigurator::configure) and a ready to use
+//! [`Adc`] is returned.
+//!
+//! ```
+//! let dp = pac::Peripherals::take().unwrap();
+//! let clocks = dp.CLKCTRL.constrain().freeze();
+//!
+//! let mut adc = dp.ADC0
+//!     .constrain(Vdd)
+//!     .configure(clocks)
+//!     .unwrap();
+//!
+//! let ain7 = porta.pa7.into_analog_input();
+//! let value = adc.read(&ain7);
+//! ```
+//!
+//! The internal reference can only be selected by passing the
+//! [`ADCReferenceVoltage`] token of the [split](crate::vref::VrefExt::split)
+//! VREF peripheral. The ADC owns the token until it is [freed](Adc::free) or
+//! the reference is [replaced](Adc::set_reference), so the voltage can't be
+//! changed under it:
+//!
+//! ```
+//! let mut vref = dp.VREF.split();
+//! let adc_ref = vref.adc0.with_voltage(&mut vref.vref, ReferenceVoltage::_2V50);
+//! let adc = dp.ADC0
+//!     .constrain(adc_ref)
+//!     .configure(clocks)
+//!     .unwrap();
+//! ```
+
+pub mod touch;
+
+use core::{convert::Infallible, ops::Deref};
+
+use crate::{
+    clkctrl::Clocks,
+    evsys::{Async, Channel, Configured, EventGenerator, EventUser, Unconfigured},
+    pac::adc0::{self, RegisterBlock},
+    slpctrl::{SleepMode, Slpctrl},
+    time::*,
+    vref::{ADCReferenceVoltage, ReferenceVoltage},
+    Toggle,
+};
+
+#[cfg(feature = "enumset")]
+use enumset::{EnumSet, EnumSetType};
+
+/// Maximum ADC clock frequency for conversions with full 10 bit resolution
+const ADC_CLOCK_MAX: u32 = 1_500_000;
+
+/// Minimum ADC clock frequency
+const ADC_CLOCK_MIN: u32 = 50_000;
+
+/// ADC error
+#[derive(ufmt::derive::uDebug, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The requested ADC clock frequency cannot be derived from the peripheral
+    /// clock using the available prescalers
+    ImpossibleClock,
+}
+
+/// Interrupts
+///
+/// Interrupts that can be enabled or disabled by [`Adc::enable_interrupt`]
+/// or [`Adc::disable_interrupt`].
+/// When an interrupt occurs, [`Event`] flags in status registers are set which can be read by
+/// [`Adc::is_event_triggered`] and cleared by [`Adc::clear_event`].
+#[derive(ufmt::derive::uDebug, Debug)]
+#[cfg_attr(feature = "enumset", derive(EnumSetType))]
+#[cfg_attr(not(feature = "enumset"), derive(Copy, Clone, PartialEq, Eq))]
+pub enum Interrupt {
+    /// Result Ready Interrupt Enable
+    ///
+    /// The interrupt is raised when a conversion is complete and a new result
+    /// is ready.
+    #[doc(alias = "RESRDY")]
+    ResultReady,
+}
+
+/// Status events
+///
+/// All events can be cleared by [`Adc::clear_event`] or [`Adc::clear_events`].
+/// Some events are also cleared on other conditions.
+#[derive(ufmt::derive::uDebug, Debug)]
+#[cfg_attr(feature = "enumset", derive(EnumSetType))]
+#[cfg_attr(not(feature = "enumset"), derive(Copy, Clone, PartialEq, Eq))]
+pub enum Event {
+    /// Result Ready Interrupt Flag
+    ///
+    /// This event is set by hardware when a conversion is complete. It is
+    /// cleared when the result is read.
+    #[doc(alias = "RESRDY")]
+    ResultReady,
+}
+
+/// Reference voltage of the ADC
+///
+/// Selected by passing a [`ReferenceSelection`] to the ADC.
+#[derive(ufmt::derive::uDebug, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Reference {
+    /// The internal reference voltage
+    ///
+    /// The voltage level is selected using the [`VREF`](crate::vref::Vref)
+    /// peripheral.
+    Internal,
+
+    /// The supply voltage VDD
+    Vdd,
+}
+
+/// Selection of the reference voltage of an ADC
+///
+/// Implemented for [`Vdd`] and for the [`ADCReferenceVoltage`] token of the
+/// ADC, which proves that the voltage of the internal reference has been
+/// selected in the [`VREF`](crate::vref::Vref) peripheral.
+pub trait ReferenceSelection<ADC: Instance>: crate::private::Sealed {
+    #[doc(hidden)]
+    fn into_selection(self) -> (Reference, Option<ADC::InternalReference>);
+}
+
+/// The supply voltage VDD as the reference of an ADC
+#[derive(ufmt::derive::uDebug, Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Vdd;
+
+impl crate::private::Sealed for Vdd {}
+
+impl<ADC: Instance> ReferenceSelection<ADC> for Vdd {
+    #[inline]
+    fn into_selection(self) -> (Reference, Option<ADC::InternalReference>) {
+        (Reference::Vdd, None)
+    }
+}
+
+impl From<Reference> for adc0::ctrlc::REFSEL_A {
+    fn from(value: Reference) -> Self {
+        match value {
+            Reference::Internal => adc0::ctrlc::REFSEL_A::INTREF,
+            Reference::Vdd => adc0::ctrlc::REFSEL_A::VDDREF,
+        }
+    }
+}
+
+/// Resolution of a conversion result
+#[derive(ufmt::derive::uDebug, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Resolution {
+    /// 8 bit
+    _8Bit,
+    /// 10 bit
+    _10Bit,
+}
+
+impl Resolution {
+    /// The number of bits of a single conversion result
+    #[inline]
+    pub fn bits(self) -> u8 {
+        match self {
+            Resolution::_8Bit => 8,
+            Resolution::_10Bit => 10,
+        }
+    }
+}
+
+impl From<Resolution> for adc0::ctrla::RESSEL_A {
+    fn from(value: Resolution) -> Self {
+        match value {
+            Resolution::_8Bit => adc0::ctrla::RESSEL_A::_8BIT,
+            Resolution::_10Bit => adc0::ctrla::RESSEL_A::_10BIT,
+        }
+    }
+}
+
+/// Delay before the first sample after the ADC was enabled or woke up
+///
+/// The delay is given in ADC clock cycles and should be at least 32µs when
+/// using the internal reference voltage, to give it time to settle.
+#[derive(ufmt::derive::uDebug, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum InitDelay {
+    /// No delay
+    None,
+    /// 16 ADC clock cycles
+    _16,
+    /// 32 ADC clock cycles
+    _32,
+    /// 64 ADC clock cycles
+    _64,
+    /// 128 ADC clock cycles
+    _128,
+    /// 256 ADC clock cycles
+    _256,
+}
+
+impl From<InitDelay> for adc0::ctrld::INITDLY_A {
+    fn from(value: InitDelay) -> Self {
+        use adc0::ctrld::INITDLY_A::*;
+        match value {
+            InitDelay::None => DLY0,
+            InitDelay::_16 => DLY16,
+            InitDelay::_32 => DLY32,
+            InitDelay::_64 => DLY64,
+            InitDelay::_128 => DLY128,
+            InitDelay::_256 => DLY256,
+        }
+    }
+}
+
+/// Number of accumulated samples per conversion
+///
+/// When accumulating multiple samples, the ADC performs the configured number
+/// of conversions back to back and sums up the results. The sum can then be
+/// decimated to gain additional effective bits of resolution, assuming there
+/// is enough noise on the input signal. See [`Adc::read_decimated`].
+#[derive(ufmt::derive::uDebug, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Accumulation {
+    /// A single sample, no accumulation
+    None,
+    /// 2 samples
+    _2,
+    /// 4 samples
+    _4,
+    /// 8 samples
+    _8,
+    /// 16 samples
+    _16,
+    /// 32 samples
+    _32,
+    /// 64 samples
+    _64,
+}
+
+impl Accumulation {
+    /// The binary logarithm of the number of accumulated samples
+    #[inline]
+    fn log2(self) -> u8 {
+        match self {
+            Accumulation::None => 0,
+            Accumulation::_2 => 1,
+            Accumulation::_4 => 2,
+            Accumulation::_8 => 3,
+            Accumulation::_16 => 4,
+            Accumulation::_32 => 5,
+            Accumulation::_64 => 6,
+        }
+    }
+
+    /// The number of accumulated samples per conversion
+    #[inline]
+    pub fn samples(self) -> u8 {
+        1 << self.log2()
+    }
+
+    /// The number of additional bits gained by decimating the accumulated result
+    ///
+    /// Every fourfold oversampling gains one additional bit of resolution.
+    #[inline]
+    pub fn extra_bits(self) -> u8 {
+        self.log2() / 2
+    }
+
+    /// The number of bits the accumulated result is shifted right during
+    /// decimation
+    #[inline]
+    fn decimation_shift(self) -> u8 {
+        self.log2() - self.extra_bits()
+    }
+}
+
+impl From<Accumulation> for adc0::ctrlb::SAMPNUM_A {
+    fn from(value: Accumulation) -> Self {
+        use adc0::ctrlb::SAMPNUM_A::*;
+        match value {
+            Accumulation::None => ACC1,
+            Accumulation::_2 => ACC2,
+            Accumulation::_4 => ACC4,
+            Accumulation::_8 => ACC8,
+            Accumulation::_16 => ACC16,
+            Accumulation::_32 => ACC32,
+            Accumulation::_64 => ACC64,
+        }
+    }
+}
+
+/// Input channel of an ADC
+///
+/// This trait is implemented for all pins that are connected to the input
+/// multiplexer of the ADC when they are in [`Analog`](crate::gpio::Analog)
+/// mode, as well as for the internal channels like the [`TemperatureSensor`].
+///
+/// The output of the DAC can be measured by passing the token returned by
+/// [`Dac::dac_get_adc_input`](crate::dac::Dac::dac_get_adc_input).
+pub trait AdcChannel<ADC>: crate::private::Sealed {
+    /// Input multiplexer selection of this channel
+    #[doc(hidden)]
+    const CHANNEL: u8;
+}
+
+/// Internal temperature sensor channel
+///
+/// The temperature sensor needs to be measured against the internal 1.1V
+/// reference.
+#[derive(ufmt::derive::uDebug, Debug, Copy, Clone, PartialEq, Eq)]
+pub struct TemperatureSensor;
+
+/// Internal reference voltage channel
+#[derive(ufmt::derive::uDebug, Debug, Copy, Clone, PartialEq, Eq)]
+pub struct InternalReference;
+
+/// Ground channel
+#[derive(ufmt::derive::uDebug, Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Ground;
+
+impl crate::private::Sealed for TemperatureSensor {}
+impl crate::private::Sealed for InternalReference {}
+impl crate::private::Sealed for Ground {}
+
+/// Available ADC clock prescalers
+const PRESCALERS: [(u16, adc0::ctrlc::PRESC_A); 8] = [
+    (2, adc0::ctrlc::PRESC_A::DIV2),
+    (4, adc0::ctrlc::PRESC_A::DIV4),
+    (8, adc0::ctrlc::PRESC_A::DIV8),
+    (16, adc0::ctrlc::PRESC_A::DIV16),
+    (32, adc0::ctrlc::PRESC_A::DIV32),
+    (64, adc0::ctrlc::PRESC_A::DIV64),
+    (128, adc0::ctrlc::PRESC_A::DIV128),
+    (256, adc0::ctrlc::PRESC_A::DIV256),
+];
+
+/// Extension trait that constrains an ADC peripheral
+pub trait AdcExt<ADC: Instance>: crate::private::Sealed {
+    /// Constrains the ADC peripheral into a configurator.
+    ///
+    /// Consumes the [`pac::ADC0`] peripheral and converts it to a [`HAL`] internal type
+    /// constraining it's public access surface to fit the design of the `HAL`.
+    /// Using the [`configurator`], the peripheral can be initially configured with
+    /// a builder pattern. Afterwards the settings can be changed using the
+    /// provided methods.
+    ///
+    /// The reference is selected right away, either [`Vdd`] or the
+    /// [`ADCReferenceVoltage`] token of the ADC. It can be changed later on
+    /// using [`Adc::set_reference`], which returns the token again.
+    ///
+    /// [`pac::ADC0`]: `crate::pac::ADC0`
+    /// [`HAL`]: `crate`
+    /// [`configurator`]: `AdcConfigurator`
+    fn constrain(self, reference: impl ReferenceSelection<ADC>) -> AdcConfigurator<ADC>;
+}
+
+/// Constrained ADC peripheral configurator
+///
+/// An instance of this struct is acquired by calling the [`constrain`](AdcExt::constrain) function
+/// on the [`ADC0`](crate::pac::ADC0) struct.
+///
+/// ```
+/// let dp = pac::Peripherals::take().unwrap();
+/// let adc_cfg = dp.ADC0.constrain(Vdd);
+/// ```
+pub struct AdcConfigurator<ADC: Instance> {
+    adc: ADC,
+    reference: Reference,
+    internal_reference: Option<ADC::InternalReference>,
+    clock: Hertz,
+    accumulation: Accumulation,
+    resolution: Resolution,
+    sample_length: u8,
+    init_delay: InitDelay,
+    reduced_sample_capacitance: bool,
+    run_in_standby: bool,
+    supply_voltage: Option<u16>,
+}
+
+/// Configured ADC peripheral
+///
+/// An instance of this struct is acquired by calling the [`constrain`](AdcExt::constrain) function
+/// on the [`ADC0`](crate::pac::ADC0) struct and then [finishing the configuration](AdcConfigurator::configure)
+/// on the constrained peripheral.
+///
+/// ```
+/// let dp = pac::Peripherals::take().unwrap();
+/// let adc_cfg = dp.ADC0.constrain(Vdd);
+/// let adc = adc_cfg.configure(clocks).unwrap();
+/// ```
+pub struct Adc<ADC: Instance> {
+    adc: ADC,
+    clock: Hertz,
+    accumulation: Accumulation,
+    resolution: Resolution,
+    reference: Reference,
+    internal_reference: Option<ADC::InternalReference>,
+    supply_voltage: Option<u16>,
+}
+
+impl<ADC: Instance> AdcConfigurator<ADC> {
+    /// Set the maximum desired ADC clock frequency
+    ///
+    /// The prescaler is chosen so that the resulting ADC clock is as fast as
+    /// possible, but does not exceed this frequency. It defaults to 1.5MHz,
+    /// which is the maximum for conversions with full resolution.
+    pub fn clock_frequency(mut self, freq: Hertz) -> Self {
+        self.clock = freq;
+        self
+    }
+
+    /// Set the number of samples accumulated per conversion
+    ///
+    /// Defaults to [`Accumulation::None`].
+    pub fn accumulation(mut self, accumulation: Accumulation) -> Self {
+        self.accumulation = accumulation;
+        self
+    }
+
+    /// Set the resolution of the conversions
+    ///
+    /// Defaults to [`Resolution::_10Bit`].
+    pub fn resolution(mut self, resolution: Resolution) -> Self {
+        self.resolution = resolution;
+        self
+    }
+
+    /// Extend the sampling time by the given number of ADC clock cycles
+    ///
+    /// The sampling time can be extended by up to 31 cycles to give the sample
+    /// capacitor enough time to charge when sampling high-impedance sources.
+    /// Defaults to 0.
+    pub fn sample_length(mut self, cycles: u8) -> Self {
+        self.sample_length = cycles.min(31);
+        self
+    }
+
+    /// Set the delay before the first sample after the ADC is enabled
+    ///
+    /// Defaults to [`InitDelay::None`].
+    pub fn init_delay(mut self, delay: InitDelay) -> Self {
+        self.init_delay = delay;
+        self
+    }
+
+    /// Use the reduced sample capacitance
+    ///
+    /// The datasheet recommends the reduced capacitance for reference voltages
+    /// above 1V. Defaults to `false`.
+    pub fn reduced_sample_capacitance(mut self, reduced: bool) -> Self {
+        self.reduced_sample_capacitance = reduced;
+        self
+    }
+
+    /// Keep the ADC running in standby sleep mode
+    ///
+    /// This allows [event triggered](Adc::configure_start_event) conversions
+    /// while the CPU sleeps in [`SleepMode::Standby`](crate::slpctrl::SleepMode::Standby).
+    /// The [`Interrupt::ResultReady`] interrupt wakes up the CPU again.
+    /// Defaults to `false`.
+    pub fn run_in_standby(mut self, enable: bool) -> Self {
+        self.run_in_standby = enable;
+        self
+    }
+
+    /// Set the supply voltage VDD in millivolts
+    ///
+    /// The supply voltage is only used to [convert](Adc::to_millivolts)
+    /// results into millivolts when [`Vdd`] is selected.
+    pub fn supply_voltage(mut self, millivolts: u16) -> Self {
+        self.supply_voltage = Some(millivolts);
+        self
+    }
+
+    /// Apply the configuration and return a configured [`Adc`]
+    ///
+    /// Returns [`Error::ImpossibleClock`] if the requested ADC clock
+    /// frequency can't be derived from the peripheral clock.
+    pub fn configure(self, clocks: Clocks) -> Result<Adc<ADC>, Error> {
+        let f_per = ADC::clock(&clocks).raw();
+        let target = self.clock.raw().min(ADC_CLOCK_MAX);
+
+        let (div, presc) = PRESCALERS
+            .iter()
+            .find(|(div, _)| f_per / (*div as u32) <= target)
+            .ok_or(Error::ImpossibleClock)?;
+
+        let clock = f_per / (*div as u32);
+        if clock < ADC_CLOCK_MIN {
+            return Err(Error::ImpossibleClock);
+        }
+
+        // Disable the ADC while reconfiguring it
+        self.adc.ctrla().write(|w| w.enable().clear_bit());
+
+        self.adc.ctrlc().write(|w| {
+            w.presc()
+                .variant(*presc)
+                .refsel()
+                .variant(self.reference.into())
+                .sampcap()
+                .bit(self.reduced_sample_capacitance)
+        });
+
+        self.adc
+            .ctrld()
+            .write(|w| w.initdly().variant(self.init_delay.into()));
+
+        // NOTE(unsafe): The sample length was limited to 5 bits
+        self.adc
+            .sampctrl()
+            .write(|w| unsafe { w.samplen().bits(self.sample_length) });
+
+        self.adc
+            .ctrlb()
+            .write(|w| w.sampnum().variant(self.accumulation.into()));
+
+        // Disable all interrupts
+        self.adc
+            .intctrl()
+            .write(|w| w.resrdy().clear_bit().wcmp().clear_bit());
+
+        self.adc.ctrla().write(|w| {
+            w.ressel()
+                .variant(self.resolution.into())
+                .freerun()
+                .clear_bit()
+                .runstby()
+                .bit(self.run_in_standby)
+                .enable()
+                .set_bit()
+        });
+
+        Ok(Adc {
+            adc: self.adc,
+            clock: clock.Hz(),
+            accumulation: self.accumulation,
+            resolution: self.resolution,
+            reference: self.reference,
+            internal_reference: self.internal_reference,
+            supply_voltage: self.supply_voltage,
+        })
+    }
+}
+
+impl<ADC: Instance> Adc<ADC> {
+    /// Returns the clock frequency of the ADC
+    pub fn clock(&self) -> Hertz {
+        self.clock
+    }
+
+    /// Set the reference voltage of the ADC
+    ///
+    /// Returns the [`ADCReferenceVoltage`] if it was selected before, so its
+    /// voltage can be changed or it can be returned to the [`VREF`](crate::vref::Vref).
+    #[inline]
+    pub fn set_reference(
+        &mut self,
+        reference: impl ReferenceSelection<ADC>,
+    ) -> Option<ADC::InternalReference> {
+        let (reference, internal_reference) = reference.into_selection();
+        self.reference = reference;
+        self.adc
+            .ctrlc()
+            .modify(|_, w| w.refsel().variant(reference.into()));
+
+        core::mem::replace(&mut self.internal_reference, internal_reference)
+    }
+
+    /// Set the number of samples accumulated per conversion
+    #[inline]
+    pub fn set_accumulation(&mut self, accumulation: Accumulation) {
+        self.accumulation = accumulation;
+        self.adc
+            .ctrlb()
+            .write(|w| w.sampnum().variant(accumulation.into()));
+    }
+
+    /// Returns the number of samples accumulated per conversion
+    #[inline]
+    pub fn accumulation(&self) -> Accumulation {
+        self.accumulation
+    }
+
+    /// Set the resolution of the conversions
+    #[inline]
+    pub fn set_resolution(&mut self, resolution: Resolution) {
+        self.resolution = resolution;
+        self.adc
+            .ctrla()
+            .modify(|_, w| w.ressel().variant(resolution.into()));
+    }
+
+    /// Returns the resolution of the conversions
+    #[inline]
+    pub fn resolution(&self) -> Resolution {
+        self.resolution
+    }
+
+    /// Enable or disable running the ADC in standby sleep mode
+    #[inline]
+    pub fn set_run_in_standby(&mut self, enable: impl Into<Toggle>) {
+        // Do a round way trip to be convert Into<Toggle> -> bool
+        let enable: Toggle = enable.into();
+        let enable: bool = enable.into();
+
+        self.adc.ctrla().modify(|_, w| w.runstby().bit(enable));
+    }
+
+    /// Extend the sampling time by the given number of ADC clock cycles
+    ///
+    /// The sample length is limited to 31 cycles.
+    #[inline]
+    pub fn set_sample_length(&mut self, cycles: u8) {
+        // NOTE(unsafe): The sample length is limited to 5 bits
+        self.adc
+            .sampctrl()
+            .write(|w| unsafe { w.samplen().bits(cycles.min(31)) });
+    }
+
+    /// Returns the number of valid bits of a result returned by [`Adc::read_decimated`]
+    #[inline]
+    pub fn decimated_bits(&self) -> u8 {
+        self.resolution.bits() + self.accumulation.extra_bits()
+    }
+
+    /// Returns the voltage of the currently selected reference in millivolts
+    ///
+    /// For the [internal reference](Reference::Internal) the voltage that is
+    /// currently selected in the [`VREF`](crate::vref::Vref) peripheral is
+    /// returned. For [`Reference::Vdd`] the [configured supply voltage](AdcConfigurator::supply_voltage)
+    /// is returned, which is `None` if it was never set.
+    pub fn reference_millivolts(&self) -> Option<u16> {
+        match self.reference {
+            Reference::Internal => ADC::internal_reference().map(|v| v.millivolts()),
+            Reference::Vdd => self.supply_voltage,
+        }
+    }
+
+    /// Convert a result returned by [`Adc::read`] into millivolts
+    ///
+    /// The configured [`Resolution`] and [`Accumulation`] are taken into
+    /// account. Returns `None` if the voltage of the reference is unknown.
+    pub fn to_millivolts(&self, raw: u16) -> Option<u16> {
+        let reference = self.reference_millivolts()? as u32;
+        let full_scale = (self.accumulation.samples() as u32) << self.resolution.bits();
+
+        Some(((raw as u32 * reference) / full_scale) as u16)
+    }
+
+    /// Perform a single conversion on the passed channel
+    ///
+    /// This function blocks until the conversion finished and returns the
+    /// result with the configured [`Resolution`]. If [accumulation](Accumulation) is enabled, the raw
+    /// sum of all accumulated samples is returned.
+    pub fn read<C: AdcChannel<ADC>>(&mut self, _channel: &C) -> u16 {
+        self.select_channel::<C>();
+
+        self.adc.command().write(|w| w.stconv().set_bit());
+
+        // Reading the result clears the flag again
+        while self.adc.intflags().read().resrdy().bit_is_clear() {}
+        self.adc.res().read().bits()
+    }
+
+    /// Perform a single conversion on the passed channel while the CPU is sleeping
+    ///
+    /// The CPU is put into [idle sleep](SleepMode::Idle) while the conversion
+    /// runs and woken up again by the [`Interrupt::ResultReady`] interrupt.
+    /// This reduces the noise coupled into the ADC by the CPU core and gives
+    /// more accurate results than [`Adc::read`]. The sleep mode of the
+    /// [`Slpctrl`] is left at [`SleepMode::Idle`].
+    ///
+    /// Global interrupts need to be enabled and the interrupt handler of the
+    /// result ready interrupt has to call [`Adc::on_result_ready`]. Otherwise
+    /// the pending interrupt would be serviced over and over again.
+    ///
+    /// ```
+    /// #[avr_device::interrupt(attiny817)]
+    /// fn ADC0_RESRDY() {
+    ///     Adc::<pac::ADC0>::on_result_ready();
+    /// }
+    ///
+    /// unsafe { avr_device::interrupt::enable() };
+    /// let value = adc.read_noise_reduced(&ain7, &mut slpctrl);
+    /// ```
+    pub fn read_noise_reduced<C: AdcChannel<ADC>>(
+        &mut self,
+        _channel: &C,
+        slpctrl: &mut Slpctrl,
+    ) -> u16 {
+        self.select_channel::<C>();
+        slpctrl.set_sleep_mode(SleepMode::Idle);
+
+        self.adc.intctrl().modify(|_, w| w.resrdy().set_bit());
+        self.adc.command().write(|w| w.stconv().set_bit());
+
+        let adc = &self.adc;
+        slpctrl.sleep_until(|| adc.intflags().read().resrdy().bit_is_set());
+
+        self.adc.intctrl().modify(|_, w| w.resrdy().clear_bit());
+
+        // Reading the result clears the flag again
+        self.adc.res().read().bits()
+    }
+
+    /// Interrupt handler helper for [`Adc::read_noise_reduced`]
+    ///
+    /// Disables the result ready interrupt so the CPU can return from the
+    /// interrupt handler while the result stays available for the sleeping
+    /// conversion. Call this function from the result ready interrupt handler.
+    #[inline]
+    pub fn on_result_ready() {
+        // NOTE(unsafe): Single read-modify-write from the interrupt handler while
+        // the main context is sleeping
+        let adc = unsafe { &*ADC::ptr() };
+        adc.intctrl().modify(|_, w| w.resrdy().clear_bit());
+    }
+
+    /// Perform a conversion on the passed channel and decimate the result
+    ///
+    /// The accumulated sum of all samples is shifted right so that the result
+    /// contains [`Adc::decimated_bits`] valid bits. Without accumulation this
+    /// is equivalent to [`Adc::read`].
+    pub fn read_decimated<C: AdcChannel<ADC>>(&mut self, channel: &C) -> u16 {
+        self.read(channel) >> self.accumulation.decimation_shift()
+    }
+
+    /// Gather a 32 bit random seed from the conversion noise of the ADC
+    ///
+    /// The least significant bit of repeated conversions of the
+    /// [`TemperatureSensor`] channel jitters due to thermal and supply noise.
+    /// Pairs of these bits are fed through a von Neumann extractor to remove
+    /// the bias. [Accumulation](Accumulation) is temporarily disabled, as it
+    /// would average the noise away.
+    ///
+    /// The result is not suitable for cryptographic purposes, but can be used to
+    /// seed a pseudo random number generator, e.g. for randomized backoff times or
+    /// session ids. Returns `None` if the conversion results did not contain
+    /// enough noise.
+    pub fn entropy_seed(&mut self) -> Option<u32> {
+        // Give up after this many pairs of conversions
+        const MAX_PAIRS: u16 = 4096;
+
+        let accumulation = self.accumulation;
+        self.set_accumulation(Accumulation::None);
+
+        let mut seed = 0u32;
+        let mut bits = 0;
+        for _ in 0..MAX_PAIRS {
+            let a = self.read(&TemperatureSensor) & 1;
+            let b = self.read(&TemperatureSensor) & 1;
+
+            // Only pairs of differing bits are unbiased
+            if a != b {
+                seed = (seed << 1) | a as u32;
+                bits += 1;
+
+                if bits == 32 {
+                    break;
+                }
+            }
+        }
+
+        self.set_accumulation(accumulation);
+
+        (bits == 32).then_some(seed)
+    }
+
+    /// Select the input channel for conversions that are not started by [`Adc::read`]
+    ///
+    /// This is needed for conversions that are triggered by an event.
+    #[inline]
+    pub fn select<C: AdcChannel<ADC>>(&mut self, _channel: &C) {
+        self.select_channel::<C>();
+    }
+
+    /// Enable or disable starting a conversion by an incoming event
+    ///
+    /// The ADC needs to be connected as a user to an event channel using
+    /// [`connect_event_user`](crate::evsys::Channel::connect_event_user). The
+    /// input channel of the triggered conversions is set using [`Adc::select`].
+    #[inline]
+    pub fn configure_start_event(&mut self, enable: impl Into<Toggle>) {
+        // Do a round way trip to be convert Into<Toggle> -> bool
+        let enable: Toggle = enable.into();
+        let enable: bool = enable.into();
+
+        self.adc.evctrl().write(|w| w.startei().bit(enable));
+    }
+
+    /// Start conversions on the passed channel by an asynchronous event generator
+    ///
+    /// Connects the generator to the passed event channel, the ADC as its
+    /// user and enables the [start event](Adc::configure_start_event). Each
+    /// event then starts a conversion whose result can be collected using
+    /// [`Adc::result`] or the [`Interrupt::ResultReady`] interrupt.
+    pub fn start_on_event<C, Evsys, Index, G>(
+        &mut self,
+        _input: &C,
+        generator: &mut G,
+        source: G::EventSource,
+        channel: Channel<Evsys, Async, Index, Unconfigured>,
+    ) -> Channel<Evsys, Async, Index, Configured>
+    where
+        C: AdcChannel<ADC>,
+        Evsys: crate::evsys::marker::Evsys,
+        Index: crate::evsys::marker::Index,
+        G: EventGenerator<Evsys, Async, Index>,
+        Self: EventUser<Evsys, Async>,
+    {
+        self.select_channel::<C>();
+        self.configure_start_event(Toggle::On);
+
+        generator
+            .connect_event_generator(channel, source)
+            .connect_event_user(self)
+    }
+
+    /// Start conversions on the passed channel by a synchronous event generator
+    ///
+    /// This is the same as [`Adc::start_on_event`] for synchronous generators.
+    /// It can be used to synchronize the sampling to a PWM signal, e.g. to
+    /// sample the current through a motor in the middle of the pulse:
+    ///
+    /// ```
+    /// let (mut ch0, ..) = pwm.split();
+    /// ch0.set_duty(duty);
+    /// let channel = adc.start_on_sync_event(&ain7, &mut ch0, (), evsys.channel_sync0);
+    /// ```
+    pub fn start_on_sync_event<C, Evsys, Index, G>(
+        &mut self,
+        _input: &C,
+        generator: &mut G,
+        source: G::EventSource,
+        channel: Channel<Evsys, crate::evsys::Sync, Index, Unconfigured>,
+    ) -> Channel<Evsys, crate::evsys::Sync, Index, Configured>
+    where
+        C: AdcChannel<ADC>,
+        Evsys: crate::evsys::marker::Evsys,
+        Index: crate::evsys::marker::Index,
+        G: EventGenerator<Evsys, crate::evsys::Sync, Index>,
+        Self: EventUser<Evsys, Async>,
+    {
+        self.select_channel::<C>();
+        self.configure_start_event(Toggle::On);
+
+        generator
+            .connect_event_generator(channel, source)
+            .connect_async_event_user(self)
+    }
+
+    /// Start a conversion on the passed channel without waiting for it to finish
+    ///
+    /// The result can be collected using [`Adc::wait`] or [`Adc::result`],
+    /// for example from the interrupt handler of the [`Interrupt::ResultReady`]
+    /// interrupt.
+    #[inline]
+    pub fn start<C: AdcChannel<ADC>>(&mut self, _channel: &C) {
+        self.select_channel::<C>();
+        self.adc.command().write(|w| w.stconv().set_bit());
+    }
+
+    /// Returns `true` if a conversion is currently running
+    #[inline]
+    pub fn is_converting(&self) -> bool {
+        self.adc.command().read().stconv().bit_is_set()
+    }
+
+    /// Wait for a [started](Adc::start) conversion to finish
+    ///
+    /// Returns [`nb::Error::WouldBlock`] until the result is ready.
+    #[inline]
+    pub fn wait(&mut self) -> nb::Result<u16, Infallible> {
+        self.result().ok_or(nb::Error::WouldBlock)
+    }
+
+    /// Returns the result of a finished conversion
+    ///
+    /// Returns `None` if no new result is available. Reading the result
+    /// clears the result ready flag.
+    #[inline]
+    pub fn result(&mut self) -> Option<u16> {
+        if self.adc.intflags().read().resrdy().bit_is_clear() {
+            return None;
+        }
+
+        Some(self.adc.res().read().bits())
+    }
+
+    /// Enable the interrupt for the specified [`Interrupt`].
+    #[inline]
+    pub fn enable_interrupt(&mut self, interrupt: Interrupt) {
+        self.configure_interrupt(interrupt, Toggle::On);
+    }
+
+    /// Disable the interrupt for the specified [`Interrupt`].
+    #[inline]
+    pub fn disable_interrupt(&mut self, interrupt: Interrupt) {
+        self.configure_interrupt(interrupt, Toggle::Off);
+    }
+
+    /// Enable or disable the interrupt for the specified [`Interrupt`].
+    #[inline]
+    pub fn configure_interrupt(&mut self, interrupt: Interrupt, enable: impl Into<Toggle>) {
+        // Do a round way trip to be convert Into<Toggle> -> bool
+        let enable: Toggle = enable.into();
+        let enable: bool = enable.into();
+        match interrupt {
+            Interrupt::ResultReady => self.adc.intctrl().modify(|_, w| w.resrdy().bit(enable)),
+        };
+    }
+
+    /// Enable or disable interrupt for the specified [`Interrupt`]s.
+    ///
+    /// Like [`Adc::configure_interrupt`], but instead using an enumset. The corresponding
+    /// interrupt for every [`Interrupt`] in the set will be enabled, every other interrupt will be
+    /// **disabled**.
+    #[cfg(feature = "enumset")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "enumset")))]
+    #[inline]
+    pub fn configure_interrupts(&mut self, interrupts: EnumSet<Interrupt>) {
+        for event in interrupts.complement().iter() {
+            self.configure_interrupt(event, false);
+        }
+        for event in interrupts.iter() {
+            self.configure_interrupt(event, true);
+        }
+    }
+
+    /// Check if an interrupt is configured for the [`Interrupt`]
+    #[inline]
+    pub fn is_interrupt_configured(&self, interrupt: Interrupt) -> bool {
+        match interrupt {
+            Interrupt::ResultReady => self.adc.intctrl().read().resrdy().bit_is_set(),
+        }
+    }
+
+    /// Check which interrupts are enabled for all [`Interrupt`]s
+    #[cfg(feature = "enumset")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "enumset")))]
+    #[inline]
+    pub fn configured_interrupts(&mut self) -> EnumSet<Interrupt> {
+        let mut interrupts = EnumSet::new();
+
+        for interrupt in EnumSet::<Interrupt>::all().iter() {
+            if self.is_interrupt_configured(interrupt) {
+                interrupts |= interrupt;
+            }
+        }
+
+        interrupts
+    }
+
+    /// Check if an interrupt event happend.
+    #[inline]
+    pub fn is_event_triggered(&self, event: Event) -> bool {
+        let flags = self.adc.intflags().read();
+        match event {
+            Event::ResultReady => flags.resrdy().bit(),
+        }
+    }
+
+    /// Get an [`EnumSet`] of all fired interrupt events.
+    #[cfg(feature = "enumset")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "enumset")))]
+    #[inline]
+    pub fn triggered_events(&self) -> EnumSet<Event> {
+        let mut events = EnumSet::new();
+
+        for event in EnumSet::<Event>::all().iter() {
+            if self.is_event_triggered(event) {
+                events |= event;
+            }
+        }
+
+        events
+    }
+
+    /// Clear the given interrupt event flag.
+    #[inline]
+    pub fn clear_event(&mut self, event: Event) {
+        self.adc.intflags().write(|w| match event {
+            Event::ResultReady => w.resrdy().set_bit(),
+        });
+    }
+
+    /// Clear **all** interrupt events.
+    #[inline]
+    pub fn clear_events(&mut self) {
+        self.adc
+            .intflags()
+            .write(|w| w.resrdy().set_bit().wcmp().set_bit());
+    }
+
+    #[inline]
+    fn select_channel<C: AdcChannel<ADC>>(&mut self) {
+        // NOTE(unsafe): The channel implementations only use valid selections
+        self.adc
+            .muxpos()
+            .write(|w| unsafe { w.muxpos().bits(C::CHANNEL) });
+    }
+
+    /// Disables the ADC and releases the peripheral
+    ///
+    /// The [`ADCReferenceVoltage`] is returned as well if it was selected.
+    pub fn free(self) -> (ADC, Option<ADC::InternalReference>) {
+        self.adc.ctrla().modify(|_, w| w.enable().clear_bit());
+        (self.adc, self.internal_reference)
+    }
+}
+
+/// ADC instance
+pub trait Instance: Deref<Target = RegisterBlock> + crate::private::Sealed {
+    #[doc(hidden)]
+    type InternalReference;
+
+    #[doc(hidden)]
+    fn clock(clocks: &Clocks) -> Hertz;
+
+    #[doc(hidden)]
+    fn internal_reference() -> Option<ReferenceVoltage>;
+
+    #[doc(hidden)]
+    fn ptr() -> *const RegisterBlock;
+}
+
+macro_rules! adc {
+    ({
+        instance: $ADC:ident,
+        reference: ($refselreg:ident, $refselbits:ident, $refidx:literal),
+        pins: [$(
+            ($X:ident/$x:ident, $pin:literal) => $ain:literal,
+        )+],
+        temperature_sensor: $tempsense:literal,
+        internal_reference: $intref:literal,
+        ground: $gnd:literal,
+        dac: ($DAC:ty, $dac:literal),
+        event_user: $evuser:literal,
+    }) => {
+        impl Instance for crate::pac::$ADC {
+            type InternalReference = ADCReferenceVoltage<$refidx>;
+
+            fn clock(clocks: &Clocks) -> Hertz {
+                clocks.per()
+            }
+
+            fn internal_reference() -> Option<ReferenceVoltage> {
+                // NOTE(unsafe): atomic read with no side effects
+                let vref = unsafe { &*crate::pac::VREF::ptr() };
+                ReferenceVoltage::from_bits(vref.$refselreg().read().$refselbits().bits())
+            }
+
+            fn ptr() -> *const RegisterBlock {
+                crate::pac::$ADC::ptr()
+            }
+        }
+
+        impl crate::private::Sealed for crate::pac::$ADC {}
+
+        impl ReferenceSelection<crate::pac::$ADC> for ADCReferenceVoltage<$refidx> {
+            #[inline]
+            fn into_selection(self) -> (Reference, Option<ADCReferenceVoltage<$refidx>>) {
+                (Reference::Internal, Some(self))
+            }
+        }
+
+        impl AdcExt<crate::pac::$ADC> for crate::pac::$ADC {
+            fn constrain(
+                self,
+                reference: impl ReferenceSelection<crate::pac::$ADC>,
+            ) -> AdcConfigurator<crate::pac::$ADC> {
+                let (reference, internal_reference) = reference.into_selection();
+
+                AdcConfigurator {
+                    adc: self,
+                    reference,
+                    internal_reference,
+                    clock: ADC_CLOCK_MAX.Hz(),
+                    accumulation: Accumulation::None,
+                    resolution: Resolution::_10Bit,
+                    sample_length: 0,
+                    init_delay: InitDelay::None,
+                    reduced_sample_capacitance: false,
+                    run_in_standby: false,
+                    supply_voltage: None,
+                }
+            }
+        }
+
+        $(
+            paste::paste! {
+                impl AdcChannel<crate::pac::$ADC> for crate::gpio::[<port $x>]::[<P $X $pin>]<Analog> {
+                    const CHANNEL: u8 = $ain;
+                }
+            }
+        )+
+
+        impl AdcChannel<crate::pac::$ADC> for TemperatureSensor {
+            const CHANNEL: u8 = $tempsense;
+        }
+
+        impl AdcChannel<crate::pac::$ADC> for InternalReference {
+            const CHANNEL: u8 = $intref;
+        }
+
+        impl AdcChannel<crate::pac::$ADC> for Ground {
+            const CHANNEL: u8 = $gnd;
+        }
+
+        impl AdcChannel<crate::pac::$ADC> for $DAC {
+            const CHANNEL: u8 = $dac;
+        }
+
+        impl EventUser<Evsys, Async> for Adc<crate::pac::$ADC> {
+            const MULTIPLEXER_INDEX: u8 = $evuser;
+        }
+    };
+}
+
+use crate::dac::DACOutputToADC;
+use crate::evsys::Evsys;
+use crate::gpio::Analog;
+
+adc!({
+    instance: ADC0,
+    reference: (ctrla, adc0refsel, 0),
+    pins: [
+        (A/a, 0) => 0,
+        (A/a, 1) => 1,
+        (A/a, 2) => 2,
+        (A/a, 3) => 3,
+        (A/a, 4) => 4,
+        (A/a, 5) => 5,
+        (A/a, 6) => 6,
+        (A/a, 7) => 7,
+        (B/b, 5) => 8,
+        (B/b, 4) => 9,
+        (B/b, 1) => 10,
+        (B/b, 0) => 11,
+    ],
+    temperature_sensor: 0x1E,
+    internal_reference: 0x1D,
+    ground: 0x1F,
+    dac: (DACOutputToADC<0>, 0x1C),
+    event_user: 1,
+});
@@ -0,0 +1,157 @@
+//! # Capacitive touch sensing using the ADC
+//!
+//! For applications where the PTC can't be used, touch buttons can also be
+//! implemented with the ADC and an ordinary analog input pin connected to an
+//! electrode using the charge sharing method:
+//!
+//! 1. The electrode is charged to VDD by driving the pin high.
+//! 2. The sample and hold capacitor of the ADC is discharged by converting
+//!    the [`Ground`] channel.
+//! 3. The pin is released and converted. During sampling, the charge of the
+//!    electrode is shared with the sample and hold capacitor.
+//!
+//! A finger on the electrode increases its capacitance and therefore the
+//! amount of charge that is shared, which results in a higher conversion
+//! result. The [`TouchSensor`] tracks the untouched value in a baseline and
+//! reports a touch when the measurement exceeds the baseline by a threshold.
+//!
+//! ```
+//! let mut adc = dp.ADC0.constrain(Vdd).configure(clocks).unwrap();
+//! let mut button = TouchSensor::new(&mut adc, porta.pa4.into_analog_input(), 20);
+//!
+//! loop {
+//!     if button.poll(&mut adc) {
+//!         // touched
+//!     }
+//! }
+//! ```
+
+use super::{Accumulation, Adc, AdcChannel, Ground, Instance};
+use crate::gpio::{marker, Analog, Pin};
+
+/// Number of charge sharing cycles summed up per measurement by default
+const DEFAULT_SAMPLES: u8 = 4;
+
+/// Weight of a new measurement in the baseline filter as a power of two
+const BASELINE_SHIFT: u8 = 4;
+
+/// Touch sensor on an analog input pin
+///
+/// See the [module level documentation](self) for details.
+pub struct TouchSensor<ADC, Gpio, Index> {
+    pin: Pin<Gpio, Index, Analog>,
+    threshold: u16,
+    samples: u8,
+    /// Baseline scaled by `1 << BASELINE_SHIFT`
+    baseline: u32,
+    last: u16,
+    _adc: core::marker::PhantomData<ADC>,
+}
+
+impl<ADC, Gpio, Index> TouchSensor<ADC, Gpio, Index>
+where
+    ADC: Instance,
+    Gpio: marker::Gpio,
+    Index: marker::Index,
+    Pin<Gpio, Index, Analog>: AdcChannel<ADC>,
+{
+    /// Creates a new touch sensor on the passed pin and calibrates its baseline
+    ///
+    /// The electrode must not be touched while the sensor is created.
+    /// A touch is reported when a measurement exceeds the baseline by more
+    /// than `threshold`.
+    pub fn new(adc: &mut Adc<ADC>, pin: Pin<Gpio, Index, Analog>, threshold: u16) -> Self {
+        let mut sensor = Self {
+            pin,
+            threshold,
+            samples: DEFAULT_SAMPLES,
+            baseline: 0,
+            last: 0,
+            _adc: core::marker::PhantomData,
+        };
+
+        sensor.recalibrate(adc);
+        sensor
+    }
+
+    /// Set the number of charge sharing cycles summed up per measurement
+    ///
+    /// More samples reduce the noise but make the measurement slower. The
+    /// baseline is recalibrated afterwards.
+    pub fn set_samples(&mut self, adc: &mut Adc<ADC>, samples: u8) {
+        self.samples = samples.max(1);
+        self.recalibrate(adc);
+    }
+
+    /// Set the threshold above the baseline at which a touch is reported
+    #[inline]
+    pub fn set_threshold(&mut self, threshold: u16) {
+        self.threshold = threshold;
+    }
+
+    /// Perform a single measurement
+    ///
+    /// Returns the sum of the configured number of charge sharing cycles.
+    /// [Accumulation](Accumulation) of the ADC is temporarily disabled.
+    pub fn measure(&mut self, adc: &mut Adc<ADC>) -> u16 {
+        let accumulation = adc.accumulation();
+        adc.set_accumulation(Accumulation::None);
+
+        let mut sum = 0u16;
+        for _ in 0..self.samples {
+            self.pin.drive_high();
+
+            // Discharge the sample and hold capacitor
+            adc.read(&Ground);
+
+            self.pin.stop_driving();
+            sum = sum.saturating_add(adc.read(&self.pin));
+        }
+
+        adc.set_accumulation(accumulation);
+
+        self.last = sum;
+        sum
+    }
+
+    /// Perform a measurement and return whether the electrode is touched
+    ///
+    /// While the electrode is not touched, the baseline slowly follows the
+    /// measurements to compensate for drift caused by temperature and humidity.
+    pub fn poll(&mut self, adc: &mut Adc<ADC>) -> bool {
+        let value = self.measure(adc);
+        let touched = self.delta() > self.threshold;
+
+        if !touched {
+            // Exponential moving average of the untouched measurements
+            self.baseline = self.baseline - (self.baseline >> BASELINE_SHIFT) + value as u32;
+        }
+
+        touched
+    }
+
+    /// Re-initialize the baseline from a fresh measurement
+    ///
+    /// The electrode must not be touched during the recalibration.
+    pub fn recalibrate(&mut self, adc: &mut Adc<ADC>) {
+        let value = self.measure(adc);
+        self.baseline = (value as u32) << BASELINE_SHIFT;
+    }
+
+    /// Returns the current baseline
+    #[inline]
+    pub fn baseline(&self) -> u16 {
+        (self.baseline >> BASELINE_SHIFT) as u16
+    }
+
+    /// Returns by how much the last measurement exceeded the baseline
+    #[inline]
+    pub fn delta(&self) -> u16 {
+        self.last.saturating_sub(self.baseline())
+    }
+
+    /// Releases the pin
+    pub fn free(self) -> Pin<Gpio, Index, Analog> {
+        self.pin
+    }
+}
@@ -0,0 +1,13 @@
+//! # Async support shared between the drivers
+
+use core::cell::RefCell;
+use core::task::Waker;
+
+use avr_device::interrupt::Mutex;
+
+/// Storage for the waker of the task waiting on a peripheral instance
+///
+/// Every instance of a peripheral with an async implementation owns one of
+/// these, which is filled by the waiting task and emptied by the interrupt
+/// handler.
+pub type WakerSlot = Mutex<RefCell<Option<Waker>>>;
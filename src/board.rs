@@ -0,0 +1,206 @@
+//! # Board setup
+//!
+//! Almost every application starts by taking the device peripherals,
+//! constraining the clock controller and port multiplexer, freezing the
+//! clocks and splitting the GPIO ports before the actual peripherals can be
+//! created. The [`board!`](crate::board!) macro generates a struct holding
+//! all the configured drivers of a board together with the code that performs
+//! this setup in a deterministic order.
+//!
+//! Pins are moved out of the split ports when they are assigned to a field.
+//! Assigning the same pin twice, or to two peripherals at once, is therefore a
+//! "use of moved value" compile error. The same goes for the peripherals
+//! taken from the device peripherals.
+//!
+//! ```
+//! use atxtiny_hal::gpio::{porta::*, portb::*, Input, Output, Stateful, Stateless};
+//! use atxtiny_hal::pac;
+//! use atxtiny_hal::serial::{Serial, UartPinset};
+//!
+//! atxtiny_hal::board! {
+//!     /// All the hardware of my board
+//!     pub struct Board {
+//!         // Name the bindings available to the field initializers below
+//!         use(dp, clocks, portmux, a, b, c);
+//!
+//!         // Configure and freeze the clock controller
+//!         clocks = |clkctrl| clkctrl.per_clk_freq(10.MHz()).freeze();
+//!
+//!         pub led: PB6<Output<Stateful>> = b.pb6.into_push_pull_output(),
+//!         pub button: PB7<Input> = b.pb7.into_pull_up_input(),
+//!         pub serial: Serial<pac::USART0, UartPinset<pac::USART0, PA2<Input>, PA1<Output<Stateless>>>> =
+//!             Serial::new(
+//!                 dp.USART0,
+//!                 (a.pa2.into_peripheral::<pac::USART0>(), a.pa1.into_peripheral::<pac::USART0>()).mux(&portmux),
+//!                 115200u32.bps(),
+//!                 clocks
+//!             ),
+//!     }
+//! }
+//!
+//! let board = Board::take().unwrap();
+//! ```
+//!
+//! The field initializers are evaluated in the order they are declared. The
+//! [`prelude`](crate::prelude) is in scope inside of them.
+//! The resulting struct always contains a `clocks` field with the frozen
+//! [`Clocks`](crate::clkctrl::Clocks) in addition to the declared fields.
+//!
+//! Board support crates, which only describe the pin assignment of a board,
+//! can use the [`pins!`](crate::pins!) macro instead.
+
+/// Declares a board struct and generates its setup code.
+///
+/// See the [module level documentation](crate::board) for details.
+#[macro_export]
+macro_rules! board {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident {
+            use($dp:ident, $clocks:ident, $portmux:ident, $porta:ident, $portb:ident, $portc:ident);
+            clocks = |$clkctrl:ident| $clkcfg:expr;
+            $(
+                $(#[$fmeta:meta])*
+                $fvis:vis $field:ident : $ty:ty = $init:expr
+            ),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis struct $name {
+            /// The frozen clock configuration of the board
+            pub clocks: $crate::clkctrl::Clocks,
+            $(
+                $(#[$fmeta])*
+                $fvis $field: $ty,
+            )*
+        }
+
+        impl $name {
+            /// Takes the device peripherals and sets up the board.
+            ///
+            /// Returns `None` if the device peripherals have already been taken.
+            #[allow(dead_code)]
+            pub fn take() -> Option<Self> {
+                $crate::pac::Peripherals::take().map(Self::setup)
+            }
+
+            /// Sets up the board from the passed device peripherals.
+            #[allow(unused_variables, unused_mut, unused_imports)]
+            pub fn setup(dp: $crate::pac::Peripherals) -> Self {
+                use $crate::prelude::*;
+                use $crate::pac;
+
+                let mut $dp = dp;
+
+                let $portmux = $dp.PORTMUX.constrain();
+                let $clocks = {
+                    let $clkctrl = $dp.CLKCTRL.constrain();
+                    $clkcfg
+                };
+
+                let mut $porta = $dp.PORTA.split();
+                let mut $portb = $dp.PORTB.split();
+                let mut $portc = $dp.PORTC.split();
+
+                $(
+                    let $field: $ty = $init;
+                )*
+
+                Self {
+                    clocks: $clocks,
+                    $($field,)*
+                }
+            }
+        }
+    };
+}
+
+/// Declares a struct with the configured pins of a board.
+///
+/// Each field names a pin of the device and optionally the mode it is
+/// converted into. Pins without a mode stay floating inputs, which is their
+/// reset state. Board support crates can use this to describe the pin
+/// assignment of a board in one place:
+///
+/// ```
+/// atxtiny_hal::pins! {
+///     /// Pins of my board
+///     pub struct Pins {
+///         /// Status LED
+///         pub led: PB6 => push_pull_output,
+///         pub button: PB7 => pull_up_input,
+///         pub strobe: PA4 => stateless_push_pull_output,
+///         pub sense: PA5 => analog,
+///         /// Receive and transmit lines of the debug serial
+///         pub rx: PB3 => peripheral(pac::USART0),
+///         pub tx: PB2 => peripheral(pac::USART0),
+///         pub spare: PC0,
+///     }
+/// }
+///
+/// let dp = pac::Peripherals::take().unwrap();
+/// let pins = Pins::new(dp.PORTA.split(), dp.PORTB.split(), dp.PORTC.split());
+/// ```
+///
+/// The available modes are `floating_input`, `pull_up_input`,
+/// `push_pull_output`, `stateless_push_pull_output`, `analog` and
+/// `peripheral(PER)`. Types in the peripheral annotation are resolved at the
+/// place the macro is invoked.
+///
+/// Assigning the same pin twice is a "use of moved value" compile error. The
+/// generated struct can be used as a field of a [`board!`](crate::board!).
+#[macro_export]
+macro_rules! pins {
+    (@mode) => { $crate::gpio::Input };
+    (@mode floating_input) => { $crate::gpio::Input };
+    (@mode pull_up_input) => { $crate::gpio::Input };
+    (@mode push_pull_output) => { $crate::gpio::Output<$crate::gpio::Stateful> };
+    (@mode stateless_push_pull_output) => { $crate::gpio::Output<$crate::gpio::Stateless> };
+    (@mode analog) => { $crate::gpio::Analog };
+    (@mode peripheral($per:ty)) => { $crate::gpio::Peripheral<$per> };
+
+    (@into $pin:expr) => { $pin };
+    (@into $pin:expr, floating_input) => { $pin.into_floating_input() };
+    (@into $pin:expr, pull_up_input) => { $pin.into_pull_up_input() };
+    (@into $pin:expr, push_pull_output) => { $pin.into_push_pull_output() };
+    (@into $pin:expr, stateless_push_pull_output) => { $pin.into_stateless_push_pull_output() };
+    (@into $pin:expr, analog) => { $pin.into_analog_input() };
+    (@into $pin:expr, peripheral($per:ty)) => { $pin.into_peripheral::<$per>() };
+
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident {
+            $(
+                $(#[$fmeta:meta])*
+                $fvis:vis $field:ident : $pin:ident $(=> $mode:ident $(($per:ty))?)?
+            ),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis struct $name {
+            $(
+                $(#[$fmeta])*
+                $fvis $field: $crate::gpio::$pin<$crate::pins!(@mode $($mode $(($per))?)?)>,
+            )*
+        }
+
+        impl $name {
+            /// Configures the pins of the split ports.
+            pub fn new(
+                a: $crate::gpio::porta::Parts,
+                b: $crate::gpio::portb::Parts,
+                c: $crate::gpio::portc::Parts,
+            ) -> Self {
+                let pins = $crate::gpio::AllPins::new(a, b, c);
+
+                $crate::paste::paste! {
+                    Self {
+                        $(
+                            $field: $crate::pins!(@into pins.[<$pin:lower>] $(, $mode $(($per))?)?),
+                        )*
+                    }
+                }
+            }
+        }
+    };
+}
@@ -1,5 +1,6 @@
 //! # Configurable Custom Logic
 
+use crate::gpio::Release;
 use crate::pac::{
     ccl::lut::{lutctrla, lutctrlb, lutctrlc},
     ccl::seqctrl,
@@ -9,7 +10,12 @@ use crate::Toggle;
 
 use core::marker::PhantomData;
 
+pub mod debounce;
+pub mod ws2812;
+
 // TODO: allow config of RUNSTDBY
+// TODO: interrupts on LUT output edges (INTCTRL0/INTFLAGS) of the 2-series, needs a
+//       2-series device to be supported first, the 1-series CCL has no interrupts
 
 /// CCL Lookup table Output pin
 pub trait OutputPin<LUT>: crate::private::Sealed {}
@@ -34,9 +40,75 @@ where
         }
     }
 
+    /// Returns the pin in the mode used by the peripheral
     pub fn free(self) -> Out {
         self.out
     }
+
+    /// Releases the pin back to GPIO
+    ///
+    /// The pin is converted back into a floating input, so it can be reused
+    /// after the peripheral has been shut down.
+    pub fn release(self) -> Out::Released
+    where
+        Out: Release,
+    {
+        self.out.release()
+    }
+}
+
+/// Input pin of a LUT
+///
+/// The inputs of the LUTs are fixed to a single pin each. Only the pins
+/// routable to the input `IDX` of the LUT are accepted.
+///
+/// The pinset is selected as an input by passing it to [`Lut::inputs`],
+/// which keeps it owned by the LUT until it's returned by
+/// [`Lut::free_inputs`]:
+///
+/// ```
+/// let in0 = CclLutInputPinset::new(a.pa0.into_floating_input());
+/// let lut0 = ccl.lut0
+///     .inputs(in0, Input1::Masked, Input2::Masked)
+///     .table(TruthTable::IN0);
+/// ```
+pub struct CclLutInputPinset<LUT, In: InputPin<LUT, IDX>, const IDX: u8> {
+    _lut: PhantomData<LUT>,
+    input: In,
+}
+
+impl<LUT, In, const IDX: u8> crate::private::Sealed for CclLutInputPinset<LUT, In, IDX> where
+    In: InputPin<LUT, IDX>
+{
+}
+
+impl<LUT, In, const IDX: u8> CclLutInputPinset<LUT, In, IDX>
+where
+    In: InputPin<LUT, IDX>,
+{
+    /// Use the pin as the input `IDX` of the LUT
+    pub fn new(input: In) -> Self {
+        CclLutInputPinset {
+            _lut: PhantomData,
+            input,
+        }
+    }
+
+    /// Returns the pin in the mode used by the peripheral
+    pub fn free(self) -> In {
+        self.input
+    }
+
+    /// Releases the pin back to GPIO
+    ///
+    /// The pin is converted back into a floating input, so it can be reused
+    /// after the peripheral has been shut down.
+    pub fn release(self) -> In::Released
+    where
+        In: Release,
+    {
+        self.input.release()
+    }
 }
 
 /// Extension trait to configure a `CCL` peripheral and all containing LUTs
@@ -50,7 +122,7 @@ pub trait CclExt {
 
 /// CCL Register interface traits private to this module
 mod private {
-    use super::{ClockSource, FilterSelection, Input0, Input1, Input2, SequencerConfig, Toggle};
+    use super::{ClockSource, FilterSelection, SequencerConfig, Toggle};
 
     pub trait CclRegExt {
         fn enable(&self);
@@ -60,9 +132,9 @@ mod private {
         fn lut_edge_detection(&self, i: u8, enable: Toggle);
         fn lut_output_enable(&self, i: u8, enable: Toggle);
         fn lut_filter_selection(&self, lut_idx: u8, filter: FilterSelection);
-        fn lut_clock_source(&self, lut_idx: u8, filter: ClockSource);
+        fn lut_clock_source(&self, lut_idx: u8, clock_src: ClockSource);
         fn lut_enable(&self, lut_idx: u8, state: Toggle);
-        fn lut_inputs(&self, lut_idx: u8, input0: Input0, input1: Input1, input2: Input2);
+        fn lut_inputs(&self, lut_idx: u8, insel0: u8, insel1: u8, insel2: u8);
         fn lut_table(&self, lut_idx: u8, table: u8);
     }
 
@@ -84,6 +156,10 @@ pub mod marker {
     /// Marker trait for configured and enabled LUT
     pub trait Enabled {}
 
+    /// Marker trait for the LUT whose output is the LINK input of the LUT
+    /// with the index `Index`
+    pub trait LinkedTo<Index> {}
+
     /// Marker trait for LUT index
     pub trait Index {
         #[doc(hidden)]
@@ -124,48 +200,214 @@ impl marker::Enabled for Active {}
 impl marker::Disabled for Inactive {}
 
 /// Generic LUT
+///
+/// `Pins` are the input pinsets owned by the LUT while they are selected as
+/// its [`inputs`](Lut::inputs).
 #[derive(Debug)]
-pub struct Lut<Ccl, Index, State> {
+pub struct Lut<Ccl, Index, State, Pins = ()> {
     pub(crate) ccl: Ccl,
     pub(crate) index: Index,
+    pins: Pins,
     _state: PhantomData<State>,
 }
 
 // Make all LUT peripheral trait extensions sealable.
-impl<Ccl, Index, State> crate::private::Sealed for Lut<Ccl, Index, State> {}
+impl<Ccl, Index, State, Pins> crate::private::Sealed for Lut<Ccl, Index, State, Pins> {}
 
 /// Fully erased LUT
 ///
 /// This moves the LUT type information to be known
 /// at runtime, and erases the specific compile time type of the LUT.
 /// The only compile time information of the LUT is it's Mode.
-pub type Lutx<Ccl, State> = Lut<Ccl, Ux, State>;
+pub type Lutx<Ccl, State, Pins = ()> = Lut<Ccl, Ux, State, Pins>;
 
-impl<Ccl, State, const X: u8> Lut<Ccl, U<X>, State> {
+impl<Ccl, State, Pins, const X: u8> Lut<Ccl, U<X>, State, Pins> {
     /// Erases the pin number from the type
     ///
     /// This is useful when you want to collect the pins into an array where you
     /// need all the elements to have the same type
-    pub fn downgrade(self) -> Lut<Ccl, Ux, State> {
+    pub fn downgrade(self) -> Lut<Ccl, Ux, State, Pins> {
         Lut {
             ccl: self.ccl,
             index: Ux(X),
+            pins: self.pins,
             _state: self._state,
         }
     }
 }
 
-impl<Ccl, Index, State> Lut<Ccl, Index, State> {
-    fn into_state<NewState>(self) -> Lut<Ccl, Index, NewState> {
+impl<Ccl, Index, State, Pins> Lut<Ccl, Index, State, Pins> {
+    fn into_state<NewState>(self) -> Lut<Ccl, Index, NewState, Pins> {
         Lut {
             ccl: self.ccl,
             index: self.index,
+            pins: self.pins,
             _state: PhantomData,
         }
     }
 }
 
+impl<Ccl, Index, State, Pins> Lut<Ccl, Index, State, Pins> {
+    /// Returns the output of the LUT as the LINK input of another LUT
+    ///
+    /// The LINK input of a LUT is always the output of the next LUT, the
+    /// output of the last LUT is linked into LUT0. Only the LUT the output is
+    /// linked into accepts it as one of its [`inputs`](Lut::inputs).
+    ///
+    /// ```
+    /// // 5 input AND: LUT0 = LUT1 & PA1 & PA2, LUT1 = PC3 & PC4 & PC5
+    /// let lut1 = ccl.lut1
+    ///     .inputs(
+    ///         CclLutInputPinset::new(c.pc3.into_floating_input()),
+    ///         CclLutInputPinset::new(c.pc4.into_floating_input()),
+    ///         CclLutInputPinset::new(c.pc5.into_floating_input()),
+    ///     )
+    ///     .table(TruthTable::AND3);
+    /// let lut0 = ccl.lut0
+    ///     .inputs(
+    ///         lut1.link(),
+    ///         CclLutInputPinset::new(a.pa1.into_floating_input()),
+    ///         CclLutInputPinset::new(a.pa2.into_floating_input()),
+    ///     )
+    ///     .table(TruthTable::AND3);
+    /// ```
+    pub fn link(&self) -> Link<'_, Index> {
+        Link {
+            _source: PhantomData,
+        }
+    }
+}
+
+impl<Ccl, State, Pins, const X: u8> Lut<Ccl, U<X>, State, Pins> {
+    /// Returns the event input A of the LUT
+    ///
+    /// Used to connect an event channel, which can then be selected as an
+    /// input of the lookup table using e.g. [`Input0::EventA`].
+    pub fn event_input_a(&self) -> EventInput<U<X>, 0> {
+        EventInput { _lut: PhantomData }
+    }
+
+    /// Returns the event input B of the LUT
+    ///
+    /// Used to connect an event channel, which can then be selected as an
+    /// input of the lookup table using e.g. [`Input0::EventB`].
+    pub fn event_input_b(&self) -> EventInput<U<X>, 1> {
+        EventInput { _lut: PhantomData }
+    }
+}
+
+/// Selection of the input `IDX` of the lookup table `LUT`
+///
+/// Implemented for the [`Input0`], [`Input1`] and [`Input2`] selections of
+/// the respective input, for the [`Link`] from the LUT whose output is
+/// linked into `LUT` and for the [`CclLutInputPinset`] of the I/O pin of the
+/// input.
+pub trait LutInput<LUT, const IDX: u8>: crate::private::Sealed {
+    /// The part of the selection owned by the LUT while it's selected
+    type Owned;
+
+    #[doc(hidden)]
+    fn insel(&self) -> u8;
+
+    #[doc(hidden)]
+    fn into_owned(self) -> Self::Owned;
+}
+
+impl<LUT, In, const IDX: u8> LutInput<LUT, IDX> for CclLutInputPinset<LUT, In, IDX>
+where
+    In: InputPin<LUT, IDX>,
+{
+    type Owned = Self;
+
+    #[inline(always)]
+    fn insel(&self) -> u8 {
+        // All three inputs share the encoding of the selections
+        lutctrlb::INSEL0_A::IO.into()
+    }
+
+    #[inline(always)]
+    fn into_owned(self) -> Self {
+        self
+    }
+}
+
+/// Output of a LUT used as the LINK input of another LUT
+///
+/// Acquired by calling [`Lut::link`] and passed to [`Lut::inputs`]. It
+/// borrows the source LUT, so the input can't be linked to a LUT which isn't
+/// owned by the caller.
+pub struct Link<'a, Index> {
+    _source: PhantomData<&'a Index>,
+}
+
+impl<Index> crate::private::Sealed for Link<'_, Index> {}
+
+impl<Ccl, SourceIndex, Index, const IDX: u8> LutInput<Lut<Ccl, Index, Inactive>, IDX>
+    for Link<'_, SourceIndex>
+where
+    SourceIndex: marker::LinkedTo<Index>,
+{
+    type Owned = ();
+
+    #[inline(always)]
+    fn insel(&self) -> u8 {
+        // All three inputs share the encoding of the selections
+        lutctrlb::INSEL0_A::LINK.into()
+    }
+
+    #[inline(always)]
+    fn into_owned(self) {}
+}
+
+/// Event input A or B of a LUT
+///
+/// Acquired by calling [`Lut::event_input_a`] or [`Lut::event_input_b`] and
+/// used to connect the input to an event channel.
+pub struct EventInput<Index, const X: u8> {
+    _lut: PhantomData<Index>,
+}
+
+impl<Index, const X: u8> crate::private::Sealed for EventInput<Index, X> {}
+
 impl<Ccl, Index> Lut<Ccl, Index, Inactive>
+where
+    Ccl: marker::Ccl,
+    Index: marker::Index,
+{
+    /// Define the three inputs into the lookup table.
+    ///
+    /// Every input is selected from the sources available to it, see
+    /// [`LutInput`]. The [`CclLutInputPinset`]s selected as inputs are owned
+    /// by the LUT until they are returned by [`Lut::free_inputs`].
+    #[inline]
+    pub fn inputs<I0, I1, I2>(
+        self,
+        input0: I0,
+        input1: I1,
+        input2: I2,
+    ) -> Lut<Ccl, Index, Inactive, (I0::Owned, I1::Owned, I2::Owned)>
+    where
+        I0: LutInput<Self, 0>,
+        I1: LutInput<Self, 1>,
+        I2: LutInput<Self, 2>,
+    {
+        let (insel0, insel1, insel2) = (input0.insel(), input1.insel(), input2.insel());
+        unsafe { (*self.ccl.ptr()).lut_inputs(self.index.index(), insel0, insel1, insel2) };
+
+        Lut {
+            ccl: self.ccl,
+            index: self.index,
+            pins: (
+                input0.into_owned(),
+                input1.into_owned(),
+                input2.into_owned(),
+            ),
+            _state: PhantomData,
+        }
+    }
+}
+
+impl<Ccl, Index, Pins> Lut<Ccl, Index, Inactive, Pins>
 where
     Ccl: marker::Ccl,
     Index: marker::Index,
@@ -175,7 +417,7 @@ where
     /// An enabled LUT cannot be reconfigured until it's disabled again using
     /// [`Lut::disable`].
     #[inline]
-    pub fn enable(self) -> Lut<Ccl, Index, Active> {
+    pub fn enable(self) -> Lut<Ccl, Index, Active, Pins> {
         unsafe { (*self.ccl.ptr()).lut_enable(self.index.index(), Toggle::On) };
         self.into_state()
     }
@@ -198,35 +440,58 @@ where
     }
 
     /// Configure the output synchronization filter.
+    ///
+    /// The synchronizer delays the output by two cycles of the LUT clock. The
+    /// filter additionally removes pulses shorter than four cycles, which
+    /// should be used when the inputs come from asynchronous pins. Both are
+    /// clocked by the [`clock_source`](Lut::clock_source) of the LUT.
     #[inline]
     pub fn filter(self, filter: FilterSelection) -> Self {
         unsafe { (*self.ccl.ptr()).lut_filter_selection(self.index.index(), filter) };
         self
     }
 
-    /// Select the clock source for the sequencer.
+    /// Select the clock of the LUT.
+    ///
+    /// The clock is used by the [`filter`](Lut::filter), the
+    /// [`edge_detection`](Lut::edge_detection) and the sequencer of the LUT.
+    /// When input 2 is used as the clock, it is not available as an input of
+    /// the lookup table anymore and should be masked.
     #[inline]
     pub fn clock_source(self, clock_src: ClockSource) -> Self {
         unsafe { (*self.ccl.ptr()).lut_clock_source(self.index.index(), clock_src) };
         self
     }
 
-    /// Define the two inputs into the lookup table.
+    /// Mask all inputs and return the input pinsets owned by the LUT.
+    ///
+    /// The LUT can then be given new [`inputs`](Lut::inputs).
     #[inline]
-    pub fn inputs(self, input0: Input0, input1: Input1, input2: Input2) -> Self {
-        unsafe { (*self.ccl.ptr()).lut_inputs(self.index.index(), input0, input1, input2) };
-        self
+    pub fn free_inputs(self) -> (Lut<Ccl, Index, Inactive>, Pins) {
+        let masked = lutctrlb::INSEL0_A::MASK.into();
+        unsafe { (*self.ccl.ptr()).lut_inputs(self.index.index(), masked, masked, masked) };
+
+        let lut = Lut {
+            ccl: self.ccl,
+            index: self.index,
+            pins: (),
+            _state: PhantomData,
+        };
+        (lut, self.pins)
     }
 
     /// Set the lookup table value.
+    ///
+    /// Either the raw `TRUTH` register value or a [`TruthTable`], e.g. built
+    /// using the [`truth_table!`](crate::truth_table) macro.
     #[inline]
-    pub fn table(self, lookup_table: u8) -> Self {
-        unsafe { (*self.ccl.ptr()).lut_table(self.index.index(), lookup_table) };
+    pub fn table(self, lookup_table: impl Into<u8>) -> Self {
+        unsafe { (*self.ccl.ptr()).lut_table(self.index.index(), lookup_table.into()) };
         self
     }
 }
 
-impl<Ccl, Index> Lut<Ccl, Index, Active>
+impl<Ccl, Index, Pins> Lut<Ccl, Index, Active, Pins>
 where
     Ccl: marker::Ccl,
     Index: marker::Index,
@@ -236,7 +501,7 @@ where
     /// A disabled LUT can be reconfigured again until it's enabled using
     /// [`Lut::enable`].
     #[inline]
-    pub fn disable(self) -> Lut<Ccl, Index, Inactive> {
+    pub fn disable(self) -> Lut<Ccl, Index, Inactive, Pins> {
         unsafe { (*self.ccl.ptr()).lut_enable(self.index.index(), Toggle::Off) };
         self.into_state()
     }
@@ -283,10 +548,10 @@ impl CclRegExt for crate::pac::ccl::RegisterBlock {
     }
 
     #[inline(always)]
-    fn lut_clock_source(&self, lut_idx: u8, filter: ClockSource) {
+    fn lut_clock_source(&self, lut_idx: u8, clock_src: ClockSource) {
         self.lut(lut_idx as usize)
             .lutctrla()
-            .modify(|_, w| w.clksrc().variant(filter.into()));
+            .modify(|_, w| w.clksrc().bit(clock_src.into()));
     }
 
     #[inline(always)]
@@ -297,16 +562,14 @@ impl CclRegExt for crate::pac::ccl::RegisterBlock {
     }
 
     #[inline(always)]
-    fn lut_inputs(&self, lut_idx: u8, input0: Input0, input1: Input1, input2: Input2) {
-        self.lut(lut_idx as usize).lutctrlb().modify(|_, w| {
-            w.insel0()
-                .variant(input0.into())
-                .insel1()
-                .variant(input1.into())
-        });
+    fn lut_inputs(&self, lut_idx: u8, insel0: u8, insel1: u8, insel2: u8) {
+        // SAFETY: the selections are converted from the INSEL enums of the PAC
+        self.lut(lut_idx as usize)
+            .lutctrlb()
+            .modify(|_, w| unsafe { w.insel0().bits(insel0).insel1().bits(insel1) });
         self.lut(lut_idx as usize)
             .lutctrlc()
-            .modify(|_, w| w.insel2().variant(input2.into()));
+            .modify(|_, w| unsafe { w.insel2().bits(insel2) });
     }
 
     #[inline(always)]
@@ -315,6 +578,138 @@ impl CclRegExt for crate::pac::ccl::RegisterBlock {
     }
 }
 
+/// Truth table of a LUT
+///
+/// Bit `n` of the table is the output of the LUT for the inputs with the
+/// values `n = IN2 << 2 | IN1 << 1 | IN0`. The tables of the single inputs
+/// can be combined with the bitwise operators to build the table of a boolean
+/// expression over the inputs:
+///
+/// ```
+/// // (IN0 & IN1) | !IN2
+/// let table = (TruthTable::IN0 & TruthTable::IN1) | !TruthTable::IN2;
+/// assert_eq!(table, truth_table!((A & B) | !C));
+/// ```
+///
+/// Masked inputs are always low.
+#[derive(ufmt::derive::uDebug, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TruthTable(u8);
+
+impl TruthTable {
+    /// The value of input 0
+    pub const IN0: Self = Self(0xAA);
+    /// The value of input 1
+    pub const IN1: Self = Self(0xCC);
+    /// The value of input 2
+    pub const IN2: Self = Self(0xF0);
+
+    /// Always low
+    pub const LOW: Self = Self(0x00);
+    /// Always high
+    pub const HIGH: Self = Self(0xFF);
+
+    /// Input 0 and input 1
+    pub const AND2: Self = Self(0x88);
+    /// Input 0 or input 1
+    pub const OR2: Self = Self(0xEE);
+    /// Input 0 xor input 1
+    pub const XOR2: Self = Self(0x66);
+
+    /// All three inputs are high
+    pub const AND3: Self = Self(0x80);
+    /// Any of the three inputs is high
+    pub const OR3: Self = Self(0xFE);
+    /// An odd number of the three inputs is high
+    pub const XOR3: Self = Self(0x96);
+
+    /// Input 2 selects input 1 if high and input 0 if low
+    pub const MUX: Self = Self(0xCA);
+
+    /// Create a truth table from the raw `TRUTH` register value
+    #[inline]
+    pub const fn from_bits(bits: u8) -> Self {
+        Self(bits)
+    }
+
+    /// Returns the raw `TRUTH` register value
+    #[inline]
+    pub const fn bits(self) -> u8 {
+        self.0
+    }
+}
+
+impl From<TruthTable> for u8 {
+    fn from(value: TruthTable) -> Self {
+        value.0
+    }
+}
+
+impl core::ops::Not for TruthTable {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        Self(!self.0)
+    }
+}
+
+impl core::ops::BitAnd for TruthTable {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self {
+        Self(self.0 & rhs.0)
+    }
+}
+
+impl core::ops::BitOr for TruthTable {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl core::ops::BitXor for TruthTable {
+    type Output = Self;
+
+    fn bitxor(self, rhs: Self) -> Self {
+        Self(self.0 ^ rhs.0)
+    }
+}
+
+/// Build a [`TruthTable`](crate::ccl::TruthTable) from a boolean expression
+///
+/// The inputs 0, 1 and 2 of the LUT are named `A`, `B` and `C`. They can be
+/// combined using `&`, `|`, `^`, `!` and parentheses. The table is computed at
+/// compile time.
+///
+/// ```
+/// let lut0 = ccl.lut0
+///     .inputs(
+///         CclLutInputPinset::new(a.pa0.into_floating_input()),
+///         CclLutInputPinset::new(a.pa1.into_floating_input()),
+///         Input2::Masked,
+///     )
+///     .table(truth_table!(A ^ B));
+///
+/// // Majority of the three inputs
+/// let majority = truth_table!((A & B) | (A & C) | (B & C));
+/// ```
+#[macro_export]
+macro_rules! truth_table {
+    ($($expr:tt)+) => {{
+        const TABLE: u8 = {
+            #[allow(non_upper_case_globals, unused)]
+            const A: u8 = $crate::ccl::TruthTable::IN0.bits();
+            #[allow(non_upper_case_globals, unused)]
+            const B: u8 = $crate::ccl::TruthTable::IN1.bits();
+            #[allow(non_upper_case_globals, unused)]
+            const C: u8 = $crate::ccl::TruthTable::IN2.bits();
+            $($expr)+
+        };
+        $crate::ccl::TruthTable::from_bits(TABLE)
+    }};
+}
+
 /// Generic main control block for a CCL
 #[derive(ufmt::derive::uDebug, Debug)]
 pub struct Control<Ccl> {
@@ -398,6 +793,7 @@ macro_rules! ccl {
                             [<lut $index>]: [<LUT $index>] {
                                 ccl: Ccl,
                                 index: U::<$index>,
+                                pins: (),
                                 _state: PhantomData,
                             },
                         )+
@@ -415,6 +811,9 @@ ccl!({
     ],
 });
 
+impl marker::LinkedTo<U<0>> for U<1> {}
+impl marker::LinkedTo<U<1>> for U<0> {}
+
 // FIXME: below structs are all device-dependent
 
 #[derive(ufmt::derive::uDebug, Debug, Clone, Copy, PartialEq, Eq)]
@@ -456,10 +855,15 @@ impl From<SequencerConfig> for seqctrl::SEQSEL_A {
     }
 }
 
+/// Filter stage at the output of a LUT
 #[derive(ufmt::derive::uDebug, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum FilterSelection {
+    /// The output of the LUT is passed through unchanged
     Disable,
+    /// The output is synchronized to the LUT clock by two flip-flops
     SynchronizerEnabled,
+    /// The output is synchronized and pulses shorter than four cycles of the
+    /// LUT clock are removed
     FilterEnabled,
 }
 
@@ -473,9 +877,12 @@ impl From<FilterSelection> for lutctrla::FILTSEL_A {
     }
 }
 
+/// Clock of a LUT
 #[derive(ufmt::derive::uDebug, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ClockSource {
+    /// CLK_PER
     PeripheralClock,
+    /// The signal selected as input 2 of the LUT
     Input2,
 }
 
@@ -488,30 +895,62 @@ impl From<ClockSource> for bool {
     }
 }
 
+/// Selection of input 0 of a LUT
+#[derive(ufmt::derive::uDebug, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Input0 {
+    /// Input is always low
     Masked,
+    /// Output of the sequencer or of the even LUT of the pair
     Feedback,
-    Link,
-    Event01,
-    Event23,
-    IoPin,
+    /// Event input A, see [`Lut::event_input_a`]
+    EventA,
+    /// Event input B, see [`Lut::event_input_b`]
+    EventB,
+    /// Output of AC0
     Ac0Out,
+    /// Waveform output of TCB0
     Tcb0Wo,
+    /// Waveform output 0 of TCA0
     Tca0Wo0,
+    /// Waveform output A of TCD0
     Tcd0Woa,
+    /// XCK of USART0
     Usart0Xck,
+    /// SCK of SPI0
     Spi0Sck,
 }
 
+#[allow(non_upper_case_globals)]
+impl Input0 {
+    /// Event input A
+    #[deprecated(note = "use `Input0::EventA` instead")]
+    pub const Event01: Self = Self::EventA;
+    /// Event input B
+    #[deprecated(note = "use `Input0::EventB` instead")]
+    pub const Event23: Self = Self::EventB;
+}
+
+impl crate::private::Sealed for Input0 {}
+
+impl<LUT> LutInput<LUT, 0> for Input0 {
+    type Owned = ();
+
+    #[inline(always)]
+    fn insel(&self) -> u8 {
+        lutctrlb::INSEL0_A::from(*self).into()
+    }
+
+    #[inline(always)]
+    fn into_owned(self) {}
+}
+
 impl From<Input0> for lutctrlb::INSEL0_A {
     fn from(input: Input0) -> Self {
         match input {
             Input0::Masked => lutctrlb::INSEL0_A::MASK,
             Input0::Feedback => lutctrlb::INSEL0_A::FEEDBACK,
-            Input0::Link => lutctrlb::INSEL0_A::LINK,
-            Input0::Event01 => lutctrlb::INSEL0_A::EVENT0,
-            Input0::Event23 => lutctrlb::INSEL0_A::EVENT1,
-            Input0::IoPin => lutctrlb::INSEL0_A::IO,
+            Input0::EventA => lutctrlb::INSEL0_A::EVENT0,
+            Input0::EventB => lutctrlb::INSEL0_A::EVENT1,
             Input0::Ac0Out => lutctrlb::INSEL0_A::AC0,
             Input0::Tcb0Wo => lutctrlb::INSEL0_A::TCB0,
             Input0::Tca0Wo0 => lutctrlb::INSEL0_A::TCA0,
@@ -522,30 +961,62 @@ impl From<Input0> for lutctrlb::INSEL0_A {
     }
 }
 
+/// Selection of input 1 of a LUT
+#[derive(ufmt::derive::uDebug, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Input1 {
+    /// Input is always low
     Masked,
+    /// Output of the sequencer or of the even LUT of the pair
     Feedback,
-    Link,
-    Event01,
-    Event23,
-    IoPin,
+    /// Event input A, see [`Lut::event_input_a`]
+    EventA,
+    /// Event input B, see [`Lut::event_input_b`]
+    EventB,
+    /// Output of AC0
     Ac0Out,
+    /// Waveform output of TCB0
     Tcb0Wo,
+    /// Waveform output 1 of TCA0
     Tca0Wo1,
+    /// Waveform output B of TCD0
     Tcd0Wob,
+    /// TXD of USART0
     Usart0Txd,
+    /// MOSI of SPI0
     Spi0Mosi,
 }
 
+#[allow(non_upper_case_globals)]
+impl Input1 {
+    /// Event input A
+    #[deprecated(note = "use `Input1::EventA` instead")]
+    pub const Event01: Self = Self::EventA;
+    /// Event input B
+    #[deprecated(note = "use `Input1::EventB` instead")]
+    pub const Event23: Self = Self::EventB;
+}
+
+impl crate::private::Sealed for Input1 {}
+
+impl<LUT> LutInput<LUT, 1> for Input1 {
+    type Owned = ();
+
+    #[inline(always)]
+    fn insel(&self) -> u8 {
+        lutctrlb::INSEL1_A::from(*self).into()
+    }
+
+    #[inline(always)]
+    fn into_owned(self) {}
+}
+
 impl From<Input1> for lutctrlb::INSEL1_A {
     fn from(input: Input1) -> Self {
         match input {
             Input1::Masked => lutctrlb::INSEL1_A::MASK,
             Input1::Feedback => lutctrlb::INSEL1_A::FEEDBACK,
-            Input1::Link => lutctrlb::INSEL1_A::LINK,
-            Input1::Event01 => lutctrlb::INSEL1_A::EVENT0,
-            Input1::Event23 => lutctrlb::INSEL1_A::EVENT1,
-            Input1::IoPin => lutctrlb::INSEL1_A::IO,
+            Input1::EventA => lutctrlb::INSEL1_A::EVENT0,
+            Input1::EventB => lutctrlb::INSEL1_A::EVENT1,
             Input1::Ac0Out => lutctrlb::INSEL1_A::AC0,
             Input1::Tcb0Wo => lutctrlb::INSEL1_A::TCB0,
             Input1::Tca0Wo1 => lutctrlb::INSEL1_A::TCA0,
@@ -556,29 +1027,60 @@ impl From<Input1> for lutctrlb::INSEL1_A {
     }
 }
 
+/// Selection of input 2 of a LUT
+#[derive(ufmt::derive::uDebug, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Input2 {
+    /// Input is always low
     Masked,
+    /// Output of the sequencer or of the even LUT of the pair
     Feedback,
-    Link,
-    Event0,
-    Event1,
-    IoPin,
+    /// Event input A, see [`Lut::event_input_a`]
+    EventA,
+    /// Event input B, see [`Lut::event_input_b`]
+    EventB,
+    /// Output of AC0
     Ac0Out,
+    /// Waveform output of TCB0
     Tcb0Wo,
+    /// Waveform output 2 of TCA0
     Tca0Wo2,
+    /// Waveform output A of TCD0
     Tcd0Woa,
+    /// MISO of SPI0
     Spi0Miso,
 }
 
+#[allow(non_upper_case_globals)]
+impl Input2 {
+    /// Event input A
+    #[deprecated(note = "use `Input2::EventA` instead")]
+    pub const Event0: Self = Self::EventA;
+    /// Event input B
+    #[deprecated(note = "use `Input2::EventB` instead")]
+    pub const Event1: Self = Self::EventB;
+}
+
+impl crate::private::Sealed for Input2 {}
+
+impl<LUT> LutInput<LUT, 2> for Input2 {
+    type Owned = ();
+
+    #[inline(always)]
+    fn insel(&self) -> u8 {
+        lutctrlc::INSEL2_A::from(*self).into()
+    }
+
+    #[inline(always)]
+    fn into_owned(self) {}
+}
+
 impl From<Input2> for lutctrlc::INSEL2_A {
     fn from(input: Input2) -> Self {
         match input {
             Input2::Masked => lutctrlc::INSEL2_A::MASK,
             Input2::Feedback => lutctrlc::INSEL2_A::FEEDBACK,
-            Input2::Link => lutctrlc::INSEL2_A::LINK,
-            Input2::Event0 => lutctrlc::INSEL2_A::EVENT0,
-            Input2::Event1 => lutctrlc::INSEL2_A::EVENT1,
-            Input2::IoPin => lutctrlc::INSEL2_A::IO,
+            Input2::EventA => lutctrlc::INSEL2_A::EVENT0,
+            Input2::EventB => lutctrlc::INSEL2_A::EVENT1,
             Input2::Ac0Out => lutctrlc::INSEL2_A::AC0,
             Input2::Tcb0Wo => lutctrlc::INSEL2_A::TCB0,
             Input2::Tca0Wo2 => lutctrlc::INSEL2_A::TCA0,
@@ -588,7 +1090,6 @@ impl From<Input2> for lutctrlc::INSEL2_A {
     }
 }
 
-// TODO: I didn't manage yet to add pins to the LUT state so far
 // TODO: macros
 use crate::gpio::{Input, Output, Stateless};
 
@@ -609,8 +1110,8 @@ impl InputPin<LUT1, 2> for crate::gpio::portc::PC5<Input> {}
 use crate::evsys::ChannelConfigurator;
 use crate::evsys::{Channel, EventGenerator, GeneratorAssigned, Unconfigured};
 
-impl<Evsys, Index, CCL, State, const X: u8> EventGenerator<Evsys, crate::evsys::Async, Index>
-    for Lut<CCL, U<X>, State>
+impl<Evsys, Index, CCL, State, Pins, const X: u8> EventGenerator<Evsys, crate::evsys::Async, Index>
+    for Lut<CCL, U<X>, State, Pins>
 where
     Evsys: crate::evsys::marker::Evsys,
     Index: crate::evsys::marker::Index,
@@ -626,3 +1127,21 @@ where
         channel.into_state()
     }
 }
+
+use crate::evsys::{Async, EventUser, Evsys};
+
+impl EventUser<Evsys, Async> for EventInput<U<0>, 0> {
+    const MULTIPLEXER_INDEX: u8 = 2;
+}
+
+impl EventUser<Evsys, Async> for EventInput<U<1>, 0> {
+    const MULTIPLEXER_INDEX: u8 = 3;
+}
+
+impl EventUser<Evsys, Async> for EventInput<U<0>, 1> {
+    const MULTIPLEXER_INDEX: u8 = 4;
+}
+
+impl EventUser<Evsys, Async> for EventInput<U<1>, 1> {
+    const MULTIPLEXER_INDEX: u8 = 5;
+}
@@ -0,0 +1,119 @@
+//! # Hardware debouncing
+//!
+//! The filter of a LUT removes pulses shorter than four cycles of the LUT
+//! clock. Clocking the LUT from a slow signal on its input 2 turns this into
+//! a debouncer for buttons, without spending any CPU time on it: with a 1kHz
+//! clock, the output only changes after the input has been stable for 4ms.
+//!
+//! ```
+//! // 1kHz clock for the LUT from the compare channel 2 of TCA0
+//! let mut pwm = Timer::new(dp.TCA0, clocks)
+//!     .pwm_hz(pwm_pins, 1.kHz(), WaveformGenerationMode::SingleSlope)
+//!     .unwrap();
+//! pwm.set_duty(Channel::C3, pwm.get_max_duty() / 2);
+//! pwm.enable(Channel::C3);
+//!
+//! let button = CclLutInputPinset::new(a.pa0.into_pull_up_input());
+//! let mut button = ccl.lut0.into_debouncer(&ccl.control, button, Input2::Tca0Wo2);
+//!
+//! // The debounced signal can be used as an event generator
+//! let _channel = evsys.channel_async0.route(&mut button, ()).to(&evout0).apply();
+//! ```
+//!
+//! Any other slow signal selectable as [`Input2`] can be used as the clock,
+//! e.g. an event of the PIT routed to the event input A of the LUT. To use
+//! the debounced signal as a pin interrupt source, enable the
+//! [`output`](Lut::output_enable) of the LUT before converting it and
+//! configure the interrupt on its output pin.
+
+use super::{
+    marker, Active, CclLutInputPinset, ClockSource, Control, FilterSelection, Inactive, Input1,
+    Input2, InputPin, Lut, U,
+};
+use crate::evsys::{
+    marker as evsys_marker, Async, Channel, EventGenerator, GeneratorAssigned, Unconfigured,
+};
+
+/// LUT debouncing its input pin
+///
+/// See the [module level documentation](self) for details.
+pub struct Debouncer<Ccl, In, const X: u8>
+where
+    In: InputPin<Lut<Ccl, U<X>, Inactive>, 0>,
+{
+    lut: Lut<Ccl, U<X>, Active, (CclLutInputPinset<Lut<Ccl, U<X>, Inactive>, In, 0>, (), ())>,
+}
+
+impl<Ccl, const X: u8> Lut<Ccl, U<X>, Inactive>
+where
+    Ccl: marker::Ccl,
+{
+    /// Configures the LUT to output its input pin 0 debounced by the filter
+    ///
+    /// The LUT is clocked by the `clock` selected as its input 2. The CCL is
+    /// disabled while configuring the LUT and enabled again afterwards.
+    pub fn into_debouncer<In>(
+        self,
+        control: &Control<Ccl>,
+        input: CclLutInputPinset<Self, In, 0>,
+        clock: Input2,
+    ) -> Debouncer<Ccl, In, X>
+    where
+        In: InputPin<Self, 0>,
+    {
+        control.disable();
+
+        let lut = self
+            .inputs(input, Input1::Masked, clock)
+            .table(super::TruthTable::IN0)
+            .clock_source(ClockSource::Input2)
+            .filter(FilterSelection::FilterEnabled)
+            .enable();
+
+        control.enable();
+
+        Debouncer { lut }
+    }
+}
+
+impl<Ccl, In, const X: u8> Debouncer<Ccl, In, X>
+where
+    Ccl: marker::Ccl,
+    In: InputPin<Lut<Ccl, U<X>, Inactive>, 0>,
+{
+    /// Disables the LUT and returns it together with the input pin
+    ///
+    /// The CCL is disabled to be able to disable the LUT and has to be
+    /// enabled again by the caller if it is still in use.
+    pub fn free(
+        self,
+        control: &Control<Ccl>,
+    ) -> (
+        Lut<Ccl, U<X>, Inactive>,
+        CclLutInputPinset<Lut<Ccl, U<X>, Inactive>, In, 0>,
+    ) {
+        control.disable();
+
+        let (lut, (input, _, _)) = self.lut.disable().free_inputs();
+        (lut, input)
+    }
+}
+
+/// The debounced signal is the event of the LUT
+impl<Evsys, Index, Ccl, In, const X: u8> EventGenerator<Evsys, Async, Index>
+    for Debouncer<Ccl, In, X>
+where
+    Evsys: evsys_marker::Evsys,
+    Index: evsys_marker::Index,
+    In: InputPin<Lut<Ccl, U<X>, Inactive>, 0>,
+{
+    type EventSource = ();
+
+    fn connect_event_generator(
+        &mut self,
+        channel: Channel<Evsys, Async, Index, Unconfigured>,
+        source: (),
+    ) -> Channel<Evsys, Async, Index, GeneratorAssigned> {
+        self.lut.connect_event_generator(channel, source)
+    }
+}
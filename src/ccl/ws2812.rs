@@ -0,0 +1,182 @@
+//! # WS2812 driver
+//!
+//! Addressable LEDs like the WS2812 (NeoPixel) encode each bit in the length
+//! of a high pulse: a short pulse is a zero, a long pulse is a one. Instead
+//! of bit-banging the waveform with exact cycle counts, it is generated in
+//! hardware from the signals of SPI0 by the two LUTs of the CCL:
+//!
+//! * LUT1 outputs SCK delayed by its filter by a few cycles of CLK_PER.
+//! * LUT0 outputs `SCK & (MOSI | !delayed SCK)` on its output pin.
+//!
+//! With the SPI in mode 0, every bit starts with SCK going high. LUT0 outputs
+//! a short pulse until the delayed SCK follows for a zero and stays high for
+//! the whole high phase of SCK for a one.
+//!
+//! ```
+//! let spi_pins = SpiPinset::new(sck, miso, mosi);
+//! let spi = Spi::new_buffered(dp.SPI0, spi_pins, 625_000u32.Hz(), clocks);
+//!
+//! let ccl = dp.CCL.split();
+//! let out = a.pa4.into_stateless_push_pull_output().mux(&portmux);
+//! let mut leds = Ws2812::new(spi, &ccl.control, ccl.lut0, ccl.lut1, out, clocks);
+//!
+//! leds.write_pixels(&[Rgb8::new(255, 0, 0), Rgb8::new(0, 0, 255)]).unwrap();
+//! ```
+//!
+//! The high time of a one is half of the SPI period, so the SPI clock has to
+//! be between 500kHz and 860kHz. The high time of a zero is the delay of the
+//! filter of four CLK_PER cycles, which has to be between 220ns and 380ns.
+//! This requires a CLK_PER between 10.6MHz and 18MHz, e.g. the 16MHz
+//! oscillator without prescaler, which results in a 250ns zero pulse and a
+//! 500kHz SPI clock.
+//!
+//! The pins of the SPI keep toggling while pixels are written, so they can't
+//! be used for anything else.
+
+use crate::clkctrl::Clocks;
+use crate::embedded_hal::spi::SpiBus;
+use crate::pac::SPI0;
+use crate::spi::{Buffered, Error, MisoPin, MosiPin, OptionalSs, SckPin, Spi, SpiPinset};
+use crate::time::*;
+use crate::{truth_table, Toggle};
+
+use super::{
+    Active, Ccl, CclLutOutputPinset, ClockSource, Control, FilterSelection, Input0, Input1, Input2,
+    Lut, OutputPin, LUT0, LUT1, U,
+};
+
+/// Color of a single LED
+#[derive(ufmt::derive::uDebug, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Rgb8 {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Rgb8 {
+    /// Create a new color
+    pub const fn new(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b }
+    }
+}
+
+/// WS2812 driver
+///
+/// See the [module level documentation](self) for details.
+pub struct Ws2812<SCK, MISO, MOSI, SS, Out>
+where
+    SCK: SckPin<SPI0>,
+    MISO: MisoPin<SPI0>,
+    MOSI: MosiPin<SPI0>,
+    SS: OptionalSs<SPI0>,
+    Out: OutputPin<LUT0>,
+{
+    spi: Spi<SPI0, Buffered, SpiPinset<SPI0, SCK, MISO, MOSI, SS>>,
+    lut0: Lut<Ccl, U<0>, Active, ((), (), ())>,
+    lut1: Lut<Ccl, U<1>, Active, ((), (), ())>,
+    output: CclLutOutputPinset<LUT0, Out>,
+}
+
+impl<SCK, MISO, MOSI, SS, Out> Ws2812<SCK, MISO, MOSI, SS, Out>
+where
+    SCK: SckPin<SPI0>,
+    MISO: MisoPin<SPI0>,
+    MOSI: MosiPin<SPI0>,
+    SS: OptionalSs<SPI0>,
+    Out: OutputPin<LUT0>,
+{
+    /// Configures the SPI and both LUTs of the CCL to generate the waveform
+    ///
+    /// The SPI is reconfigured to mode 0 at about 625kHz, which results in
+    /// 500kHz from a 16MHz peripheral clock. The CCL is disabled while
+    /// configuring the LUTs and enabled again afterwards.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the peripheral clock is not between 10.6MHz and 18MHz or if
+    /// the SPI clock can't be set to a frequency between 500kHz and 860kHz
+    /// from it.
+    pub fn new(
+        mut spi: Spi<SPI0, Buffered, SpiPinset<SPI0, SCK, MISO, MOSI, SS>>,
+        control: &Control<Ccl>,
+        lut0: LUT0,
+        lut1: LUT1,
+        output: CclLutOutputPinset<LUT0, Out>,
+        clocks: Clocks,
+    ) -> Self {
+        assert!(
+            (10_600_000..=18_000_000).contains(&clocks.per().raw()),
+            "unsupported peripheral clock"
+        );
+
+        spi.reconfigure(625_000u32.Hz(), clocks);
+        let frequency = spi.frequency(clocks).raw();
+        assert!(
+            (500_000..=860_000).contains(&frequency),
+            "unsupported SPI clock"
+        );
+
+        control.disable();
+
+        let lut1 = lut1
+            .inputs(Input0::Spi0Sck, Input1::Masked, Input2::Masked)
+            .table(truth_table!(A))
+            .clock_source(ClockSource::PeripheralClock)
+            .filter(FilterSelection::FilterEnabled);
+
+        let lut0 = lut0
+            .inputs(Input0::Spi0Sck, Input1::Spi0Mosi, lut1.link())
+            .table(truth_table!(A & (B | !C)))
+            .output_enable(Toggle::On);
+
+        let lut1 = lut1.enable();
+        let lut0 = lut0.enable();
+
+        control.enable();
+
+        Self {
+            spi,
+            lut0,
+            lut1,
+            output,
+        }
+    }
+
+    /// Send the colors to the LEDs
+    ///
+    /// The colors are sent in the GRB order expected by the LEDs. The LEDs
+    /// only show the new colors once the line stayed low for the reset time
+    /// of at least 280µs, which the caller has to wait before writing the
+    /// next pixels.
+    ///
+    /// Interrupts running for more than a few µs while the pixels are written
+    /// stretch the low time of a bit, which the LEDs might take for a reset.
+    pub fn write_pixels(&mut self, pixels: &[Rgb8]) -> Result<(), Error> {
+        for pixel in pixels {
+            self.spi.write(&[pixel.g, pixel.r, pixel.b])?;
+        }
+
+        Ok(())
+    }
+
+    /// Disables the CCL and releases the SPI, the LUTs and the output pin
+    ///
+    /// The CCL has to be enabled again by the caller if it is still in use.
+    #[allow(clippy::type_complexity)]
+    pub fn free(
+        self,
+        control: &Control<Ccl>,
+    ) -> (
+        Spi<SPI0, Buffered, SpiPinset<SPI0, SCK, MISO, MOSI, SS>>,
+        LUT0,
+        LUT1,
+        CclLutOutputPinset<LUT0, Out>,
+    ) {
+        control.disable();
+
+        let (lut0, _) = self.lut0.disable().output_enable(Toggle::Off).free_inputs();
+        let (lut1, _) = self.lut1.disable().free_inputs();
+
+        (self.spi, lut0, lut1, self.output)
+    }
+}
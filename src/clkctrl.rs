@@ -1,13 +1,19 @@
 //! # Clock Controller
 
+use crate::gpio::{
+    portb::{PB2, PB3},
+    Peripheral,
+};
 use crate::pac::{
-    clkctrl::{mclkctrla, mclkctrlb},
-    CLKCTRL,
+    clkctrl::{mclkctrla, mclkctrlb, xosc32kctrla},
+    CLKCTRL, SIGROW,
 };
 use crate::time::*;
 
 use avr_device::ccp::ProtectedWritable;
 
+pub mod measure;
+
 impl crate::private::Sealed for CLKCTRL {}
 
 // FIXME: stop using from_raw now with fugit?
@@ -69,6 +75,153 @@ fn into_pdiv(div: u32) -> Option<mclkctrlb::PDIV_A> {
     }
 }
 
+/// Prescaler dividing the main clock down to the CPU and peripheral clock
+#[derive(ufmt::derive::uDebug, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MainPrescaler {
+    /// Not divided
+    _1X,
+    /// Divided by 2
+    _2X,
+    /// Divided by 4
+    _4X,
+    /// Divided by 6
+    _6X,
+    /// Divided by 8
+    _8X,
+    /// Divided by 10
+    _10X,
+    /// Divided by 12
+    _12X,
+    /// Divided by 16
+    _16X,
+    /// Divided by 24
+    _24X,
+    /// Divided by 32
+    _32X,
+    /// Divided by 48
+    _48X,
+    /// Divided by 64
+    _64X,
+}
+
+impl MainPrescaler {
+    /// Returns the division factor of the prescaler
+    pub const fn divider(self) -> u8 {
+        match self {
+            MainPrescaler::_1X => 1,
+            MainPrescaler::_2X => 2,
+            MainPrescaler::_4X => 4,
+            MainPrescaler::_6X => 6,
+            MainPrescaler::_8X => 8,
+            MainPrescaler::_10X => 10,
+            MainPrescaler::_12X => 12,
+            MainPrescaler::_16X => 16,
+            MainPrescaler::_24X => 24,
+            MainPrescaler::_32X => 32,
+            MainPrescaler::_48X => 48,
+            MainPrescaler::_64X => 64,
+        }
+    }
+}
+
+/// Write the main prescaler and wait until the clock has switched
+fn write_main_prescaler(clkctrl: &crate::pac::clkctrl::RegisterBlock, divider: u32) {
+    if divider > 1 {
+        let pdiv = into_pdiv(divider).expect("Impossible clock divider");
+        clkctrl
+            .mclkctrlb()
+            .write_protected(|w| w.pdiv().variant(pdiv).pen().set_bit());
+    } else {
+        clkctrl.mclkctrlb().write_protected(|w| w.pen().clear_bit());
+    }
+
+    while clkctrl.mclkstatus().read().sosc().bit_is_set() {}
+}
+
+/// Start-up time of the external 32.768kHz crystal oscillator
+///
+/// The oscillator output is only used after the selected number of
+/// oscillator cycles passed after it was started.
+#[derive(ufmt::derive::uDebug, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum XOsc32KStartup {
+    /// 1k cycles
+    _1K,
+    /// 16k cycles
+    _16K,
+    /// 32k cycles
+    _32K,
+    /// 64k cycles
+    _64K,
+}
+
+impl From<XOsc32KStartup> for xosc32kctrla::CSUT_A {
+    fn from(value: XOsc32KStartup) -> Self {
+        match value {
+            XOsc32KStartup::_1K => xosc32kctrla::CSUT_A::_1K,
+            XOsc32KStartup::_16K => xosc32kctrla::CSUT_A::_16K,
+            XOsc32KStartup::_32K => xosc32kctrla::CSUT_A::_32K,
+            XOsc32KStartup::_64K => xosc32kctrla::CSUT_A::_64K,
+        }
+    }
+}
+
+/// Supply voltage the OSC20M frequency error in the signature row was measured at
+///
+/// The factory measured frequency error of the internal 16/20MHz oscillator is
+/// stored for 3V and 5V. The value closest to the actual supply voltage should
+/// be used.
+#[derive(ufmt::derive::uDebug, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OscErrorVoltage {
+    /// 3V
+    _3V,
+    /// 5V
+    _5V,
+}
+
+/// Pin set of the external 32.768kHz crystal oscillator
+///
+/// The crystal is connected to TOSC1 (PB3) and TOSC2 (PB2), which are taken
+/// over by the oscillator when it is enabled using [`ClkCtrl::xosc32k`].
+pub struct XOsc32KPinset {
+    tosc1: PB3<Peripheral<CLKCTRL>>,
+    tosc2: PB2<Peripheral<CLKCTRL>>,
+}
+
+impl XOsc32KPinset {
+    pub(crate) fn new(tosc1: PB3<Peripheral<CLKCTRL>>, tosc2: PB2<Peripheral<CLKCTRL>>) -> Self {
+        XOsc32KPinset { tosc1, tosc2 }
+    }
+
+    /// Returns the pins in the mode used by the oscillator
+    pub fn free(self) -> (PB3<Peripheral<CLKCTRL>>, PB2<Peripheral<CLKCTRL>>) {
+        (self.tosc1, self.tosc2)
+    }
+}
+
+/// Token representing the enabled external 32.768kHz crystal oscillator
+///
+/// It can only be acquired from the frozen [`Clocks`] when the crystal was
+/// enabled using [`ClkCtrl::xosc32k`] and is passed to peripherals like the
+/// RTC to use the crystal as their clock source.
+#[derive(ufmt::derive::uDebug, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct XOsc32K {
+    _private: (),
+}
+
+impl XOsc32K {
+    /// Frequency of the crystal
+    pub const FREQUENCY: u32 = 32_768;
+
+    /// Returns `true` if the crystal is running and stable
+    ///
+    /// The oscillator is only started when a peripheral requests it and its
+    /// output is available after the configured [`XOsc32KStartup`] time.
+    pub fn is_stable(&self) -> bool {
+        let clkctrl = unsafe { &*CLKCTRL::ptr() };
+        clkctrl.mclkstatus().read().xosc32ks().bit_is_set()
+    }
+}
+
 /// Clock controller abstraction
 ///
 /// This is an abstraction of the CLKCTRL peripheral used to configure the
@@ -78,6 +231,9 @@ pub struct ClkCtrl {
     main_clk_src: MainClkSrc,
     enable_clkout: bool,
     per_clk: Option<u32>,
+    main_prescaler: Option<MainPrescaler>,
+    xosc32k: Option<(XOsc32KStartup, XOsc32KPinset)>,
+    osc_error_voltage: Option<OscErrorVoltage>,
 }
 
 impl Default for ClkCtrl {
@@ -87,6 +243,9 @@ impl Default for ClkCtrl {
             main_clk_src: MainClkSrc::Osc20M,
             enable_clkout: false,
             per_clk: None,
+            main_prescaler: None,
+            xosc32k: None,
+            osc_error_voltage: None,
         }
     }
 }
@@ -127,6 +286,42 @@ impl ClkCtrl {
         self
     }
 
+    /// Enable the external 32.768kHz crystal oscillator on the TOSC1/TOSC2 pins.
+    ///
+    /// The oscillator is started when it's requested by a peripheral or
+    /// selected as the main clock source. Its output is used after the passed
+    /// start-up time. When enabled, the frozen [`Clocks`] hand out a
+    /// [`XOsc32K`] token which can be passed to the RTC.
+    ///
+    /// The pins are consumed, as the oscillator can't be disabled again after
+    /// [`ClkCtrl::freeze`].
+    ///
+    /// ```
+    /// let portb = dp.PORTB.split();
+    /// let tosc = (
+    ///     portb.pb3.into_peripheral::<pac::CLKCTRL>(),
+    ///     portb.pb2.into_peripheral::<pac::CLKCTRL>(),
+    /// )
+    ///     .mux(&portmux);
+    ///
+    /// let clocks = dp.CLKCTRL.constrain().xosc32k(XOsc32KStartup::_64K, tosc).freeze();
+    /// ```
+    pub fn xosc32k(mut self, startup: XOsc32KStartup, pins: XOsc32KPinset) -> Self {
+        self.xosc32k = Some((startup, pins));
+        self
+    }
+
+    /// Read the factory measured frequency error of the internal 16/20MHz oscillator.
+    ///
+    /// The error is taken from the signature row for the passed supply voltage
+    /// and is made available through [`Clocks::osc20m_error`] when the oscillator
+    /// is the main clock source. Peripherals like the USART can use it to
+    /// compensate their baud rate calculation.
+    pub fn osc20m_error(mut self, voltage: OscErrorVoltage) -> Self {
+        self.osc_error_voltage = Some(voltage);
+        self
+    }
+
     /// Set the desired `PER_CLK`` peripheral clock.
     ///
     /// This clock is divided down from the main clock. The prescaler also
@@ -145,16 +340,47 @@ impl ClkCtrl {
         self
     }
 
+    /// Set the main prescaler directly
+    ///
+    /// This is an alternative to [`ClkCtrl::per_clk_freq`] and takes
+    /// precedence over it, e.g. to run at 5MHz or 1.25MHz from the 20MHz
+    /// oscillator to save power. The prescaler can be changed later on when
+    /// the clocks are frozen using [`ClkCtrl::freeze_adjustable`].
+    pub fn main_prescaler(mut self, prescaler: MainPrescaler) -> Self {
+        self.main_prescaler = Some(prescaler);
+        self
+    }
+
     // FIXME: return Error for impossible dividers and clock rates?
     /// Configure the clock controller as desired.
     ///
     /// The returned [`Clocks`] struct contains the resulting clock frequencies.
     pub fn freeze(self) -> Clocks {
+        self.freeze_adjustable().clocks
+    }
+
+    /// Configure the clock controller and keep control over the main prescaler
+    ///
+    /// Like [`ClkCtrl::freeze`], but the returned [`AdjustableClocks`] allow
+    /// to change the main prescaler at runtime, e.g. to save power.
+    pub fn freeze_adjustable(self) -> AdjustableClocks {
         assert!(self.main_osc <= 20_000_000);
 
         let clkctrl = unsafe { &*CLKCTRL::ptr() };
         let clksel = into_clksel(self.main_clk_src);
 
+        // Enable the external crystal before it might be selected as the main clock
+        if let Some((startup, _)) = &self.xosc32k {
+            clkctrl.xosc32kctrla().write_protected(|w| {
+                w.sel()
+                    .clear_bit()
+                    .csut()
+                    .variant((*startup).into())
+                    .enable()
+                    .set_bit()
+            });
+        }
+
         // Wait for the selected clock to stabilize
         match clksel {
             mclkctrla::CLKSEL_A::EXTCLK => {
@@ -176,29 +402,90 @@ impl ClkCtrl {
             .mclkctrla()
             .write_protected(|w| w.clksel().variant(clksel).clkout().bit(self.enable_clkout));
 
-        // Set per_clk divider
-        let desired_per_clk = self.per_clk.unwrap_or(self.main_osc);
-        assert!(desired_per_clk <= 20_000_000);
-        let divider = self.main_osc / desired_per_clk;
-
-        if divider > 1 {
-            let pdiv = into_pdiv(divider).expect("Impossible clock divider");
-            clkctrl
-                .mclkctrlb()
-                .write_protected(|w| w.pdiv().variant(pdiv).pen().set_bit());
-        } else {
-            clkctrl.mclkctrlb().write_protected(|w| w.pen().clear_bit());
-        }
+        // Set per_clk divider and wait for the clock change to the new source
+        let divider = match self.main_prescaler {
+            Some(prescaler) => prescaler.divider() as u32,
+            None => {
+                let desired_per_clk = self.per_clk.unwrap_or(self.main_osc);
+                assert!(desired_per_clk <= 20_000_000);
+                self.main_osc / desired_per_clk
+            }
+        };
+        write_main_prescaler(clkctrl, divider);
 
-        // Wait for the clock change to the new source
-        while clkctrl.mclkstatus().read().sosc().bit_is_set() {}
+        // The error is only known for the internal oscillator
+        let osc20m_error = match (self.main_clk_src, self.osc_error_voltage) {
+            (MainClkSrc::Osc20M, Some(voltage)) => {
+                let sigrow = unsafe { &*SIGROW::ptr() };
+                let error = match (self.main_osc == 16_000_000, voltage) {
+                    (true, OscErrorVoltage::_3V) => sigrow.osc16err3v().read().bits(),
+                    (true, OscErrorVoltage::_5V) => sigrow.osc16err5v().read().bits(),
+                    (false, OscErrorVoltage::_3V) => sigrow.osc20err3v().read().bits(),
+                    (false, OscErrorVoltage::_5V) => sigrow.osc20err5v().read().bits(),
+                };
+                Some(error as i8)
+            }
+            _ => None,
+        };
 
-        Clocks {
+        let clocks = Clocks {
             main: Hertz::from_raw(self.main_osc),
             per: Hertz::from_raw(self.main_osc / divider),
             main_prescaler: divider as u8,
             bod_wdt: (32768u32 / 1024).Hz(),
-        }
+            xosc32k: self.xosc32k.map(|_| XOsc32K { _private: () }),
+            osc20m_error,
+        };
+
+        AdjustableClocks { clocks }
+    }
+}
+
+/// Frozen clock controller whose main prescaler can still be changed
+///
+/// Returned by [`ClkCtrl::freeze_adjustable`]. Unlike the [`Clocks`], this
+/// handle can't be copied, so it always knows the current clock frequencies.
+///
+/// ```
+/// let mut clkctrl = dp.CLKCTRL.constrain().freeze_adjustable();
+/// let clocks = clkctrl.set_main_prescaler(MainPrescaler::_16X);
+/// ```
+pub struct AdjustableClocks {
+    clocks: Clocks,
+}
+
+impl AdjustableClocks {
+    /// Returns the current clock frequencies
+    pub fn clocks(&self) -> Clocks {
+        self.clocks
+    }
+
+    /// Change the main prescaler
+    ///
+    /// Returns the new clock frequencies. Peripherals which derived their
+    /// settings like baud rates or timer periods from the previous [`Clocks`]
+    /// keep their register values and have to be reconfigured with the new
+    /// ones. A lower CPU clock allows a lower supply voltage, which has to be
+    /// taken into account before the prescaler is lowered again.
+    pub fn set_main_prescaler(&mut self, prescaler: MainPrescaler) -> Clocks {
+        let clkctrl = unsafe { &*CLKCTRL::ptr() };
+        write_main_prescaler(clkctrl, prescaler.divider() as u32);
+
+        self.clocks = Clocks {
+            per: Hertz::from_raw(self.clocks.main.raw() / prescaler.divider() as u32),
+            main_prescaler: prescaler.divider(),
+            ..self.clocks
+        };
+        self.clocks
+    }
+
+    /// Replace the frequency of the main clock by a measured one
+    ///
+    /// See [`Clocks::with_measured_main`]. The measured frequency is kept
+    /// when the main prescaler is changed afterwards.
+    pub fn set_measured_main(&mut self, main: Hertz) -> Clocks {
+        self.clocks = self.clocks.with_measured_main(main);
+        self.clocks
     }
 }
 
@@ -209,6 +496,8 @@ pub struct Clocks {
     per: Hertz,
     main_prescaler: u8,
     bod_wdt: Hertz,
+    xosc32k: Option<XOsc32K>,
+    osc20m_error: Option<i8>,
 }
 
 impl Clocks {
@@ -232,4 +521,48 @@ impl Clocks {
     pub fn main_prescaler(&self) -> u8 {
         self.main_prescaler
     }
+
+    /// Returns the token of the external 32.768kHz crystal oscillator
+    ///
+    /// Returns `None` if it wasn't enabled using [`ClkCtrl::xosc32k`].
+    pub fn xosc32k(&self) -> Option<XOsc32K> {
+        self.xosc32k
+    }
+
+    /// Returns the frequency error of the main oscillator in units of 1/1024
+    ///
+    /// A positive error means the oscillator runs faster than its nominal
+    /// frequency. Returns `None` if it wasn't read using [`ClkCtrl::osc20m_error`]
+    /// or the main clock isn't sourced from the internal 16/20MHz oscillator.
+    pub fn osc20m_error(&self) -> Option<i8> {
+        self.osc20m_error
+    }
+
+    /// Apply the frequency error of the main oscillator to a frequency derived from it
+    ///
+    /// The frequency is returned unchanged if the error isn't known.
+    pub fn compensate(&self, freq: Hertz) -> Hertz {
+        match self.osc20m_error {
+            Some(error) => {
+                let freq = freq.raw();
+                Hertz::from_raw(freq.saturating_add_signed((freq / 1024) as i32 * error as i32))
+            }
+            None => freq,
+        }
+    }
+
+    /// Replace the frequency of the main clock by a measured one
+    ///
+    /// The peripheral clock is derived from it using the main prescaler. As
+    /// the measured frequency already contains the error of the main
+    /// oscillator, the factory calibrated [`osc20m_error`](Clocks::osc20m_error)
+    /// is dropped. See [`measure::measure_main_clock`].
+    pub fn with_measured_main(self, main: Hertz) -> Self {
+        Self {
+            main,
+            per: Hertz::from_raw(main.raw() / self.main_prescaler as u32),
+            osc20m_error: None,
+            ..self
+        }
+    }
 }
@@ -0,0 +1,136 @@
+//! # Main oscillator measurement
+//!
+//! The internal 16/20MHz oscillator is only calibrated to a few percent at
+//! the factory, which can be too much for high UART baud rates. Its actual
+//! frequency can be measured against the 32.768kHz oscillator driving the RTC:
+//! The periodic interrupt timer of the RTC generates an event every 64 cycles
+//! of the 32.768kHz clock, which is routed through the event channel 3 to
+//! TCB0. TCB0 runs in frequency measurement mode and captures the number of
+//! CLK_PER cycles between two events.
+//!
+//! ```
+//! let clocks = dp.CLKCTRL.constrain().freeze();
+//! let mut evsys = dp.EVSYS.split();
+//!
+//! let main = measure_main_clock(&clocks, &mut dp.TCB0, &mut dp.RTC, &mut evsys.channel_async3);
+//! let clocks = clocks.with_measured_main(main);
+//!
+//! let serial = Serial::new(dp.USART0, usart_pair, 115200u32.bps(), clocks);
+//! ```
+//!
+//! The result can only be as accurate as the 32.768kHz clock. The internal
+//! OSCULP32K itself has an error of a few percent, so measuring against it
+//! mostly helps with the temperature and voltage drift of OSC20M. If the
+//! external crystal oscillator has been enabled with [`ClkCtrl::xosc32k`](super::ClkCtrl::xosc32k),
+//! it is used as the reference instead.
+
+use crate::evsys::{ChannelAsync3, ChannelConfigurator};
+use crate::pac::{EVSYS, RTC, TCB0};
+use crate::time::*;
+
+use super::Clocks;
+
+/// Index of the asynchronous channel 3
+const CHANNEL: usize = 3;
+
+/// Event generator PIT_DIV64 of the asynchronous channel 3
+const PIT_DIV64: u8 = 0x11;
+
+/// Asynchronous event user multiplexer of TCB0
+const TCB0_USER: u8 = 0;
+
+/// Number of 32.768kHz cycles between two events of PIT_DIV64
+const PIT_DIVIDER: u32 = 64;
+
+/// Number of captured periods to average
+const SAMPLES: u32 = 8;
+
+/// Measure the frequency of the main clock against the 32.768kHz clock
+///
+/// TCB0, the RTC and the event channel must not be used otherwise while
+/// measuring. Their configuration is restored afterwards, a running TCB0 or
+/// RTC is stopped for the duration of the measurement though. The
+/// measurement takes about 20ms.
+///
+/// The frequency of CLK_PER is measured and multiplied by the main prescaler
+/// to get the frequency of the main clock. Pass it to [`Clocks::with_measured_main`]
+/// to derive the peripheral clock from it.
+pub fn measure_main_clock(
+    clocks: &Clocks,
+    tcb: &mut TCB0,
+    rtc: &mut RTC,
+    channel: &mut ChannelAsync3,
+) -> Hertz {
+    // Save everything we touch
+    let tcb_ctrla = tcb.ctrla().read().bits();
+    let tcb_ctrlb = tcb.ctrlb().read().bits();
+    let tcb_evctrl = tcb.evctrl().read().bits();
+    let tcb_ccmp = tcb.ccmp().read().bits();
+    let rtc_ctrla = rtc.ctrla().read().bits();
+    let rtc_clksel = rtc.clksel().read().bits();
+    let rtc_pitctrla = rtc.pitctrla().read().bits();
+    // NOTE(unsafe) only the registers of the channel and of the TCB0 user
+    // are accessed, which are lent to the measurement by the caller
+    let evsys = unsafe { &*EVSYS::ptr() };
+    let generator = evsys.asyncch(CHANNEL).read().bits();
+    let tcb_user = evsys.asyncuser(TCB0_USER as usize).read().bits();
+
+    // CLKSEL must only be changed while the RTC is disabled
+    while rtc.status().read().ctrlabusy().bit_is_set() {}
+    rtc.ctrla().modify(|_, w| w.rtcen().clear_bit());
+    while rtc.status().read().ctrlabusy().bit_is_set() {}
+
+    // Let the PIT generate events from the 32.768kHz clock
+    rtc.pitctrla().write(|w| w.piten().clear_bit());
+    while rtc.pitstatus().read().ctrlbusy().bit_is_set() {}
+    match clocks.xosc32k() {
+        Some(_) => rtc.clksel().write(|w| w.clksel().tosc32k()),
+        None => rtc.clksel().write(|w| w.clksel().int32k()),
+    }
+    rtc.pitctrla().write(|w| w.period().off().piten().set_bit());
+    while rtc.pitstatus().read().ctrlbusy().bit_is_set() {}
+
+    channel.set_generator(PIT_DIV64);
+    channel.set_multiplexer(TCB0_USER);
+
+    // Capture the number of CLK_PER cycles between two events
+    tcb.ctrla().write(|w| w.enable().clear_bit());
+    tcb.ctrlb().write(|w| w.cntmode().frq());
+    tcb.evctrl().write(|w| w.captei().set_bit());
+    tcb.intflags().write(|w| w.capt().set_bit());
+    tcb.ctrla()
+        .write(|w| w.clksel().clkdiv1().enable().set_bit());
+
+    let capture = || {
+        while tcb.intflags().read().capt().bit_is_clear() {}
+        // Reading the capture clears the flag
+        tcb.ccmp().read().bits() as u32
+    };
+
+    // The counter wasn't reset before the first event
+    capture();
+    let cycles: u32 = (0..SAMPLES).map(|_| capture()).sum();
+
+    // Restore the previous configuration
+    tcb.ctrla().write(|w| w.enable().clear_bit());
+    tcb.ctrlb().write(|w| unsafe { w.bits(tcb_ctrlb) });
+    tcb.evctrl().write(|w| unsafe { w.bits(tcb_evctrl) });
+    tcb.ccmp().write(|w| w.bits(tcb_ccmp));
+    tcb.intflags().write(|w| w.capt().set_bit());
+    tcb.ctrla().write(|w| unsafe { w.bits(tcb_ctrla) });
+
+    channel.set_generator(generator);
+    evsys
+        .asyncuser(TCB0_USER as usize)
+        .write(|w| unsafe { w.bits(tcb_user) });
+
+    rtc.pitctrla().write(|w| w.piten().clear_bit());
+    while rtc.pitstatus().read().ctrlbusy().bit_is_set() {}
+    rtc.clksel().write(|w| unsafe { w.bits(rtc_clksel) });
+    rtc.pitctrla().write(|w| unsafe { w.bits(rtc_pitctrla) });
+    while rtc.status().read().ctrlabusy().bit_is_set() {}
+    rtc.ctrla().write(|w| unsafe { w.bits(rtc_ctrla) });
+
+    let per = cycles * (32768 / PIT_DIVIDER) / SAMPLES;
+    Hertz::from_raw(per * clocks.main_prescaler() as u32)
+}
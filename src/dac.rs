@@ -1,24 +1,66 @@
 //! # Digital to Analog Converter
+//!
+//! The DAC converts an 8-bit value into a fraction of the reference voltage
+//! selected for it in the [`VREF`](crate::vref::Vref) peripheral, which is
+//! why a [`DACReferenceVoltage`] is needed to constrain it. The reference is
+//! enabled automatically while the DAC is enabled. The reference of DAC0 is
+//! shared with AC0, so changing it for one of them also changes it for the
+//! other.
+//!
+//! Only DAC0 is supported. The internal-only DAC1 and DAC2 of the 1-series
+//! parts with 16kB and 32kB of flash are not, as the ATtiny817 is the only
+//! supported device.
+//!
+//! ```
+//! let mut vref = dp.VREF.split();
+//! let dacout = IntoMuxedPinset::<pac::DAC0>::mux(a.pa6.into_stateless_push_pull_output(), &portmux);
+//!
+//! let dac_ref = vref.dac0.with_voltage(&mut vref.vref, ReferenceVoltage::_2V50);
+//! let mut dac = dp.DAC0.constrain(dac_ref);
+//! dac.dac_set_value(128);
+//! dac.output_pin(&dacout);
+//! let dac = dac.enable();
+//! ```
 
-// TODO: macros for different CPUs which have different peripherals
 // FIXME: RUNSTDBY
 
 use core::marker::PhantomData;
 
+use crate::gpio::{Output, Release, Stateless};
 use crate::pac::DAC0;
-use crate::vref::DACReferenceVoltage;
+use crate::vref::{DACReferenceVoltage, ReferenceVoltage};
+
+pub mod waveform;
 
 /// DAC Register interface traits private to this module
 mod private {
     pub trait DacRegExt {
+        type Reference;
+        type AcInput;
+        type AdcInput;
+
+        fn ac_input() -> Self::AcInput;
+        fn adc_input() -> Self::AdcInput;
+
         fn enable(&self, enable: bool);
         fn enable_output(&self, enable: bool);
         fn set_value(&self, value: u8);
+        fn reference(&self) -> Option<crate::vref::ReferenceVoltage>;
     }
 }
 
 use private::DacRegExt;
 
+/// DAC error
+#[derive(ufmt::derive::uDebug, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The requested voltage is above the highest output voltage of the DAC
+    /// with the selected reference
+    OutOfRange,
+    /// The reference voltage selected in VREF is reserved
+    UnknownReference,
+}
+
 /// Enabled DAC (type state)
 pub struct Enabled;
 
@@ -33,7 +75,7 @@ impl ED for Enabled {}
 impl ED for Disabled {}
 impl ED for LockedEnabled {}
 
-/// Extension trait that constrains the [`DAC0`] peripheral
+/// Extension trait that constrains the DAC peripherals
 pub trait DacExt<INST: DacRegExt, const IDX: u8> {
     /// Constrains the [`DAC0`] peripheral.
     ///
@@ -54,23 +96,18 @@ pub trait DacExt<INST: DacRegExt, const IDX: u8> {
 /// let dp = pac::Peripherals::take().unwrap();
 /// let dac = dp.DAC.constrain();
 /// ```
-pub struct Dac<INST, ED> {
+pub struct Dac<INST: DacRegExt, ED> {
     dac: INST,
-    _ref: DACReferenceVoltage<0>,
+    _ref: INST::Reference,
     _enabled: PhantomData<ED>,
 }
 
-impl DacExt<DAC0, 0> for DAC0 {
-    fn constrain(self, ref_voltage: DACReferenceVoltage<0>) -> Dac<Self, Disabled> {
-        Dac {
-            dac: self,
-            _ref: ref_voltage,
-            _enabled: PhantomData,
-        }
+impl<INST: DacRegExt> Dac<INST, Disabled> {
+    /// Releases the peripheral and its reference token
+    pub fn free(self) -> (INST, INST::Reference) {
+        (self.dac, self._ref)
     }
-}
 
-impl<INST: DacRegExt> Dac<INST, Disabled> {
     /// Enable the DAC
     pub fn enable(self) -> Dac<INST, Enabled> {
         self.dac.enable(true);
@@ -82,13 +119,22 @@ impl<INST: DacRegExt> Dac<INST, Disabled> {
         }
     }
 
-    /// Assign a DAC output pin
+    /// Enable the DAC as an internal level source only
     ///
-    /// The DAC can be passed to the Analog Comparator, so a physical output pin
-    /// is not always needed
-    #[inline]
-    pub fn output_pin<P: DACOutputPin>(&mut self, _pin: P) {
-        self.dac.enable_output(true);
+    /// The output buffer is disabled, so the output pin stays free for other
+    /// uses and the DAC only feeds AC0 and the ADC through the tokens returned
+    /// by [`dac_get_ac_input`](Dac::dac_get_ac_input) and
+    /// [`dac_get_adc_input`](Dac::dac_get_adc_input). The DAC is locked
+    /// enabled right away, as those peripherals depend on it.
+    ///
+    /// ```
+    /// let dac_ref = vref.dac0.with_voltage(&mut vref.vref, ReferenceVoltage::_1V10);
+    /// let dac = dp.DAC0.constrain(dac_ref).enable_internal();
+    /// let ac = dp.AC0.comparator(ainp0, dac.dac_get_ac_input(), Config::default());
+    /// ```
+    pub fn enable_internal(mut self) -> Dac<INST, LockedEnabled> {
+        self.disable_output_pin();
+        self.enable().lock_enable()
     }
 
     /// Set the current DAC output value
@@ -104,6 +150,46 @@ impl<INST: DacRegExt> Dac<INST, Disabled> {
     }
 }
 
+impl<INST: DacRegExt, ED> Dac<INST, ED> {
+    /// Drive the output pin of the `pinset` with the DAC output
+    ///
+    /// The DAC can be passed to the Analog Comparator or the ADC, so a physical
+    /// output pin is not always needed. The output buffer is only active while
+    /// the DAC is enabled.
+    #[inline]
+    pub fn output_pin<P: DACOutputPin<INST>>(&mut self, _pinset: &DacOutputPinset<INST, P>) {
+        self.dac.enable_output(true);
+    }
+
+    /// Stop driving the output pin with the DAC output
+    #[inline]
+    pub fn disable_output_pin(&mut self) {
+        self.dac.enable_output(false);
+    }
+
+    /// Returns the voltage of the reference currently selected in the
+    /// [`VREF`](crate::vref::Vref) peripheral in millivolts
+    pub fn reference_millivolts(&self) -> Option<u16> {
+        self.dac.reference().map(|v| v.millivolts())
+    }
+
+    /// Set the DAC output to the voltage closest to `millivolts`
+    ///
+    /// The output voltage is `value / 256` times the reference voltage, so the
+    /// highest voltage is slightly below the reference. The reference is read
+    /// back from the [`VREF`](crate::vref::Vref) peripheral on every call, so
+    /// changing it later is taken into account. The output is left unchanged
+    /// if an error is returned.
+    pub fn set_output_mv(&mut self, millivolts: u16) -> Result<(), Error> {
+        let reference = self.reference_millivolts().ok_or(Error::UnknownReference)? as u32;
+        let value = (millivolts as u32 * 256 + reference / 2) / reference;
+        let value: u8 = value.try_into().map_err(|_| Error::OutOfRange)?;
+
+        self.dac.set_value(value);
+        Ok(())
+    }
+}
+
 impl<INST: DacRegExt> Dac<INST, Enabled> {
     /// Disable the DAC
     pub fn disable(self) -> Dac<INST, Disabled> {
@@ -149,38 +235,146 @@ impl<INST: DacRegExt> Dac<INST, LockedEnabled> {
 }
 
 /// Marker trait for a pin that can be used as a DAC output
-pub trait DACOutputPin: crate::private::Sealed {}
+pub trait DACOutputPin<DAC>: crate::private::Sealed {}
+
+/// Pin set for the port multiplexer
+///
+/// The output pin of the DAC is fixed. Its pull-up should be disabled, as it
+/// would load the output.
+///
+/// ```
+/// let mut dacout = a.pa6.into_stateless_push_pull_output();
+/// dacout.internal_pull_up(Toggle::Off);
+/// let dacout = IntoMuxedPinset::<pac::DAC0>::mux(dacout, &portmux);
+///
+/// let dac_ref = vref.dac0.with_voltage(&mut vref.vref, ReferenceVoltage::_2V50);
+/// let mut dac = dp.DAC0.constrain(dac_ref);
+/// dac.output_pin(&dacout);
+/// ```
+pub struct DacOutputPinset<DAC, Out: DACOutputPin<DAC>> {
+    _dac: PhantomData<DAC>,
+    out: Out,
+}
+
+impl<DAC, Out> DacOutputPinset<DAC, Out>
+where
+    Out: DACOutputPin<DAC>,
+{
+    pub(crate) fn new(out: Out) -> Self {
+        DacOutputPinset {
+            _dac: PhantomData,
+            out,
+        }
+    }
+
+    /// Returns the pin in the mode used by the peripheral
+    pub fn free(self) -> Out {
+        self.out
+    }
+
+    /// Releases the pin back to GPIO
+    ///
+    /// The pin is converted back into a floating input, so it can be reused
+    /// after the peripheral has been shut down.
+    pub fn release(self) -> Out::Released
+    where
+        Out: Release,
+    {
+        self.out.release()
+    }
+}
 
 /// A virtual DAC output that can be passed into an analog comparator negative
 /// input pin
+///
+/// Only a locked enabled DAC hands out this token, so the level it stands for
+/// can't disappear while the comparator uses it.
 pub struct DACOutputToAC<const IDX: u8>;
 impl<const IDX: u8> crate::private::Sealed for DACOutputToAC<IDX> {}
 
-// TODO: implement macros for the following code
-
-impl DacRegExt for DAC0 {
-    #[inline]
-    fn enable(&self, enable: bool) {
-        self.ctrla().modify(|_, w| w.enable().variant(enable));
-    }
+/// A virtual DAC output that can be measured by an ADC
+///
+/// Only a locked enabled DAC hands out this token, so the level it stands for
+/// can't disappear while the ADC uses it.
+pub struct DACOutputToADC<const IDX: u8>;
+impl<const IDX: u8> crate::private::Sealed for DACOutputToADC<IDX> {}
 
-    #[inline]
-    fn enable_output(&self, enable: bool) {
-        self.ctrla().modify(|_, w| w.outen().variant(enable));
+impl<INST: DacRegExt> Dac<INST, LockedEnabled> {
+    /// Get the DAC output that can be used as a negative input into the
+    /// comparator with the same index as this DAC
+    pub fn dac_get_ac_input(&self) -> INST::AcInput {
+        INST::ac_input()
     }
 
-    #[inline]
-    fn set_value(&self, value: u8) {
-        self.data().write(|w| w.bits(value));
+    /// Get the DAC output that can be used as an input channel of the ADC
+    ///
+    /// This allows to measure the DAC output to self-test the analog path or
+    /// for closed-loop calibration.
+    pub fn dac_get_adc_input(&self) -> INST::AdcInput {
+        INST::adc_input()
     }
 }
 
-impl<INST: DacRegExt> Dac<INST, LockedEnabled> {
+impl Dac<DAC0, LockedEnabled> {
     /// Get the DAC output that can be used as a negative input into AC0
+    #[deprecated(note = "use `dac_get_ac_input` instead")]
     pub fn dac_get_ac0_input(&self) -> DACOutputToAC<0> {
-        DACOutputToAC
+        self.dac_get_ac_input()
     }
 }
 
-use crate::gpio::{Output, Stateless};
-impl DACOutputPin for crate::gpio::porta::PA6<Output<Stateless>> {}
+macro_rules! impl_dac {
+    ($DAC:ident, $IDX:literal, $refselbits:ident) => {
+        impl DacExt<crate::pac::$DAC, $IDX> for crate::pac::$DAC {
+            fn constrain(self, ref_voltage: DACReferenceVoltage<$IDX>) -> Dac<Self, Disabled> {
+                Dac {
+                    dac: self,
+                    _ref: ref_voltage,
+                    _enabled: PhantomData,
+                }
+            }
+        }
+
+        impl DacRegExt for crate::pac::$DAC {
+            type Reference = DACReferenceVoltage<$IDX>;
+            type AcInput = DACOutputToAC<$IDX>;
+            type AdcInput = DACOutputToADC<$IDX>;
+
+            #[inline]
+            fn ac_input() -> Self::AcInput {
+                DACOutputToAC
+            }
+
+            #[inline]
+            fn adc_input() -> Self::AdcInput {
+                DACOutputToADC
+            }
+
+            #[inline]
+            fn enable(&self, enable: bool) {
+                self.ctrla().modify(|_, w| w.enable().variant(enable));
+            }
+
+            #[inline]
+            fn enable_output(&self, enable: bool) {
+                self.ctrla().modify(|_, w| w.outen().variant(enable));
+            }
+
+            #[inline]
+            fn set_value(&self, value: u8) {
+                self.data().write(|w| w.bits(value));
+            }
+
+            #[inline]
+            fn reference(&self) -> Option<ReferenceVoltage> {
+                // NOTE(unsafe): atomic read with no side effects
+                let vref = unsafe { &*crate::pac::VREF::ptr() };
+                ReferenceVoltage::from_bits(vref.ctrla().read().$refselbits().bits())
+            }
+        }
+    };
+}
+
+impl_dac!(DAC0, 0, dac0refsel);
+
+impl DACOutputPin<DAC0> for crate::gpio::porta::PA6<Output<Stateless>> {}
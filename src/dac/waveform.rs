@@ -0,0 +1,158 @@
+//! # Waveform playback
+//!
+//! Plays a table of samples on the DAC, one sample per period of a timer. The
+//! timer interrupt has to call [`Waveform::on_interrupt`], which writes the
+//! next sample to the DAC. This is enough for test signals and beeps, the
+//! jitter of the interrupt latency limits the quality of the output though.
+//!
+//! ```
+//! static WAVEFORM: Mutex<RefCell<Option<Waveform<'static, pac::DAC0, pac::TCB0>>>> = Mutex::new(RefCell::new(None));
+//!
+//! #[avr_device::interrupt(attiny817)]
+//! fn TCB0_INT() {
+//!     interrupt::free(|cs| {
+//!         if let Some(waveform) = WAVEFORM.borrow(cs).borrow_mut().as_mut() {
+//!             waveform.on_interrupt();
+//!         }
+//!     });
+//! }
+//!
+//! let dac_ref = vref.dac0.with_voltage(&mut vref.vref, ReferenceVoltage::_2V50);
+//! let dac = dp.DAC0.constrain(dac_ref).enable();
+//!
+//! let mut counter = Timer::new(dp.TCB0, clocks.into()).counter_hz();
+//! counter.enable_interrupt(Interrupt::CaptureCompare);
+//!
+//! // A 1kHz sine wave
+//! let mut waveform = Waveform::new(dac, counter, &SINE, Playback::Repeat);
+//! waveform.start(32.kHz()).unwrap();
+//! interrupt::free(|cs| WAVEFORM.borrow(cs).replace(Some(waveform)));
+//! unsafe { avr_device::interrupt::enable() };
+//! ```
+//!
+//! The frequency of the signal is the sample rate divided by the number of
+//! samples in the table. At 20MHz, the interrupt handler needs a few µs, so
+//! sample rates above 50kHz leave little time for anything else.
+
+use super::{Dac, DacRegExt, Enabled};
+use crate::time::*;
+use crate::timer::{CounterHz, Error, Instance, PeriodicMode};
+
+/// One period of a sine wave
+pub const SINE: [u8; 32] = [
+    128, 153, 177, 199, 218, 234, 245, 253, 255, 253, 245, 234, 218, 199, 177, 153, 128, 103, 79,
+    57, 38, 22, 11, 3, 1, 3, 11, 22, 38, 57, 79, 103,
+];
+
+/// One period of a triangle wave
+pub const TRIANGLE: [u8; 32] = [
+    0, 16, 32, 48, 64, 80, 96, 112, 128, 144, 160, 176, 192, 208, 224, 240, 255, 239, 223, 207,
+    191, 175, 159, 143, 127, 111, 95, 79, 63, 47, 31, 15,
+];
+
+/// What happens at the end of the sample table
+#[derive(ufmt::derive::uDebug, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Playback {
+    /// Stop the timer after the last sample, the DAC keeps its value
+    Once,
+    /// Start over with the first sample
+    Repeat,
+}
+
+/// Waveform player driving a DAC from a timer
+///
+/// See the [module level documentation](self) for details.
+pub struct Waveform<'a, INST: DacRegExt, TIM: Instance> {
+    dac: Dac<INST, Enabled>,
+    counter: CounterHz<TIM>,
+    samples: &'a [u8],
+    position: usize,
+    playback: Playback,
+}
+
+impl<'a, INST, TIM> Waveform<'a, INST, TIM>
+where
+    INST: DacRegExt,
+    TIM: Instance + PeriodicMode,
+{
+    /// Creates a player for the `samples`
+    ///
+    /// The interrupt of the counter has to be configured by the caller.
+    /// Nothing is played until [`start`](Self::start) is called.
+    pub fn new(
+        dac: Dac<INST, Enabled>,
+        counter: CounterHz<TIM>,
+        samples: &'a [u8],
+        playback: Playback,
+    ) -> Self {
+        Self {
+            dac,
+            counter,
+            samples,
+            position: 0,
+            playback,
+        }
+    }
+
+    /// Starts playing the samples from the beginning at the `sample_rate`
+    pub fn start(&mut self, sample_rate: Hertz) -> Result<(), Error> {
+        self.position = 0;
+        self.write_next();
+        self.counter.start(sample_rate)
+    }
+
+    /// Stops playing, the DAC keeps the current value
+    pub fn stop(&mut self) -> Result<(), Error> {
+        self.counter.cancel()
+    }
+
+    /// Replaces the samples, which are played from the beginning
+    ///
+    /// The sample rate stays the same.
+    pub fn set_samples(&mut self, samples: &'a [u8]) {
+        self.samples = samples;
+        self.position = 0;
+    }
+
+    /// Returns `true` if all samples have been played in [`Playback::Once`] mode
+    #[inline]
+    pub fn is_finished(&self) -> bool {
+        self.position >= self.samples.len()
+    }
+
+    /// Writes the next sample to the DAC
+    ///
+    /// Call this from the interrupt handler of the timer. The interrupt flag
+    /// is cleared, calls without a pending flag don't change the output.
+    pub fn on_interrupt(&mut self) {
+        if self.counter.wait().is_err() {
+            return;
+        }
+
+        if self.position >= self.samples.len() {
+            match self.playback {
+                Playback::Once => {
+                    let _ = self.counter.cancel();
+                    return;
+                }
+                Playback::Repeat => self.position = 0,
+            }
+        }
+
+        self.write_next();
+    }
+
+    /// Stops playing and returns the DAC and the counter
+    pub fn free(mut self) -> (Dac<INST, Enabled>, CounterHz<TIM>) {
+        let _ = self.counter.cancel();
+        (self.dac, self.counter)
+    }
+
+    #[inline]
+    fn write_next(&mut self) {
+        if let Some(sample) = self.samples.get(self.position) {
+            self.dac.dac.set_value(*sample);
+            self.position += 1;
+        }
+    }
+}
@@ -2,7 +2,7 @@
 
 use core::marker::PhantomData;
 
-use crate::gpio::Peripheral;
+use crate::gpio::{Peripheral, Release};
 use crate::pac::EVSYS;
 
 /// Event output channel 0 (EVOUT0)
@@ -34,9 +34,21 @@ where
         }
     }
 
+    /// Returns the pin in the mode used by the peripheral
     pub fn free(self) -> EventOutput {
         self.output
     }
+
+    /// Releases the pin back to GPIO
+    ///
+    /// The pin is converted back into a floating input, so it can be reused
+    /// after the peripheral has been shut down.
+    pub fn release(self) -> EventOutput::Released
+    where
+        EventOutput: Release,
+    {
+        self.output.release()
+    }
 }
 
 impl EventOutputPin<EVSYS, EVOUT0> for crate::gpio::porta::PA2<Peripheral<EVSYS>> {}
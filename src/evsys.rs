@@ -1,9 +1,27 @@
 //! # Event system
+//!
+//! The event system of the 1-series has two kinds of channels which are
+//! encoded in the type of a [`Channel`]:
+//!
+//! * [`Async`] channels route events without involving the peripheral clock.
+//!   Most generators, like the port pins, the PIT or the CCL LUTs, only exist
+//!   on some of the asynchronous channels. Only asynchronous users can be
+//!   connected to them.
+//! * [`Sync`] channels are synchronized to the peripheral clock. The timer
+//!   generators like the compare matches of TCA0 only exist on them. Both
+//!   synchronous users and asynchronous users can be connected to them.
+//!
+//! Generators implement [`EventGenerator`] only for the kind of channel and
+//! the channel indexes they are available on, users implement [`EventUser`]
+//! for the kind of channel they can listen to. Routings which would silently
+//! do nothing on the hardware are rejected at compile time this way.
 
 use core::marker::PhantomData;
 
 use crate::pac::EVSYS;
 
+pub mod route;
+
 /// Extension trait to configure an `EVSYS` peripheral and all containing channels
 pub trait EvsysExt {
     /// The Parts to split the `EVSYS` peripheral into
@@ -22,6 +40,9 @@ mod private {
         fn set_async_user(&self, user_idx: u8, multiplexer_select: u8);
         fn set_sync_user(&self, user_idx: u8, multiplexer_select: u8);
 
+        /// Disconnects all users listening to the channel with the user index
+        fn disconnect_users(&self, user_idx: u8);
+
         //FIXME: add strobes
     }
 
@@ -118,6 +139,8 @@ impl<Evsys, Flavor, Index, State> Channel<Evsys, Flavor, Index, State> {
 
 macro_rules! evsys {
     ({
+        async_users: $async_users:literal,
+        sync_users: $sync_users:literal,
         channels: [$(
             {
                 channel: $index:literal,
@@ -161,6 +184,22 @@ macro_rules! evsys {
                 fn set_sync_user(&self, user_idx: u8, multiplexer_select: u8) {
                     self.syncuser(multiplexer_select as usize).write(|f| unsafe { f.bits(user_idx) });
                 }
+
+                fn disconnect_users(&self, user_idx: u8) {
+                    // Synchronous channels can be connected to asynchronous
+                    // users as well, so both have to be checked
+                    for user in (0..$async_users).map(|i| self.asyncuser(i)) {
+                        if user.read().bits() == user_idx {
+                            user.write(|f| unsafe { f.bits(0) });
+                        }
+                    }
+
+                    for user in (0..$sync_users).map(|i| self.syncuser(i)) {
+                        if user.read().bits() == user_idx {
+                            user.write(|f| unsafe { f.bits(0) });
+                        }
+                    }
+                }
             }
 
             $(
@@ -203,6 +242,11 @@ macro_rules! evsys {
     };
 }
 
+/// A peripheral listening to events of a channel
+///
+/// `Flavor` is [`Async`] for users selecting one of the asynchronous
+/// channels through an `ASYNCUSER` register and [`Sync`] for users selecting
+/// one of the synchronous channels through a `SYNCUSER` register.
 pub trait EventUser<Evsys, Flavor>
 where
     Evsys: marker::Evsys,
@@ -216,6 +260,7 @@ where
     Evsys: marker::Evsys,
     Index: marker::Index,
 {
+    /// Connects an event user to this asynchronous channel
     pub fn connect_event_user<U: EventUser<Evsys, Async>>(
         mut self,
         _user: &U,
@@ -230,8 +275,9 @@ where
     Evsys: marker::Evsys,
     Index: marker::Index,
 {
-    pub fn free_user(mut self) -> Channel<Evsys, Async, Index, GeneratorAssigned> {
-        self.set_multiplexer(0);
+    /// Disconnects all users from this channel
+    pub fn free_user(self) -> Channel<Evsys, Async, Index, GeneratorAssigned> {
+        unsafe { (*self.evsys.ptr()).disconnect_users(Index::UX) }
         self.into_state()
     }
 }
@@ -252,8 +298,56 @@ where
     Evsys: marker::Evsys,
     Index: marker::Index,
 {
-    pub fn free_user(mut self) -> Channel<Evsys, Sync, Index, GeneratorAssigned> {
-        self.set_multiplexer(0);
+    /// Disconnects all users from this channel
+    ///
+    /// This includes asynchronous users connected using
+    /// [`connect_async_event_user`](Channel::connect_async_event_user).
+    pub fn free_user(self) -> Channel<Evsys, Sync, Index, GeneratorAssigned> {
+        unsafe { (*self.evsys.ptr()).disconnect_users(Index::UX) }
+        self.into_state()
+    }
+}
+
+impl<Evsys, Index> Channel<Evsys, Sync, Index, GeneratorAssigned>
+where
+    Evsys: marker::Evsys,
+    Index: marker::Index,
+{
+    /// Connects a synchronous event user to this synchronous channel
+    pub fn connect_event_user<U: EventUser<Evsys, Sync>>(
+        mut self,
+        _user: &U,
+    ) -> Channel<Evsys, Sync, Index, Configured> {
+        self.set_multiplexer(U::MULTIPLEXER_INDEX);
+        self.into_state()
+    }
+
+    /// Connects an asynchronous event user to this synchronous channel
+    ///
+    /// Asynchronous users like the ADC can also be connected to the synchronous
+    /// channels. This allows e.g. to trigger an ADC conversion by a compare
+    /// match of TCA0.
+    pub fn connect_async_event_user<U: EventUser<Evsys, Async>>(
+        self,
+        _user: &U,
+    ) -> Channel<Evsys, Sync, Index, Configured> {
+        unsafe { (*self.evsys.ptr()).set_async_user(Index::UX, U::MULTIPLEXER_INDEX) }
+        self.into_state()
+    }
+}
+
+impl<Evsys, Index> Channel<Evsys, Sync, Index, Configured>
+where
+    Evsys: marker::Evsys,
+    Index: marker::Index,
+{
+    /// Disconnects an asynchronous event user that was connected using
+    /// [`connect_async_event_user`](Channel::connect_async_event_user)
+    pub fn free_async_user<U: EventUser<Evsys, Async>>(
+        self,
+        _user: &U,
+    ) -> Channel<Evsys, Sync, Index, GeneratorAssigned> {
+        unsafe { (*self.evsys.ptr()).set_async_user(0, U::MULTIPLEXER_INDEX) }
         self.into_state()
     }
 }
@@ -269,6 +363,26 @@ where
     }
 }
 
+impl<Evsys, Flavor, Index, State> Channel<Evsys, Flavor, Index, State>
+where
+    Evsys: marker::Evsys,
+    Flavor: marker::ChannelFlavor,
+    Index: marker::Index,
+    State: marker::ChannelState,
+    Self: ChannelConfigurator<Flavor>,
+{
+    /// Releases the channel regardless of its configuration
+    ///
+    /// All users are disconnected and the generator is cleared, so the
+    /// channel can be used again with a different routing. Dropping a channel
+    /// leaves the routing in place.
+    pub fn free(mut self) -> Channel<Evsys, Flavor, Index, Unconfigured> {
+        unsafe { (*self.evsys.ptr()).disconnect_users(Index::UX) }
+        self.set_generator(0);
+        self.into_state()
+    }
+}
+
 pub trait ChannelConfigurator<F> {
     fn set_multiplexer(&mut self, multiplexer: u8);
     fn set_generator(&mut self, generator: u8);
@@ -304,6 +418,10 @@ where
     }
 }
 
+/// A peripheral generating events on a channel
+///
+/// Implemented only for the kind of channel and, where the hardware
+/// restricts it, for the channel indexes the generator is available on.
 pub trait EventGenerator<Evsys, Flavor, Index>
 where
     Evsys: marker::Evsys,
@@ -320,6 +438,8 @@ where
 }
 
 evsys!({
+    async_users: 13,
+    sync_users: 2,
     channels: [
         {
             channel: 0,
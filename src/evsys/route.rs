@@ -0,0 +1,137 @@
+//! # Routing builder
+//!
+//! Describes a complete routing from a generator through a channel to any
+//! number of users in one expression. Nothing is written to the event system
+//! until the routing is [`apply`](Route::apply)ed, which then happens in a
+//! single critical section.
+//!
+//! ```
+//! let evsys = dp.EVSYS.split();
+//!
+//! // AC0 -> ASYNCCH0 -> EVOUT0 and EVOUT1
+//! let route = evsys
+//!     .channel_async0
+//!     .route(&mut ac, ())
+//!     .to(&evout0)
+//!     .to(&evout1)
+//!     .apply();
+//!
+//! // ...
+//!
+//! // Disconnect everything and get the channel back
+//! let channel_async0 = route.free();
+//! ```
+//!
+//! The generator and the users are checked for the kind of channel like with
+//! [`EventGenerator::connect_event_generator`] and [`Channel::connect_event_user`].
+
+use avr_device::interrupt;
+
+use super::private::EvsysRegExt;
+use super::{marker, Async, Channel, Configured, EventGenerator, EventUser, Sync, Unconfigured};
+
+/// A routing which hasn't been applied yet
+///
+/// Created by [`Channel::route`].
+pub struct Route<'a, Evsys, Flavor, Index, G>
+where
+    Evsys: marker::Evsys,
+    Flavor: marker::ChannelFlavor,
+    Index: marker::Index,
+    G: EventGenerator<Evsys, Flavor, Index>,
+{
+    channel: Channel<Evsys, Flavor, Index, Unconfigured>,
+    generator: &'a mut G,
+    source: G::EventSource,
+    /// Bitmask of the `ASYNCUSER` registers to connect
+    async_users: u16,
+    /// Bitmask of the `SYNCUSER` registers to connect
+    sync_users: u8,
+}
+
+impl<Evsys, Flavor, Index> Channel<Evsys, Flavor, Index, Unconfigured>
+where
+    Evsys: marker::Evsys,
+    Flavor: marker::ChannelFlavor,
+    Index: marker::Index,
+{
+    /// Start describing a routing from the `generator` through this channel
+    pub fn route<G: EventGenerator<Evsys, Flavor, Index>>(
+        self,
+        generator: &mut G,
+        source: G::EventSource,
+    ) -> Route<'_, Evsys, Flavor, Index, G> {
+        Route {
+            channel: self,
+            generator,
+            source,
+            async_users: 0,
+            sync_users: 0,
+        }
+    }
+}
+
+impl<Evsys, Index, G> Route<'_, Evsys, Async, Index, G>
+where
+    Evsys: marker::Evsys,
+    Index: marker::Index,
+    G: EventGenerator<Evsys, Async, Index>,
+{
+    /// Add a user to the routing
+    pub fn to<U: EventUser<Evsys, Async>>(mut self, _user: &U) -> Self {
+        self.async_users |= 1 << U::MULTIPLEXER_INDEX;
+        self
+    }
+}
+
+impl<Evsys, Index, G> Route<'_, Evsys, Sync, Index, G>
+where
+    Evsys: marker::Evsys,
+    Index: marker::Index,
+    G: EventGenerator<Evsys, Sync, Index>,
+{
+    /// Add a synchronous user to the routing
+    pub fn to<U: EventUser<Evsys, Sync>>(mut self, _user: &U) -> Self {
+        self.sync_users |= 1 << U::MULTIPLEXER_INDEX;
+        self
+    }
+
+    /// Add an asynchronous user to the routing
+    ///
+    /// See [`Channel::connect_async_event_user`].
+    pub fn to_async<U: EventUser<Evsys, Async>>(mut self, _user: &U) -> Self {
+        self.async_users |= 1 << U::MULTIPLEXER_INDEX;
+        self
+    }
+}
+
+impl<Evsys, Flavor, Index, G> Route<'_, Evsys, Flavor, Index, G>
+where
+    Evsys: marker::Evsys,
+    Flavor: marker::ChannelFlavor,
+    Index: marker::Index,
+    G: EventGenerator<Evsys, Flavor, Index>,
+{
+    /// Write the routing to the event system
+    ///
+    /// Returns the configured channel, which can be torn down again using
+    /// [`Channel::free`].
+    pub fn apply(self) -> Channel<Evsys, Flavor, Index, Configured> {
+        interrupt::free(|_| {
+            let channel = self
+                .generator
+                .connect_event_generator(self.channel, self.source);
+            let regs = unsafe { &*channel.evsys.ptr() };
+
+            for i in (0..16).filter(|i| self.async_users & (1 << i) != 0) {
+                regs.set_async_user(Index::UX, i);
+            }
+
+            for i in (0..8).filter(|i| self.sync_users & (1 << i) != 0) {
+                regs.set_sync_user(Index::UX, i);
+            }
+
+            channel.into_state()
+        })
+    }
+}
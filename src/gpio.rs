@@ -16,11 +16,43 @@ pub trait GpioExt {
 
     /// Splits the GPIO block into independent pins and registers
     fn split(self) -> Self::Parts;
+
+    /// Splits the GPIO block after applying the initial pin configuration
+    ///
+    /// The output levels and pull-ups are applied before any pin is
+    /// converted into another mode, so an output doesn't drive the wrong
+    /// level and an input doesn't float in between.
+    fn split_with_config(self, config: PortConfig) -> Self::Parts;
+}
+
+/// Initial configuration of the pins of a port for [`GpioExt::split_with_config`]
+///
+/// Bit `n` of each mask applies to the pin `n` of the port.
+#[derive(ufmt::derive::uDebug, Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct PortConfig {
+    /// Pins with enabled pull-up
+    pub pull_ups: u8,
+    /// Pins driving a high level once they are switched to outputs
+    pub high: u8,
+}
+
+impl PortConfig {
+    /// Enables the pull-ups of the pins in the mask
+    pub fn pull_ups(mut self, mask: u8) -> Self {
+        self.pull_ups = mask;
+        self
+    }
+
+    /// Sets the initial output level of the pins in the mask to high
+    pub fn high(mut self, mask: u8) -> Self {
+        self.high = mask;
+        self
+    }
 }
 
 /// GPIO Register interface traits private to this module
 mod private {
-    use super::Edge;
+    use super::InputSense;
 
     pub trait GpioRegExt {
         fn is_low(&self, i: u8) -> bool;
@@ -37,13 +69,25 @@ mod private {
 
         fn normal(&self, i: u8);
         fn inverted(&self, i: u8);
+        fn is_inverted(&self, i: u8) -> bool;
 
         fn enable_input_buffer(&self, i: u8);
         fn disable_input_buffer(&self, i: u8);
 
+        fn read_bits(&self) -> u8;
+        fn write_bits(&self, mask: u8, bits: u8);
+        fn input_bits(&self, mask: u8);
+        fn output_bits(&self, mask: u8);
+        fn set_high_bits(&self, mask: u8);
+        fn set_low_bits(&self, mask: u8);
+        fn toggle_bits(&self, mask: u8);
+
         fn interrupt_pending(&self, i: u8) -> bool;
         fn clear_interrupt_pending(&self, i: u8);
-        fn configure_interrupt(&self, i: u8, edge: Edge);
+        fn interrupt_bits(&self) -> u8;
+        fn clear_interrupt_bits(&self, mask: u8);
+        fn set_input_sense(&self, i: u8, sense: InputSense);
+        fn input_sense(&self, i: u8) -> InputSense;
     }
 
     pub trait Gpio {
@@ -51,12 +95,35 @@ mod private {
 
         fn ptr(&self) -> *const Self::Reg;
         fn port_index(&self) -> u8;
+
+        // Access to pins with a compile time defined pin number. Ports with
+        // a VPORT override these with single `sbi` instructions.
+        #[inline(always)]
+        fn set_high_static<const X: u8>(&self) {
+            unsafe { (*self.ptr()).set_high(X) }
+        }
+
+        #[inline(always)]
+        fn set_low_static<const X: u8>(&self) {
+            unsafe { (*self.ptr()).set_low(X) }
+        }
+
+        #[inline(always)]
+        fn toggle_static<const X: u8>(&self) {
+            unsafe { (*self.ptr()).toggle(X) }
+        }
     }
 }
 
 use embedded_hal::digital::ErrorType;
 use private::GpioRegExt;
 
+pub mod bus;
+#[cfg(feature = "gpio-dispatch")]
+pub mod dispatch;
+pub mod dynamic;
+pub mod group;
+
 /// Marker traits used in this module
 pub mod marker {
     /// Marker trait for GPIO ports
@@ -69,6 +136,15 @@ pub mod marker {
     pub trait Index {
         #[doc(hidden)]
         fn index(&self) -> u8;
+
+        #[doc(hidden)]
+        fn set_high<G: Gpio>(&self, gpio: &G);
+
+        #[doc(hidden)]
+        fn set_low<G: Gpio>(&self, gpio: &G);
+
+        #[doc(hidden)]
+        fn toggle<G: Gpio>(&self, gpio: &G);
     }
 
     /// Marker trait for readable pin modes
@@ -79,6 +155,9 @@ pub mod marker {
 
     /// Marker trait for pins which can have an enabled pull-up resistor
     pub trait Pullupable {}
+
+    /// Marker trait for the pin numbers 2 and 6, which are fully asynchronous
+    pub trait FullyAsynchronous: Index {}
 }
 
 /// Runtime defined GPIO port (type state)
@@ -122,6 +201,24 @@ impl marker::Index for Ux {
     fn index(&self) -> u8 {
         self.0
     }
+
+    #[inline(always)]
+    fn set_high<G: marker::Gpio>(&self, gpio: &G) {
+        // NOTE(unsafe) atomic write to a stateless register
+        unsafe { (*gpio.ptr()).set_high(self.0) }
+    }
+
+    #[inline(always)]
+    fn set_low<G: marker::Gpio>(&self, gpio: &G) {
+        // NOTE(unsafe) atomic write to a stateless register
+        unsafe { (*gpio.ptr()).set_low(self.0) }
+    }
+
+    #[inline(always)]
+    fn toggle<G: marker::Gpio>(&self, gpio: &G) {
+        // NOTE(unsafe) atomic write to a stateless register
+        unsafe { (*gpio.ptr()).toggle(self.0) }
+    }
 }
 
 /// Compile time defined pin number (type state)
@@ -133,8 +230,26 @@ impl<const X: u8> marker::Index for U<X> {
     fn index(&self) -> u8 {
         X
     }
+
+    #[inline(always)]
+    fn set_high<G: marker::Gpio>(&self, gpio: &G) {
+        gpio.set_high_static::<X>()
+    }
+
+    #[inline(always)]
+    fn set_low<G: marker::Gpio>(&self, gpio: &G) {
+        gpio.set_low_static::<X>()
+    }
+
+    #[inline(always)]
+    fn toggle<G: marker::Gpio>(&self, gpio: &G) {
+        gpio.toggle_static::<X>()
+    }
 }
 
+impl marker::FullyAsynchronous for U<2> {}
+impl marker::FullyAsynchronous for U<6> {}
+
 /// Input mode (type state)
 #[derive(ufmt::derive::uDebug, Debug)]
 pub struct Input;
@@ -144,6 +259,12 @@ pub struct Input;
 pub struct Output<Statefulness>(PhantomData<Statefulness>);
 
 /// Analog mode with disabled input buffer (type state)
+///
+/// This is the pin mode accepted by the analog peripherals like the
+/// [`Adc`](crate::adc::Adc) channels and the [`Comparator`](crate::ac::Comparator) inputs.
+/// The digital input buffer is disabled, which reduces the power consumption
+/// for analog voltages and prevents the pin from being used as a digital pin
+/// by accident.
 #[derive(ufmt::derive::uDebug, Debug)]
 pub struct Analog;
 
@@ -190,6 +311,39 @@ pub enum Edge {
     LowLevel,
 }
 
+/// Input and sense configuration of a pin
+///
+/// Wrapper around [`ISC_A`](crate::pac::porta::pinctrl::ISC_A)
+#[derive(ufmt::derive::uDebug, Debug, Copy, Clone, PartialEq, Eq)]
+#[doc(alias = "ISC")]
+pub enum InputSense {
+    /// Input buffer enabled, no interrupt
+    InterruptDisabled,
+    /// Interrupt on both edges
+    BothEdges,
+    /// Interrupt on the rising edge
+    Rising,
+    /// Interrupt on the falling edge
+    Falling,
+    /// Digital input buffer disabled
+    ///
+    /// The pin always reads low in this configuration.
+    InputDisabled,
+    /// Interrupt while the pin is low
+    LowLevel,
+}
+
+impl From<Edge> for InputSense {
+    fn from(edge: Edge) -> Self {
+        match edge {
+            Edge::Rising => InputSense::Rising,
+            Edge::Falling => InputSense::Falling,
+            Edge::RisingFalling => InputSense::BothEdges,
+            Edge::LowLevel => InputSense::LowLevel,
+        }
+    }
+}
+
 /// Generic pin
 #[derive(Debug)]
 pub struct Pin<Gpio, Index, Mode> {
@@ -201,6 +355,51 @@ pub struct Pin<Gpio, Index, Mode> {
 // Make all GPIO peripheral trait extensions sealable.
 impl<Gpio, Index, Mode> crate::private::Sealed for Pin<Gpio, Index, Mode> {}
 
+/// Pins of a peripheral pinset which can be released back to GPIO
+///
+/// Implemented for single pins and pairs of pins as used by the pinsets of
+/// the peripherals.
+pub trait Release: crate::private::Sealed {
+    /// The released pins
+    type Released;
+
+    /// Converts the pins back into floating inputs with normal polarity,
+    /// which is their reset state
+    fn release(self) -> Self::Released;
+}
+
+impl<Gpio, Index, Mode> Release for Pin<Gpio, Index, Mode>
+where
+    Gpio: marker::GpioStatic,
+    Index: marker::Index,
+{
+    type Released = Pin<Gpio, Index, Input>;
+
+    fn release(mut self) -> Self::Released {
+        self.invert_polarity(Toggle::Off);
+        self.into_floating_input()
+    }
+}
+
+impl<A: Release, B: Release> Release for (A, B) {
+    type Released = (A::Released, B::Released);
+
+    fn release(self) -> Self::Released {
+        (self.0.release(), self.1.release())
+    }
+}
+
+/// Pin which can wake up the device from all sleep modes on any sense condition
+///
+/// Only the pins 2 and 6 of each port are fully asynchronous. All other pins
+/// can only wake up the device from power-down on both edges or on a low
+/// level, as edges are detected with the peripheral clock, which is stopped in
+/// these sleep modes. Sleep code can require this trait to make sure that a
+/// configured edge actually wakes up the device.
+pub trait WakeupPin: crate::private::Sealed {}
+
+impl<Gpio, Index, Mode> WakeupPin for Pin<Gpio, Index, Mode> where Index: marker::FullyAsynchronous {}
+
 /// Fully erased pin
 ///
 /// This moves the pin type information to be known
@@ -298,6 +497,7 @@ where
     ///
     /// It is not strictly necessary to configure a pin into an analog mode,
     /// but the datasheet recommends to disable the input and output driver.
+    #[doc(alias = "into_analog")]
     pub fn into_analog_input(self) -> Pin<Gpio, Index, Analog> {
         unsafe { (*self.gpio.ptr()).disable_input_buffer(self.index.index()) }
         unsafe { (*self.gpio.ptr()).input(self.index.index()) }
@@ -328,16 +528,93 @@ where
 
 impl<Gpio, Index, Mode> Pin<Gpio, Index, Mode>
 where
-    Gpio: marker::GpioStatic,
+    Gpio: marker::Gpio,
     Index: marker::Index,
 {
     /// Set pin inversion for inputs or outputs
-    pub fn invert_polarity(&mut self, invert: Toggle) {
-        match invert {
+    ///
+    /// The inversion applies to the input and output of the pin, including
+    /// the signals of peripherals using the pin and the sense configuration.
+    /// This allows to handle active-low signals uniformly.
+    #[doc(alias = "INVEN")]
+    pub fn invert_polarity(&mut self, invert: impl Into<Toggle>) {
+        match invert.into() {
             Toggle::On => unsafe { (*self.gpio.ptr()).inverted(self.index.index()) },
             Toggle::Off => unsafe { (*self.gpio.ptr()).normal(self.index.index()) },
         }
     }
+
+    /// Inverts the input and output of the pin
+    ///
+    /// See [`Pin::invert_polarity`].
+    pub fn into_inverted(mut self) -> Self {
+        self.invert_polarity(Toggle::On);
+        self
+    }
+
+    /// Check if the input and output of the pin are inverted
+    pub fn is_inverted(&self) -> bool {
+        // NOTE(unsafe) atomic read with no side effects
+        unsafe { (*self.gpio.ptr()).is_inverted(self.index.index()) }
+    }
+}
+
+impl<Gpio, Index> Pin<Gpio, Index, Analog>
+where
+    Gpio: marker::Gpio,
+    Index: marker::Index,
+{
+    /// Drive the pin high while staying in the analog mode
+    ///
+    /// Used by drivers like the ADC touch sensor which need to charge an
+    /// analog pin before measuring it.
+    pub(crate) fn drive_high(&mut self) {
+        // NOTE(unsafe) atomic writes to stateless registers
+        unsafe { (*self.gpio.ptr()).set_high(self.index.index()) }
+        unsafe { (*self.gpio.ptr()).output(self.index.index()) }
+    }
+
+    /// Disable the output driver again after [`Pin::drive_high`]
+    pub(crate) fn stop_driving(&mut self) {
+        // NOTE(unsafe) atomic write to a stateless register
+        unsafe { (*self.gpio.ptr()).input(self.index.index()) }
+    }
+}
+
+impl<Gpio, Index, PER> Pin<Gpio, Index, Peripheral<PER>>
+where
+    Gpio: marker::Gpio,
+    Index: marker::Index,
+{
+    /// Emulate an open drain output while the peripheral is disabled
+    ///
+    /// Used by drivers like the TWI bus recovery which need to drive the bus
+    /// lines of a disabled peripheral by software. The line is released when
+    /// `high` is set and relies on an external pull-up in that case.
+    pub(crate) fn set_open_drain(&mut self, high: bool) {
+        // The input buffer is disabled in the peripheral mode, but is needed
+        // to read back the line
+        unsafe { (*self.gpio.ptr()).enable_input_buffer(self.index.index()) }
+        // NOTE(unsafe) atomic writes to stateless registers
+        unsafe { (*self.gpio.ptr()).set_low(self.index.index()) }
+        if high {
+            unsafe { (*self.gpio.ptr()).input(self.index.index()) }
+        } else {
+            unsafe { (*self.gpio.ptr()).output(self.index.index()) }
+        }
+    }
+
+    /// Read the level of the line driven by [`Pin::set_open_drain`]
+    pub(crate) fn is_line_low(&self) -> bool {
+        // NOTE(unsafe) atomic read with no side effects
+        unsafe { (*self.gpio.ptr()).is_low(self.index.index()) }
+    }
+
+    /// Hand the pin back to the peripheral after [`Pin::set_open_drain`]
+    pub(crate) fn restore_peripheral(&mut self) {
+        unsafe { (*self.gpio.ptr()).input(self.index.index()) }
+        unsafe { (*self.gpio.ptr()).disable_input_buffer(self.index.index()) }
+    }
 }
 
 impl<Gpio, Index, Itype> Pin<Gpio, Index, Itype>
@@ -369,14 +646,12 @@ where
     Index: marker::Index,
 {
     fn set_high(&mut self) -> Result<(), Self::Error> {
-        // NOTE(unsafe) atomic write to a stateless register
-        unsafe { (*self.gpio.ptr()).set_high(self.index.index()) };
+        self.index.set_high(&self.gpio);
         Ok(())
     }
 
     fn set_low(&mut self) -> Result<(), Self::Error> {
-        // NOTE(unsafe) atomic write to a stateless register
-        unsafe { (*self.gpio.ptr()).set_low(self.index.index()) };
+        self.index.set_low(&self.gpio);
         Ok(())
     }
 }
@@ -412,7 +687,7 @@ where
     }
 
     fn toggle(&mut self) -> Result<(), Self::Error> {
-        unsafe { (*self.gpio.ptr()).toggle(self.index.index()) }
+        self.index.toggle(&self.gpio);
         Ok(())
     }
 }
@@ -425,8 +700,30 @@ where
 {
     /// Configure external interrupts from this pin
     pub fn configure_interrupt(&mut self, edge: Edge) {
+        self.set_input_sense(edge.into());
+    }
+
+    /// Set the input and sense configuration of this pin
+    ///
+    /// This selects the condition setting the interrupt flag of the pin, or
+    /// disables the digital input buffer.
+    #[doc(alias = "ISC")]
+    pub fn set_input_sense(&mut self, sense: InputSense) {
         // NOTE(unsafe) atomic write with no side effects
-        unsafe { (*self.gpio.ptr()).configure_interrupt(self.index.index(), edge) }
+        unsafe { (*self.gpio.ptr()).set_input_sense(self.index.index(), sense) }
+    }
+
+    /// Check if the pin is fully asynchronous
+    ///
+    /// This is the runtime version of [`WakeupPin`] for erased pins.
+    pub fn is_fully_asynchronous(&self) -> bool {
+        matches!(self.index.index(), 2 | 6)
+    }
+
+    /// Read the input and sense configuration of this pin
+    pub fn input_sense(&self) -> InputSense {
+        // NOTE(unsafe) atomic read with no side effects
+        unsafe { (*self.gpio.ptr()).input_sense(self.index.index()) }
     }
 
     /// Disable the external interrupts from this pin
@@ -514,6 +811,11 @@ macro_rules! gpio_trait {
                     self.pinctrl(i as usize).modify(|_, w| w.inven().set_bit())
                 }
 
+                #[inline(always)]
+                fn is_inverted(&self, i: u8) -> bool {
+                    self.pinctrl(i as usize).read().inven().bit_is_set()
+                }
+
                 #[inline(always)]
                 fn enable_input_buffer(&self, i: u8) {
                     self.pinctrl(i as usize).modify(|_, w| w.isc().intdisable())
@@ -524,6 +826,56 @@ macro_rules! gpio_trait {
                     self.pinctrl(i as usize).modify(|_, w| w.isc().input_disable())
                 }
 
+                #[inline(always)]
+                fn read_bits(&self) -> u8 {
+                    self.in_().read().bits()
+                }
+
+                #[inline(always)]
+                fn write_bits(&self, mask: u8, bits: u8) {
+                    if mask == 0xff {
+                        // NOTE(unsafe, write) the whole port is owned by the caller
+                        unsafe { self.out().write(|w| w.bits(bits)) };
+                    } else {
+                        // The other pins of the port may be changed from an interrupt
+                        avr_device::interrupt::free(|_| {
+                            // NOTE(unsafe, write) only the masked pins are changed
+                            self.out()
+                                .modify(|r, w| unsafe { w.bits(r.bits() & !mask | bits & mask) })
+                        });
+                    }
+                }
+
+                #[inline(always)]
+                fn input_bits(&self, mask: u8) {
+                    // NOTE(unsafe, write) atomic write to a stateless register
+                    unsafe { self.dirclr().write(|w| w.bits(mask)) };
+                }
+
+                #[inline(always)]
+                fn output_bits(&self, mask: u8) {
+                    // NOTE(unsafe, write) atomic write to a stateless register
+                    unsafe { self.dirset().write(|w| w.bits(mask)) };
+                }
+
+                #[inline(always)]
+                fn set_high_bits(&self, mask: u8) {
+                    // NOTE(unsafe, write) atomic write to a stateless register
+                    unsafe { self.outset().write(|w| w.bits(mask)) };
+                }
+
+                #[inline(always)]
+                fn set_low_bits(&self, mask: u8) {
+                    // NOTE(unsafe, write) atomic write to a stateless register
+                    unsafe { self.outclr().write(|w| w.bits(mask)) };
+                }
+
+                #[inline(always)]
+                fn toggle_bits(&self, mask: u8) {
+                    // NOTE(unsafe, write) atomic write to a stateless register
+                    unsafe { self.outtgl().write(|w| w.bits(mask)) };
+                }
+
                 #[inline(always)]
                 fn interrupt_pending(&self, i: u8) -> bool {
                     self.intflags().read().bits() & (1 << i) != 0
@@ -536,12 +888,43 @@ macro_rules! gpio_trait {
                 }
 
                 #[inline(always)]
-                fn configure_interrupt(&self, i: u8, edge: Edge) {
-                    match edge {
-                        Edge::Rising => self.pinctrl(i as usize).modify(|_, w| w.isc().rising()),
-                        Edge::Falling => self.pinctrl(i as usize).modify(|_, w| w.isc().falling()),
-                        Edge::RisingFalling => self.pinctrl(i as usize).modify(|_, w| w.isc().bothedges()),
-                        Edge::LowLevel => self.pinctrl(i as usize).modify(|_, w| w.isc().level()),
+                fn interrupt_bits(&self) -> u8 {
+                    self.intflags().read().bits()
+                }
+
+                #[inline(always)]
+                fn clear_interrupt_bits(&self, mask: u8) {
+                    // NOTE(unsafe, write) atomic write to a stateless register
+                    unsafe { self.intflags().write(|w| w.bits(mask)) };
+                }
+
+                #[inline(always)]
+                fn set_input_sense(&self, i: u8, sense: InputSense) {
+                    self.pinctrl(i as usize).modify(|_, w| match sense {
+                        InputSense::InterruptDisabled => w.isc().intdisable(),
+                        InputSense::BothEdges => w.isc().bothedges(),
+                        InputSense::Rising => w.isc().rising(),
+                        InputSense::Falling => w.isc().falling(),
+                        InputSense::InputDisabled => w.isc().input_disable(),
+                        InputSense::LowLevel => w.isc().level(),
+                    })
+                }
+
+                #[inline(always)]
+                fn input_sense(&self, i: u8) -> InputSense {
+                    let isc = self.pinctrl(i as usize).read().isc();
+                    if isc.is_bothedges() {
+                        InputSense::BothEdges
+                    } else if isc.is_rising() {
+                        InputSense::Rising
+                    } else if isc.is_falling() {
+                        InputSense::Falling
+                    } else if isc.is_input_disable() {
+                        InputSense::InputDisabled
+                    } else if isc.is_level() {
+                        InputSense::LowLevel
+                    } else {
+                        InputSense::InterruptDisabled
                     }
                 }
             }
@@ -579,6 +962,46 @@ macro_rules! gpio {
             fn port_index(&self) -> u8 {
                 $port_index
             }
+
+            #[inline(always)]
+            fn set_high_static<const X: u8>(&self) {
+                // NOTE(unsafe) single instruction write to VPORT.OUT
+                unsafe {
+                    core::arch::asm!(
+                        "sbi {port}, {bit}",
+                        port = const 4 * $port_index + 1,
+                        bit = const X,
+                        options(nostack, preserves_flags),
+                    )
+                }
+            }
+
+            #[inline(always)]
+            fn set_low_static<const X: u8>(&self) {
+                // NOTE(unsafe) single instruction write to VPORT.OUT
+                unsafe {
+                    core::arch::asm!(
+                        "cbi {port}, {bit}",
+                        port = const 4 * $port_index + 1,
+                        bit = const X,
+                        options(nostack, preserves_flags),
+                    )
+                }
+            }
+
+            #[inline(always)]
+            fn toggle_static<const X: u8>(&self) {
+                // Writing a one to VPORT.IN toggles the output
+                // NOTE(unsafe) single instruction write to VPORT.IN
+                unsafe {
+                    core::arch::asm!(
+                        "sbi {port}, {bit}",
+                        port = const 4 * $port_index + 2,
+                        bit = const X,
+                        options(nostack, preserves_flags),
+                    )
+                }
+            }
         }
 
         impl marker::Gpio for $Portx {}
@@ -598,7 +1021,7 @@ macro_rules! gpio {
 
             use crate::pac::$PORTX;
 
-            use super::{$Portx, GpioExt, U};
+            use super::{$Portx, GpioExt, PortConfig, U};
             use super::Input;
 
             pub use super::{
@@ -630,6 +1053,17 @@ macro_rules! gpio {
                         )+
                     }
                 }
+
+                fn split_with_config(self, config: PortConfig) -> Parts {
+                    // NOTE(unsafe) the port isn't split yet, so all pins are owned here
+                    self.out().write(|w| unsafe { w.bits(config.high) });
+                    $(
+                        self.pinctrl($i)
+                            .modify(|_, w| w.pullupen().bit(config.pull_ups & (1 << $i) != 0));
+                    )+
+
+                    self.split()
+                }
             }
         }
     };
@@ -660,6 +1094,28 @@ macro_rules! gpio {
                     )+],
                 });
             )+
+
+            /// All pins of the device
+            ///
+            /// Collects the pins of all split ports in a single struct. This is
+            /// used by the [`pins!`](crate::pins!) macro to look up pins by name.
+            pub struct AllPins {
+                $($(
+                    #[doc = concat!("Pin ", stringify!([<P $X $i>]))]
+                    pub [<p $x $i>]: [<P $X $i>]<Input>,
+                )+)+
+            }
+
+            impl AllPins {
+                /// Collects the pins of the split ports
+                pub fn new($([<port $x>]: [<port $x>]::Parts),+) -> Self {
+                    Self {
+                        $($(
+                            [<p $x $i>]: [<port $x>].[<p $x $i>],
+                        )+)+
+                    }
+                }
+            }
         }
     };
 }
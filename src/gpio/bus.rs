@@ -0,0 +1,101 @@
+//! # Parallel bus on a GPIO port
+//!
+//! A [`PortBus`] drives a contiguous range of pins of a port as a parallel
+//! bus. All pins are read and written at once through the IN and OUT
+//! registers of the port, which is needed to drive parallel displays or
+//! DACs efficiently.
+//!
+//! ```
+//! let a = dp.PORTA.split();
+//! let mut bus = PortBus::new([
+//!     a.pa4.downgrade(),
+//!     a.pa5.downgrade(),
+//!     a.pa6.downgrade(),
+//!     a.pa7.downgrade(),
+//! ]);
+//!
+//! bus.set_output();
+//! bus.write(0b1010);
+//!
+//! bus.set_input();
+//! let nibble = bus.read();
+//! ```
+
+use super::{marker, private::GpioRegExt, Input, Pin, Ux};
+
+/// Parallel bus of `N` contiguous pins of a port
+///
+/// See the [module level documentation](self) for details.
+pub struct PortBus<Gpio, const N: usize> {
+    pins: [Pin<Gpio, Ux, Input>; N],
+    shift: u8,
+    mask: u8,
+}
+
+impl<Gpio, const N: usize> PortBus<Gpio, N>
+where
+    Gpio: marker::GpioStatic,
+{
+    /// Creates a new bus from the pins of a port
+    ///
+    /// The first pin is the least significant bit of the bus. All pins are
+    /// switched to inputs with an enabled input buffer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the pins aren't in ascending, contiguous order.
+    pub fn new<Mode>(pins: [Pin<Gpio, Ux, Mode>; N]) -> Self {
+        assert!(N > 0 && N <= 8, "invalid bus width");
+
+        let shift = pins[0].index.0;
+        for (i, pin) in pins.iter().enumerate() {
+            assert!(pin.index.0 == shift + i as u8, "pins not contiguous");
+        }
+
+        let pins = pins.map(|pin| pin.into_floating_input());
+        let mask = (0xffu16 >> (8 - N) << shift) as u8;
+
+        Self { pins, shift, mask }
+    }
+
+    /// Switches all pins of the bus to outputs
+    #[inline]
+    pub fn set_output(&mut self) {
+        // NOTE(unsafe) atomic write to a stateless register
+        unsafe { (*self.port()).output_bits(self.mask) }
+    }
+
+    /// Switches all pins of the bus to inputs
+    #[inline]
+    pub fn set_input(&mut self) {
+        // NOTE(unsafe) atomic write to a stateless register
+        unsafe { (*self.port()).input_bits(self.mask) }
+    }
+
+    /// Drives the value onto the bus
+    ///
+    /// Only the lower `N` bits of the value are used.
+    #[inline]
+    pub fn write(&mut self, value: u8) {
+        // NOTE(unsafe) only the pins of the bus are changed
+        unsafe { (*self.port()).write_bits(self.mask, value << self.shift) }
+    }
+
+    /// Reads the current levels of the bus
+    #[inline]
+    pub fn read(&self) -> u8 {
+        // NOTE(unsafe) atomic read with no side effects
+        (unsafe { (*self.port()).read_bits() } & self.mask) >> self.shift
+    }
+
+    /// Releases the pins as inputs
+    pub fn free(mut self) -> [Pin<Gpio, Ux, Input>; N] {
+        self.set_input();
+        self.pins
+    }
+
+    #[inline(always)]
+    fn port(&self) -> *const Gpio::Reg {
+        self.pins[0].gpio.ptr()
+    }
+}
@@ -0,0 +1,112 @@
+//! # Interrupt dispatch
+//!
+//! All pins of a port share a single interrupt vector. With the
+//! `gpio-dispatch` feature the HAL provides the interrupt handlers of the
+//! ports, which call a handler registered for each pin with a pending
+//! interrupt flag and clear the flags afterwards.
+//!
+//! ```
+//! fn button_pressed() {
+//!     // ...
+//! }
+//!
+//! let mut button = a.pa2.into_pull_up_input();
+//! button.configure_interrupt(Edge::Falling);
+//! button.set_interrupt_handler(button_pressed);
+//!
+//! unsafe { avr_device::interrupt::enable() };
+//! ```
+//!
+//! Handlers are plain function pointers, so closures can only be used as long
+//! as they don't capture anything. State shared with the main program has to
+//! be put into a `static`, as with any other interrupt handler.
+//!
+//! As the interrupt vectors are defined by the HAL, an application using this
+//! feature can't define its own `PORTA_PORT`, `PORTB_PORT` or `PORTC_PORT`
+//! interrupt handlers.
+
+use core::cell::Cell;
+
+use avr_device::interrupt::{self, Mutex};
+
+use super::{marker, private::GpioRegExt, Pin};
+
+/// Interrupt handler of a single pin
+pub type Handler = fn();
+
+type PortHandlers = Mutex<Cell<[Option<Handler>; 8]>>;
+
+static HANDLERS: [PortHandlers; 3] = [
+    Mutex::new(Cell::new([None; 8])),
+    Mutex::new(Cell::new([None; 8])),
+    Mutex::new(Cell::new([None; 8])),
+];
+
+/// Store the handler of a pin
+fn register(port_index: u8, i: u8, handler: Option<Handler>) {
+    interrupt::free(|cs| {
+        let port = HANDLERS[port_index as usize].borrow(cs);
+        let mut handlers = port.get();
+        handlers[i as usize] = handler;
+        port.set(handlers);
+    });
+}
+
+/// Call the handlers of all pins of a port with a pending interrupt
+#[inline(always)]
+fn dispatch<R: GpioRegExt>(port: &R, port_index: u8) {
+    // The flags are cleared before calling the handlers, so edges occurring
+    // while a handler runs trigger the interrupt again
+    let flags = port.interrupt_bits();
+    port.clear_interrupt_bits(flags);
+
+    let handlers = interrupt::free(|cs| HANDLERS[port_index as usize].borrow(cs).get());
+    for (i, handler) in handlers.iter().enumerate() {
+        if flags & (1 << i) != 0 {
+            if let Some(handler) = handler {
+                handler();
+            }
+        }
+    }
+}
+
+impl<Gpio, Index, Mode> Pin<Gpio, Index, Mode>
+where
+    Gpio: marker::Gpio,
+    Index: marker::Index,
+    Mode: marker::Readable,
+{
+    /// Register a handler called from the port interrupt for this pin
+    ///
+    /// A previously registered handler is replaced. The condition triggering
+    /// the interrupt is selected with [`configure_interrupt`](Pin::configure_interrupt).
+    pub fn set_interrupt_handler(&mut self, handler: Handler) {
+        register(self.gpio.port_index(), self.index.index(), Some(handler));
+    }
+
+    /// Remove the handler of this pin
+    ///
+    /// Interrupts of the pin are still acknowledged by the port interrupt,
+    /// but nothing is called anymore.
+    pub fn remove_interrupt_handler(&mut self) {
+        register(self.gpio.port_index(), self.index.index(), None);
+    }
+}
+
+#[avr_device::interrupt(attiny817)]
+fn PORTA_PORT() {
+    // NOTE(unsafe) only the interrupt flags are accessed
+    dispatch(unsafe { &*crate::pac::PORTA::ptr() }, 0);
+}
+
+#[avr_device::interrupt(attiny817)]
+fn PORTB_PORT() {
+    // NOTE(unsafe) only the interrupt flags are accessed
+    dispatch(unsafe { &*crate::pac::PORTB::ptr() }, 1);
+}
+
+#[avr_device::interrupt(attiny817)]
+fn PORTC_PORT() {
+    // NOTE(unsafe) only the interrupt flags are accessed
+    dispatch(unsafe { &*crate::pac::PORTC::ptr() }, 2);
+}
@@ -0,0 +1,173 @@
+//! # Pins with a mode selected at runtime
+//!
+//! The mode of a [`DynamicPin`] isn't encoded in its type, but can be changed
+//! at runtime. Together with the erased pins this allows to decide the pin
+//! assignments by configuration data instead of types.
+//!
+//! ```
+//! let a = dp.PORTA.split();
+//! let mut pin = DynamicPin::new(a.pa3.downgrade().downgrade());
+//!
+//! pin.make_pull_up_input();
+//! let pressed = pin.is_low().unwrap();
+//!
+//! pin.make_push_pull_output();
+//! pin.set_high().unwrap();
+//! ```
+
+use crate::embedded_hal::digital::{self, ErrorType, InputPin, OutputPin};
+
+use super::{marker, private::GpioRegExt, Pin};
+
+/// Mode of a [`DynamicPin`]
+#[derive(ufmt::derive::uDebug, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DynamicMode {
+    /// Input with floating internal resistor
+    FloatingInput,
+    /// Input with enabled internal pull-up
+    PullUpInput,
+    /// Push-pull output
+    Output,
+}
+
+/// Error returned when a [`DynamicPin`] is used in the wrong mode
+#[derive(ufmt::derive::uDebug, Debug, Copy, Clone, PartialEq, Eq)]
+pub struct PinModeError;
+
+impl digital::Error for PinModeError {
+    fn kind(&self) -> digital::ErrorKind {
+        digital::ErrorKind::Other
+    }
+}
+
+/// Pin with a mode which can be changed at runtime
+///
+/// See the [module level documentation](self) for details.
+pub struct DynamicPin<Gpio, Index> {
+    pin: Pin<Gpio, Index, DynamicMode>,
+    mode: DynamicMode,
+}
+
+impl<Gpio, Index> DynamicPin<Gpio, Index>
+where
+    Gpio: marker::Gpio,
+    Index: marker::Index,
+{
+    /// Creates a dynamic pin, starting as a floating input
+    pub fn new<Mode>(pin: Pin<Gpio, Index, Mode>) -> Self {
+        let mut pin = Self {
+            pin: pin.into_mode(),
+            mode: DynamicMode::FloatingInput,
+        };
+        pin.set_mode(DynamicMode::FloatingInput);
+        pin
+    }
+
+    /// Returns the current mode of the pin
+    #[inline]
+    pub fn mode(&self) -> DynamicMode {
+        self.mode
+    }
+
+    /// Changes the mode of the pin
+    pub fn set_mode(&mut self, mode: DynamicMode) {
+        let i = self.pin.index.index();
+        // NOTE(unsafe) atomic writes to stateless registers and read-modify-writes
+        // of the PINCTRL register owned by this pin
+        let port = unsafe { &*self.pin.gpio.ptr() };
+
+        port.enable_input_buffer(i);
+        match mode {
+            DynamicMode::FloatingInput => {
+                port.input(i);
+                port.floating(i);
+            }
+            DynamicMode::PullUpInput => {
+                port.input(i);
+                port.pull_up(i);
+            }
+            DynamicMode::Output => {
+                port.floating(i);
+                port.output(i);
+            }
+        }
+
+        self.mode = mode;
+    }
+
+    /// Configures the pin to operate as a floating input
+    #[inline]
+    pub fn make_floating_input(&mut self) {
+        self.set_mode(DynamicMode::FloatingInput);
+    }
+
+    /// Configures the pin to operate as an input with enabled pull-up
+    #[inline]
+    pub fn make_pull_up_input(&mut self) {
+        self.set_mode(DynamicMode::PullUpInput);
+    }
+
+    /// Configures the pin to operate as a push-pull output
+    #[inline]
+    pub fn make_push_pull_output(&mut self) {
+        self.set_mode(DynamicMode::Output);
+    }
+
+    /// Releases the pin in a statically known mode
+    ///
+    /// The pin is switched back to a floating input.
+    pub fn free(mut self) -> Pin<Gpio, Index, super::Input> {
+        self.make_floating_input();
+        self.pin.into_mode()
+    }
+}
+
+impl<Gpio, Index> ErrorType for DynamicPin<Gpio, Index>
+where
+    Gpio: marker::Gpio,
+    Index: marker::Index,
+{
+    type Error = PinModeError;
+}
+
+impl<Gpio, Index> OutputPin for DynamicPin<Gpio, Index>
+where
+    Gpio: marker::Gpio,
+    Index: marker::Index,
+{
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        if self.mode != DynamicMode::Output {
+            return Err(PinModeError);
+        }
+
+        // NOTE(unsafe) atomic write to a stateless register
+        unsafe { (*self.pin.gpio.ptr()).set_high(self.pin.index.index()) };
+        Ok(())
+    }
+
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        if self.mode != DynamicMode::Output {
+            return Err(PinModeError);
+        }
+
+        // NOTE(unsafe) atomic write to a stateless register
+        unsafe { (*self.pin.gpio.ptr()).set_low(self.pin.index.index()) };
+        Ok(())
+    }
+}
+
+impl<Gpio, Index> InputPin for DynamicPin<Gpio, Index>
+where
+    Gpio: marker::Gpio,
+    Index: marker::Index,
+{
+    fn is_high(&mut self) -> Result<bool, Self::Error> {
+        Ok(!self.is_low()?)
+    }
+
+    fn is_low(&mut self) -> Result<bool, Self::Error> {
+        // The input buffer stays enabled in all modes, so outputs can be read back
+        // NOTE(unsafe) atomic read with no side effects
+        Ok(unsafe { (*self.pin.gpio.ptr()).is_low(self.pin.index.index()) })
+    }
+}
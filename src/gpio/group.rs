@@ -0,0 +1,93 @@
+//! # Groups of output pins
+//!
+//! A [`PinGroup`] collects output pins of the same port and changes all of
+//! them with a single write to the OUTSET, OUTCLR or OUTTGL register. This
+//! guarantees simultaneous edges on all pins, e.g. for the phases of a
+//! stepper motor.
+//!
+//! ```
+//! let b = dp.PORTB.split();
+//! let mut phases = PinGroup::new([
+//!     b.pb0.into_stateless_push_pull_output().downgrade(),
+//!     b.pb1.into_stateless_push_pull_output().downgrade(),
+//!     b.pb4.into_stateless_push_pull_output().downgrade(),
+//!     b.pb5.into_stateless_push_pull_output().downgrade(),
+//! ]);
+//!
+//! phases.write(0b0011);
+//! phases.toggle();
+//! ```
+
+use super::{marker, private::GpioRegExt, Output, Pin, Ux};
+
+/// Group of `N` output pins of a port
+///
+/// See the [module level documentation](self) for details.
+pub struct PinGroup<Gpio, Otype, const N: usize> {
+    pins: [Pin<Gpio, Ux, Output<Otype>>; N],
+    mask: u8,
+}
+
+impl<Gpio, Otype, const N: usize> PinGroup<Gpio, Otype, N>
+where
+    Gpio: marker::GpioStatic,
+{
+    /// Creates a new group from output pins of a port
+    ///
+    /// # Panics
+    ///
+    /// Panics if the group is empty.
+    pub fn new(pins: [Pin<Gpio, Ux, Output<Otype>>; N]) -> Self {
+        assert!(N > 0, "empty pin group");
+
+        let mask = pins.iter().fold(0, |mask, pin| mask | 1 << pin.index.0);
+        Self { pins, mask }
+    }
+
+    /// Drives all pins of the group high
+    #[inline]
+    pub fn set_high(&mut self) {
+        // NOTE(unsafe) atomic write to a stateless register
+        unsafe { (*self.port()).set_high_bits(self.mask) }
+    }
+
+    /// Drives all pins of the group low
+    #[inline]
+    pub fn set_low(&mut self) {
+        // NOTE(unsafe) atomic write to a stateless register
+        unsafe { (*self.port()).set_low_bits(self.mask) }
+    }
+
+    /// Toggles all pins of the group
+    #[inline]
+    pub fn toggle(&mut self) {
+        // NOTE(unsafe) atomic write to a stateless register
+        unsafe { (*self.port()).toggle_bits(self.mask) }
+    }
+
+    /// Drives the pins according to the pattern
+    ///
+    /// Bit `i` of the pattern is the level of the `i`-th pin of the group. All
+    /// pins are changed with a single write to the OUT register.
+    pub fn write(&mut self, pattern: u8) {
+        let bits = self
+            .pins
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| pattern & (1 << i) != 0)
+            .fold(0, |bits, (_, pin)| bits | 1 << pin.index.0);
+
+        // NOTE(unsafe) only the pins of the group are changed
+        unsafe { (*self.port()).write_bits(self.mask, bits) }
+    }
+
+    /// Releases the pins of the group
+    pub fn free(self) -> [Pin<Gpio, Ux, Output<Otype>>; N] {
+        self.pins
+    }
+
+    #[inline(always)]
+    fn port(&self) -> *const Gpio::Reg {
+        self.pins[0].gpio.ptr()
+    }
+}
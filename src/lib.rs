@@ -3,9 +3,12 @@
 #![feature(panic_info_message)]
 #![feature(associated_type_defaults)]
 #![feature(associated_const_equality)]
+#![cfg_attr(feature = "gpio-dispatch", feature(abi_avr_interrupt))]
 #![deny(rustdoc::broken_intra_doc_links)]
 
 pub use embedded_hal;
+#[cfg(feature = "async")]
+pub use embedded_hal_async;
 pub use embedded_hal_bus;
 pub use embedded_hal_nb;
 pub use embedded_io;
@@ -21,11 +24,17 @@ pub mod prelude;
 pub mod time;
 
 pub use avr_device;
+#[doc(hidden)]
+pub use paste;
 
 #[cfg(feature = "attiny817")]
 pub use avr_device::attiny817 as pac;
 
 pub mod ac;
+pub mod adc;
+#[cfg(feature = "async")]
+pub mod asynch;
+pub mod board;
 pub mod bod;
 pub mod ccl;
 pub mod clkctrl;
@@ -71,7 +71,7 @@ use crate::gpio::{Input, Output, Peripheral, Stateless};
 
 // Serial
 use crate::pac::USART0;
-use crate::serial::UartPinset;
+use crate::serial::{TxXdir, UartPinset};
 
 impl IntoMuxedPinset<USART0>
     for (
@@ -127,6 +127,70 @@ impl IntoMuxedPinset<USART0>
     }
 }
 
+impl IntoMuxedPinset<USART0>
+    for (
+        crate::gpio::portb::PB3<Peripheral<USART0>>,
+        crate::gpio::portb::PB2<Peripheral<USART0>>,
+        crate::gpio::portb::PB0<Peripheral<USART0>>,
+    )
+{
+    type Pinset = UartPinset<
+        USART0,
+        crate::gpio::portb::PB3<Input>,
+        TxXdir<
+            crate::gpio::portb::PB2<Output<Stateless>>,
+            crate::gpio::portb::PB0<Output<Stateless>>,
+        >,
+    >;
+
+    fn mux(self, portmux: &Portmux) -> Self::Pinset {
+        portmux.mux.ctrlb().modify(|_r, w| w.usart0().clear_bit());
+        let mut tx = self.1.into_stateless_push_pull_output();
+
+        // Set the TX pin high to turn switch it to idle level
+        // See the pinset without XDIR above
+        tx.set_high().unwrap();
+
+        // Keep the external line driver disabled until the USART takes over
+        let mut xdir = self.2.into_stateless_push_pull_output();
+        xdir.set_low().unwrap();
+
+        UartPinset::new(self.0.into_floating_input(), TxXdir::new(tx, xdir))
+    }
+}
+
+impl IntoMuxedPinset<USART0>
+    for (
+        crate::gpio::porta::PA2<Peripheral<USART0>>,
+        crate::gpio::porta::PA1<Peripheral<USART0>>,
+        crate::gpio::porta::PA4<Peripheral<USART0>>,
+    )
+{
+    type Pinset = UartPinset<
+        USART0,
+        crate::gpio::porta::PA2<Input>,
+        TxXdir<
+            crate::gpio::porta::PA1<Output<Stateless>>,
+            crate::gpio::porta::PA4<Output<Stateless>>,
+        >,
+    >;
+
+    fn mux(self, portmux: &Portmux) -> Self::Pinset {
+        portmux.mux.ctrlb().modify(|_r, w| w.usart0().set_bit());
+        let mut tx = self.1.into_stateless_push_pull_output();
+
+        // Set the TX pin high to turn switch it to idle level
+        // See the pinset without XDIR above
+        tx.set_high().unwrap();
+
+        // Keep the external line driver disabled until the USART takes over
+        let mut xdir = self.2.into_stateless_push_pull_output();
+        xdir.set_low().unwrap();
+
+        UartPinset::new(self.0.into_floating_input(), TxXdir::new(tx, xdir))
+    }
+}
+
 // TWI
 use crate::pac::TWI0;
 use crate::twi::TwiPinset;
@@ -223,6 +287,112 @@ impl IntoMuxedPinset<SPI0>
     }
 }
 
+impl IntoMuxedPinset<SPI0>
+    for (
+        crate::gpio::porta::PA3<Peripheral<SPI0>>,
+        crate::gpio::porta::PA2<Peripheral<SPI0>>,
+        crate::gpio::porta::PA1<Peripheral<SPI0>>,
+        crate::gpio::porta::PA4<Peripheral<SPI0>>,
+    )
+{
+    type Pinset = SpiPinset<
+        SPI0,
+        crate::gpio::porta::PA3<Output<Stateless>>,
+        crate::gpio::porta::PA2<Input>,
+        crate::gpio::porta::PA1<Output<Stateless>>,
+        crate::gpio::porta::PA4<Input>,
+    >;
+
+    fn mux(self, portmux: &Portmux) -> Self::Pinset {
+        portmux.mux.ctrlb().modify(|_r, w| w.spi0().clear_bit());
+        // Turn the pins into stateless outputs
+        // In SPI host mode, this hands over the pin to the SPI peripheral
+        // The ~SS pin is pulled up to avoid falling back to client mode while no other
+        // host is driving it
+        SpiPinset::new_with_ss(
+            self.0.into_stateless_push_pull_output(),
+            self.1.into_floating_input(),
+            self.2.into_stateless_push_pull_output(),
+            self.3.into_pull_up_input(),
+        )
+    }
+}
+
+impl IntoMuxedPinset<SPI0>
+    for (
+        crate::gpio::portc::PC0<Peripheral<SPI0>>,
+        crate::gpio::portc::PC1<Peripheral<SPI0>>,
+        crate::gpio::portc::PC2<Peripheral<SPI0>>,
+        crate::gpio::portc::PC3<Peripheral<SPI0>>,
+    )
+{
+    type Pinset = SpiPinset<
+        SPI0,
+        crate::gpio::portc::PC0<Output<Stateless>>,
+        crate::gpio::portc::PC1<Input>,
+        crate::gpio::portc::PC2<Output<Stateless>>,
+        crate::gpio::portc::PC3<Input>,
+    >;
+
+    fn mux(self, portmux: &Portmux) -> Self::Pinset {
+        portmux.mux.ctrlb().modify(|_r, w| w.spi0().set_bit());
+        // Turn the pins into stateless outputs
+        // In SPI host mode, this hands over the pin to the SPI peripheral
+        // The ~SS pin is pulled up to avoid falling back to client mode while no other
+        // host is driving it
+        SpiPinset::new_with_ss(
+            self.0.into_stateless_push_pull_output(),
+            self.1.into_floating_input(),
+            self.2.into_stateless_push_pull_output(),
+            self.3.into_pull_up_input(),
+        )
+    }
+}
+
+// CLKCTRL
+use crate::clkctrl::XOsc32KPinset;
+use crate::pac::CLKCTRL;
+
+impl IntoMuxedPinset<CLKCTRL>
+    for (
+        crate::gpio::portb::PB3<Peripheral<CLKCTRL>>,
+        crate::gpio::portb::PB2<Peripheral<CLKCTRL>>,
+    )
+{
+    type Pinset = XOsc32KPinset;
+
+    // TOSC1 and TOSC2 can't be moved to other pins
+    fn mux(self, _portmux: &Portmux) -> Self::Pinset {
+        XOsc32KPinset::new(self.0, self.1)
+    }
+}
+
+// AC
+use crate::ac::AcOutputPinset;
+use crate::pac::AC0;
+
+impl IntoMuxedPinset<AC0> for crate::gpio::porta::PA5<Output<Stateless>> {
+    type Pinset = AcOutputPinset<AC0, crate::gpio::porta::PA5<Output<Stateless>>>;
+
+    // The output of AC0 can't be moved to another pin
+    fn mux(self, _portmux: &Portmux) -> Self::Pinset {
+        AcOutputPinset::new(self)
+    }
+}
+
+// DAC
+use crate::dac::DacOutputPinset;
+use crate::pac::DAC0;
+
+impl IntoMuxedPinset<DAC0> for crate::gpio::porta::PA6<Output<Stateless>> {
+    type Pinset = DacOutputPinset<DAC0, crate::gpio::porta::PA6<Output<Stateless>>>;
+
+    // The output of DAC0 can't be moved to another pin
+    fn mux(self, _portmux: &Portmux) -> Self::Pinset {
+        DacOutputPinset::new(self)
+    }
+}
+
 // CCL
 use crate::ccl::{CclLutOutputPinset, LUT0, LUT1};
 
@@ -374,3 +544,25 @@ impl IntoMuxedPinset<EVSYS> for crate::gpio::portc::PC2<Peripheral<EVSYS>> {
         EventOutputPinset::new(self)
     }
 }
+
+// TCD
+use crate::pac::TCD0;
+use crate::timer::tcd::TcdPinset;
+
+impl IntoMuxedPinset<TCD0> for crate::gpio::porta::PA4<Output<Stateless>> {
+    type Pinset = TcdPinset<TCD0, crate::gpio::porta::PA4<Output<Stateless>>, C1>;
+
+    fn mux(self, _portmux: &Portmux) -> Self::Pinset {
+        // WOA can't be muxed to another pin
+        TcdPinset::new(self)
+    }
+}
+
+impl IntoMuxedPinset<TCD0> for crate::gpio::porta::PA5<Output<Stateless>> {
+    type Pinset = TcdPinset<TCD0, crate::gpio::porta::PA5<Output<Stateless>>, C2>;
+
+    fn mux(self, _portmux: &Portmux) -> Self::Pinset {
+        // WOB can't be muxed to another pin
+        TcdPinset::new(self)
+    }
+}
@@ -11,6 +11,7 @@
 pub use fugit::ExtU32 as _fugit_DurationExtU32;
 pub use fugit::RateExtU32 as _fugit_RateExtU32;
 
+pub use crate::adc::AdcExt as _atxtiny_hal_adc_AdcExt;
 pub use crate::clkctrl::{CLKCTRLExt as _atxtiny_hal_clkctrl_ClkCtrlExt, MainClkSrc};
 pub use crate::gpio::GpioExt as _atxtiny_hal_gpio_GpioExt;
 pub use crate::nvmctrl::NvmctrlExt as _atxtiny_hal_nvmctrl_NvmctrlExt;
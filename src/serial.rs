@@ -7,6 +7,8 @@
 //! `embedded-io`:
 //! * [`Read (embedded-io)`]
 //! * [`Write (embedded-io)`]
+//! * [`ReadReady (embedded-io)`]
+//! * [`WriteReady (embedded-io)`]
 //!
 //! `embedded-hal-nb`:
 //! * [`Read (embedded-hal-nb)`]
@@ -14,29 +16,83 @@
 //!
 //! [`Read (embedded-io)`]: embedded_io::Read
 //! [`Write (embedded-io)`]: embedded_io::Write
+//! [`ReadReady (embedded-io)`]: embedded_io::ReadReady
+//! [`WriteReady (embedded-io)`]: embedded_io::WriteReady
 //!
 //! [`Read (embedded-hal-nb)`]: embedded_hal_nb::serial::Read
 //! [`Write (embedded-hal-nb)`]: embedded_hal_nb::serial::Write
 
-use core::{fmt, marker::PhantomData, ops::Deref};
+use core::{convert::Infallible, fmt, marker::PhantomData, ops::Deref};
 
 use crate::embedded_hal_nb::serial::{ErrorType as NbErrorType, Read as NbRead, Write as NbWrite};
-use crate::embedded_io::{ErrorType as IoErrorType, Read as IoRead, Write as IoWrite};
+use crate::embedded_io::{
+    ErrorType as IoErrorType, Read as IoRead, ReadReady as IoReadReady, Write as IoWrite,
+    WriteReady as IoWriteReady,
+};
 use crate::pac::usart0::{ctrlb::RXMODE_A, RegisterBlock};
 
-use crate::{clkctrl::Clocks, time::*, Toggle};
+use crate::{clkctrl::Clocks, embedded_hal::delay::DelayNs, gpio::Release, time::*, Toggle};
 
 #[cfg(feature = "enumset")]
 use enumset::{EnumSet, EnumSetType};
 
 pub mod config;
+pub mod flow;
+pub mod mpcm;
 
 /// TX pin
-pub trait TxPin<Usart>: crate::private::Sealed {}
+pub trait TxPin<Usart>: crate::private::Sealed {
+    #[doc(hidden)]
+    const HAS_XDIR: bool = false;
+}
 
 /// RX pin
 pub trait RxPin<Usart>: crate::private::Sealed {}
 
+/// XDIR pin
+///
+/// Used in RS-485 mode to control the transmit enable input of an
+/// external line driver.
+pub trait XdirPin<Usart>: crate::private::Sealed {}
+
+/// A TX pin paired with an XDIR pin, the transmitter of a RS-485 pinset
+///
+/// The XDIR pin is owned by the [`Tx`] half when splitting the [`Serial`], as
+/// it is driven by the transmitter.
+pub struct TxXdir<TX, XDIR> {
+    tx: TX,
+    xdir: XDIR,
+}
+
+impl<TX, XDIR> TxXdir<TX, XDIR> {
+    pub(crate) fn new(tx: TX, xdir: XDIR) -> Self {
+        TxXdir { tx, xdir }
+    }
+
+    /// Returns the TX and XDIR pins
+    pub fn free(self) -> (TX, XDIR) {
+        (self.tx, self.xdir)
+    }
+}
+
+impl<TX, XDIR> crate::private::Sealed for TxXdir<TX, XDIR> {}
+
+impl<Usart, TX, XDIR> TxPin<Usart> for TxXdir<TX, XDIR>
+where
+    TX: TxPin<Usart>,
+    XDIR: XdirPin<Usart>,
+{
+    const HAS_XDIR: bool = true;
+}
+
+impl<TX: Release, XDIR: Release> Release for TxXdir<TX, XDIR> {
+    type Released = (TX::Released, XDIR::Released);
+
+    fn release(self) -> Self::Released {
+        (self.tx.release(), self.xdir.release())
+    }
+}
+
 /// Pin set for the port multiplexer
 pub struct UartPinset<Usart, Rx: RxPin<Usart>, Tx: TxPin<Usart>> {
     _usart: PhantomData<Usart>,
@@ -57,9 +113,22 @@ where
         }
     }
 
+    /// Returns the pins in the modes used by the peripheral
     pub fn free(self) -> (Rx, Tx) {
         (self.rx, self.tx)
     }
+
+    /// Releases the pins back to GPIO
+    ///
+    /// The pins are converted back into floating inputs, so they can be
+    /// reused after the peripheral has been shut down.
+    pub fn release(self) -> (Rx::Released, Tx::Released)
+    where
+        Rx: Release,
+        Tx: Release,
+    {
+        (self.rx.release(), self.tx.release())
+    }
 }
 
 /// Status events.
@@ -186,6 +255,17 @@ pub enum Error {
     ///
     /// This error is thrown by hardware when a parity error occurs in receiver mode.
     Parity,
+    /// Break received
+    ///
+    /// A framing error where all data bits are zero, caused by the line being held
+    /// low for at least a whole frame.
+    Break,
+    /// Inconsistent synchronization field
+    ///
+    /// In an auto-baud mode, the synchronization field following a break was too short
+    /// or too long to give a valid baud setting. In [`AutoBaudMode::Lin`] this is also
+    /// reported if the synchronization character differs from 0x55.
+    InconsistentSynchronizationField,
 }
 
 impl crate::embedded_io::Error for Error {
@@ -195,6 +275,8 @@ impl crate::embedded_io::Error for Error {
             Error::Framing => ErrorKind::Other,
             Error::Overrun => ErrorKind::Other,
             Error::Parity => ErrorKind::Other,
+            Error::Break => ErrorKind::Other,
+            Error::InconsistentSynchronizationField => ErrorKind::Other,
         }
     }
 }
@@ -206,10 +288,108 @@ impl crate::embedded_hal_nb::serial::Error for Error {
             Error::Framing => ErrorKind::Other,
             Error::Overrun => ErrorKind::Other,
             Error::Parity => ErrorKind::Other,
+            Error::Break => ErrorKind::Other,
+            Error::InconsistentSynchronizationField => ErrorKind::Other,
         }
     }
 }
 
+/// Receive status of a single received byte
+///
+/// Returned together with the data by [`Serial::read_with_status`] and
+/// [`Rx::read_with_status`].
+#[derive(ufmt::derive::uDebug, Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct ReceiveStatus {
+    /// The stop bit of the frame was zero
+    #[doc(alias = "FERR")]
+    pub frame_error: bool,
+    /// The frame error was caused by a break, all data bits were zero as well
+    pub break_received: bool,
+    /// The parity of the frame didn't match
+    #[doc(alias = "PERR")]
+    pub parity_error: bool,
+    /// At least one frame was lost, because the receive buffer was full
+    #[doc(alias = "BUFOVF")]
+    pub buffer_overflow: bool,
+}
+
+impl ReceiveStatus {
+    /// Returns `true` if the byte was received without any error
+    #[inline]
+    pub fn is_ok(&self) -> bool {
+        !(self.frame_error || self.parity_error || self.buffer_overflow)
+    }
+
+    /// Returns the most severe error of the byte, if any
+    ///
+    /// The precedence is the same as for the errors returned by the `Read` implementations.
+    #[inline]
+    pub fn error(&self) -> Option<Error> {
+        if self.parity_error {
+            Some(Error::Parity)
+        } else if self.frame_error {
+            if self.break_received {
+                Some(Error::Break)
+            } else {
+                Some(Error::Framing)
+            }
+        } else if self.buffer_overflow {
+            Some(Error::Overrun)
+        } else {
+            None
+        }
+    }
+}
+
+/// Auto-baud mode of the receiver
+///
+/// In both modes the receiver measures the length of a synchronization field
+/// following a break and updates the baud rate accordingly.
+#[derive(ufmt::derive::uDebug, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AutoBaudMode {
+    /// Generic auto-baud mode
+    ///
+    /// Any synchronization field with the length of 8 bits is accepted.
+    #[doc(alias = "GENAUTO")]
+    Generic,
+    /// LIN constrained auto-baud mode
+    ///
+    /// The synchronization character must be 0x55 and the new baud rate may
+    /// only deviate by ±15% from the currently configured one, as required by
+    /// the LIN specification.
+    #[doc(alias = "LINAUTO")]
+    Lin,
+}
+
+/// Error returned when the requested baud rate can't be generated
+#[derive(ufmt::derive::uDebug, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BaudRateError {
+    /// The baud rate is too high or too low for the peripheral clock
+    OutOfRange,
+    /// The achieved baud rate deviates more than the configured tolerance
+    Inaccurate {
+        /// Deviation of the best achievable baud rate in per mille
+        deviation: u16,
+    },
+}
+
+/// Error returned when a [`Config`](config::Config) can't be applied
+#[derive(ufmt::derive::uDebug, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ConfigError {
+    /// The baud rate can't be generated
+    BaudRate(BaudRateError),
+    /// [`Rs485Mode::External`](config::Rs485Mode::External) needs a pinset
+    /// including the XDIR pin, the external line driver would never be
+    /// enabled otherwise
+    MissingXdir,
+}
+
+impl From<BaudRateError> for ConfigError {
+    fn from(error: BaudRateError) -> Self {
+        ConfigError::BaudRate(error)
+    }
+}
+
 /// An error that can be returned by the [`ufmt::uWrite`] trait
 #[allow(non_camel_case_types)]
 #[derive(ufmt::derive::uDebug, Debug, Default, Copy, Clone, PartialEq, Eq)]
@@ -222,6 +402,10 @@ pub struct uWriteError;
 pub struct Serial<Usart, Pinset> {
     usart: Usart,
     pinset: Pinset,
+    /// Data has been written since the last completed [`flush`](IoWrite::flush)
+    transmitting: bool,
+    /// The configured guard time
+    guard_time_ns: u32,
 }
 
 mod split {
@@ -239,6 +423,8 @@ mod split {
     pub struct Tx<Usart, Pin> {
         usart: Usart,
         pub(crate) pin: Pin,
+        transmitting: bool,
+        guard_time_ns: u32,
     }
 
     impl<Usart, Pin> Tx<Usart, Pin>
@@ -246,13 +432,26 @@ mod split {
         Usart: Instance,
         Pin: super::TxPin<Usart>,
     {
-        pub(crate) fn new(usart: Usart, pin: Pin) -> Self {
-            Tx { usart, pin }
+        pub(crate) fn new(usart: Usart, pin: Pin, transmitting: bool, guard_time_ns: u32) -> Self {
+            Tx {
+                usart,
+                pin,
+                transmitting,
+                guard_time_ns,
+            }
         }
 
-        /// Destruct [`Tx`] to regain access to underlying USART and pin.
-        pub(crate) fn free(self) -> (Usart, Pin) {
-            (self.usart, self.pin)
+        /// Destruct [`Tx`] to regain access to underlying USART, pin,
+        /// whether data is still being transmitted and the guard time.
+        pub(crate) fn free(self) -> (Usart, Pin, bool, u32) {
+            (self.usart, self.pin, self.transmitting, self.guard_time_ns)
+        }
+
+        /// Wait for the configured [guard time](super::config::Config::guard_time)
+        ///
+        /// See [`Serial::wait_guard_time`](super::Serial::wait_guard_time).
+        pub fn wait_guard_time(&self, delay: &mut impl super::DelayNs) {
+            delay.delay_ns(self.guard_time_ns);
         }
     }
 
@@ -288,6 +487,15 @@ mod split {
         pub(crate) unsafe fn usart_mut(&mut self) -> &mut Usart {
             &mut self.usart
         }
+
+        /// Get a reference to internal usart peripheral and the transmission state
+        ///
+        /// # Saftey
+        ///
+        /// Same as in [`Self::usart()`].
+        pub(crate) unsafe fn usart_transmitting(&mut self) -> (&Usart, &mut bool) {
+            (&self.usart, &mut self.transmitting)
+        }
     }
 
     impl<Usart, Pin> Rx<Usart, Pin>
@@ -352,37 +560,69 @@ where
     TX: TxPin<Usart>,
 {
     /// Configures a USART peripheral to provide serial communication
+    ///
+    /// # Panics
+    ///
+    /// Panics if the configuration is rejected, see [`Serial::try_new`].
     pub fn new<Config>(
         usart: Usart,
         pinset: UartPinset<Usart, RX, TX>,
         config: Config,
         clocks: Clocks,
     ) -> Self
+    where
+        Config: Into<config::Config>,
+    {
+        match Self::try_new(usart, pinset, config, clocks) {
+            Ok(serial) => serial,
+            Err(ConfigError::BaudRate(_)) => panic!("impossible baud rate"),
+            Err(ConfigError::MissingXdir) => {
+                panic!("RS-485 external mode requires a pinset with an XDIR pin")
+            }
+        }
+    }
+
+    /// Configures a USART peripheral to provide serial communication
+    ///
+    /// The double speed mode is only used if the baud rate can't be generated
+    /// within the configured tolerance in normal mode, as it makes the receiver
+    /// less tolerant to baud rate differences between the communication partners.
+    /// Without a [tolerance](config::Config::baud_tolerance), the normal mode
+    /// is used whenever the baud rate is low enough for it.
+    pub fn try_new<Config>(
+        usart: Usart,
+        pinset: UartPinset<Usart, RX, TX>,
+        config: Config,
+        clocks: Clocks,
+    ) -> Result<Self, ConfigError>
     where
         Config: Into<config::Config>,
     {
         let config = config.into();
 
+        if config.rs485 == config::Rs485Mode::External && !TX::HAS_XDIR {
+            return Err(ConfigError::MissingXdir);
+        }
+
+        // Calculate the baud rate
+        let f_per = Usart::clock(&clocks);
+        let f_per = if config.osc_error_compensation {
+            clocks.compensate(f_per)
+        } else {
+            f_per
+        }
+        .raw();
+        let (rxmode, brr) = calculate_baud(f_per, config.baudrate.0, config.baud_tolerance)?;
+        let guard_time_ns =
+            u32::from(config.guard_time).saturating_mul(1_000_000_000 / config.baudrate.0);
+
         // Disable the transmitter and receiver
         usart
             .ctrlb()
             .modify(|_, w| w.rxen().clear_bit().txen().clear_bit());
 
-        // Calculate and set the baud rate
-        let baudrate = config.baudrate.0;
-        let f_per = Usart::clock(&clocks).raw();
-
-        let (rxmode, brr) = if baudrate > (f_per / 16) {
-            (RXMODE_A::CLK2X, (4 * f_per) / (baudrate / 2))
-        } else {
-            (RXMODE_A::NORMAL, (4 * f_per) / baudrate)
-        };
-
-        // FIXME: return error
-        assert!(brr >= 64, "impossible baud rate");
-
         // FIXME: does the 16 bit write work correctly on the AVR mega cores?
-        usart.baud().write(|w| w.bits(brr as u16));
+        usart.baud().write(|w| w.bits(brr));
 
         // Asynchronous mode, Parity, Stopbits and character size according to config
         usart.ctrlc().write(|w| {
@@ -412,7 +652,7 @@ where
                     .abeie()
                     .clear_bit() // Auto-Baud Error Interrupt Enable
                     .rs485()
-                    .off()
+                    .variant(config.rs485.into())
             }, // RS-485 Mode
         );
 
@@ -431,7 +671,21 @@ where
             }, // Set the baudrate generator mode
         );
 
-        Self { usart, pinset }
+        Ok(Self {
+            usart,
+            pinset,
+            transmitting: false,
+            guard_time_ns,
+        })
+    }
+
+    /// Wait for the configured [guard time](config::Config::guard_time)
+    ///
+    /// On a half-duplex RS-485 bus, call this after a frame has been received
+    /// and before answering it, so the line driver of the other node is
+    /// disabled before the own one is enabled by the transmitter.
+    pub fn wait_guard_time(&self, delay: &mut impl DelayNs) {
+        delay.delay_ns(self.guard_time_ns);
     }
 
     /// Get access to the underlying register block.
@@ -476,11 +730,13 @@ where
         RX: RxPin<Usart>,
         TX: TxPin<Usart>,
     {
-        let (usart, tx_pin) = tx.free();
+        let (usart, tx_pin, transmitting, guard_time_ns) = tx.free();
         let rx_pin = rx.free();
         Self {
             usart,
             pinset: UartPinset::new(rx_pin, tx_pin),
+            transmitting,
+            guard_time_ns,
         }
     }
 }
@@ -527,6 +783,16 @@ where
         Some(bit9 << 8 | (self.usart.rxdatal().read().bits() as u16))
     }
 
+    /// Read a received byte together with its error flags
+    ///
+    /// Unlike the `Read` implementations, which discard a byte received with an
+    /// error, this always returns the received byte and leaves it up to the
+    /// caller to decide how to handle the reported [`ReceiveStatus`]. On a buffer
+    /// overflow, the bytes still waiting in the receive buffer are not flushed.
+    pub fn read_with_status(&mut self) -> nb::Result<(u8, ReceiveStatus), Infallible> {
+        read_with_status(&self.usart)
+    }
+
     /// Enable the interrupt for the specified [`Interrupt`].
     #[inline]
     pub fn enable_interrupt(&mut self, interrupt: Interrupt) {
@@ -542,16 +808,7 @@ where
     /// Enable or disable the interrupt for the specified [`Interrupt`].
     #[inline]
     pub fn configure_interrupt(&mut self, interrupt: Interrupt, enable: impl Into<Toggle>) {
-        // Do a round way trip to be convert Into<Toggle> -> bool
-        let enable: Toggle = enable.into();
-        let enable: bool = enable.into();
-        match interrupt {
-            Interrupt::ReceiveComplete => self.usart.ctrla().modify(|_, w| w.rxcie().bit(enable)),
-            Interrupt::TransmitComplete => self.usart.ctrla().modify(|_, w| w.txcie().bit(enable)),
-            Interrupt::DataRegisterEmpty => self.usart.ctrla().modify(|_, w| w.dreie().bit(enable)),
-            Interrupt::ReceiveStart => self.usart.ctrla().modify(|_, w| w.rxsie().bit(enable)),
-            Interrupt::AutoBaudError => self.usart.ctrla().modify(|_, w| w.abeie().bit(enable)),
-        };
+        configure_interrupt(&self.usart, interrupt, enable);
     }
 
     /// Enable or disable interrupt for the specified [`Interrupt`]s.
@@ -659,10 +916,77 @@ where
         self.usart.status().write(|w| unsafe { w.bits(u8::MAX) });
     }
 
-    /// Set the baud rate generator mode to automatic baud rate generation
+    /// Set the baud rate generator mode to generic automatic baud rate generation
+    ///
+    /// This is the same as calling [`Serial::set_autobaud_mode`] with [`AutoBaudMode::Generic`].
     #[inline]
     pub fn enable_autobaud(&mut self) {
-        self.usart.ctrlb().write(|w| w.rxmode().genauto());
+        self.set_autobaud_mode(AutoBaudMode::Generic);
+    }
+
+    /// Set the baud rate generator mode to automatic baud rate generation
+    ///
+    /// Once a break and a valid synchronization field are received, the baud rate
+    /// is updated by hardware. Use [`Serial::poll_autobaud`] to check for this.
+    ///
+    /// As the auto-baud modes don't support double speed operation, the baud rate
+    /// configured when creating the [`Serial`] should be low enough not to require it.
+    #[inline]
+    pub fn set_autobaud_mode(&mut self, mode: AutoBaudMode) {
+        self.usart.ctrlb().modify(|_, w| match mode {
+            AutoBaudMode::Generic => w.rxmode().genauto(),
+            AutoBaudMode::Lin => w.rxmode().linauto(),
+        });
+    }
+
+    /// Return to a fixed baud rate
+    ///
+    /// The baud rate is kept at the value last detected by the auto-baud logic.
+    #[inline]
+    pub fn disable_autobaud(&mut self) {
+        self.usart.ctrlb().modify(|_, w| w.rxmode().normal());
+    }
+
+    /// Check for the result of the auto-baud detection
+    ///
+    /// Returns `Ok(())` once a break and a valid synchronization field have been
+    /// detected and the baud rate has been updated by hardware. An inconsistent
+    /// synchronization field is reported as [`Error::InconsistentSynchronizationField`],
+    /// the receiver then waits for the next break.
+    ///
+    /// Both [`Event::BreakDetected`] and [`Event::InconsistentSynchronizationField`]
+    /// are cleared when they are reported.
+    pub fn poll_autobaud(&mut self) -> nb::Result<(), Error> {
+        let status = self.usart.status().read();
+
+        if status.isfif().bit_is_set() {
+            self.clear_event(Event::InconsistentSynchronizationField);
+            Err(nb::Error::Other(Error::InconsistentSynchronizationField))
+        } else if status.bdf().bit_is_set() {
+            self.clear_event(Event::BreakDetected);
+            Ok(())
+        } else {
+            Err(nb::Error::WouldBlock)
+        }
+    }
+
+    /// Returns the currently configured baud rate
+    ///
+    /// After a successful auto-baud detection, this is the baud rate learned from
+    /// the synchronization field.
+    ///
+    /// The frequency error of the internal oscillator is taken into account, if it
+    /// is known by the `clocks`.
+    pub fn baudrate(&self, clocks: Clocks) -> Bps {
+        let f_per = clocks.compensate(Usart::clock(&clocks)).raw();
+        let brr = self.usart.baud().read().bits() as u32;
+
+        let baudrate = match self.usart.ctrlb().read().rxmode().variant() {
+            RXMODE_A::CLK2X => (8 * f_per) / brr,
+            _ => (4 * f_per) / brr,
+        };
+
+        baudrate.bps()
     }
 
     /// Enable or disable the interrupt for the break field detection.
@@ -675,17 +999,261 @@ where
         self.usart.status().write(|w| w.wfb().bit(enable));
     }
 
+    /// Enable or disable the start-of-frame detection
+    ///
+    /// When enabled, a falling edge on the RX pin wakes up the device from standby
+    /// sleep mode and sets [`Event::ReceiveStart`], which can be used to detect the
+    /// beginning of a break.
+    #[doc(alias = "SFDEN")]
+    #[inline]
+    pub fn set_start_of_frame_detection(&mut self, enable: impl Into<Toggle>) {
+        // Do a round way trip to be convert Into<Toggle> -> bool
+        let enable: Toggle = enable.into();
+        let enable: bool = enable.into();
+
+        self.usart.ctrlb().modify(|_, w| w.sfden().bit(enable));
+    }
+
+    /// Transmit a break
+    ///
+    /// The line is held low for `bits` bit periods at the current baud rate, at
+    /// least for the 9 bit periods of a regular frame. This is done by sending a
+    /// zero byte with a temporarily reduced baud rate, so the break length is
+    /// limited by the largest possible baud register value.
+    ///
+    /// This blocks until all pending data and the break have been transmitted.
+    /// A break received back by the own receiver is reported as [`Error::Break`].
+    pub fn send_break(&mut self, bits: u8) {
+        let brr = self.usart.baud().read().bits();
+        let break_brr = (brr as u32 * bits.max(9) as u32).div_ceil(9);
+        let break_brr = break_brr.min(u16::MAX as u32) as u16;
+
+        // Changing the baud rate while a frame is shifted out would corrupt it
+        while !is_transmission_complete(&self.usart, &mut self.transmitting) {}
+
+        self.usart.baud().write(|w| w.bits(break_brr));
+        transmit(&self.usart, &mut self.transmitting, 0);
+        while !is_transmission_complete(&self.usart, &mut self.transmitting) {}
+
+        self.usart.baud().write(|w| w.bits(brr));
+    }
+
     /// Enable or disable the multiprocessor communication mode.
+    ///
+    /// See [`mpcm::Multiprocessor`] for a higher level abstraction.
     #[inline]
     pub fn set_multiprocessor_communication(&mut self, enable: impl Into<Toggle>) {
         // Do a round way trip to be convert Into<Toggle> -> bool
         let enable: Toggle = enable.into();
         let enable: bool = enable.into();
 
-        self.usart.ctrlb().write(|w| w.mpcm().bit(enable));
+        self.usart.ctrlb().modify(|_, w| w.mpcm().bit(enable));
     }
 }
 
+/// Write a byte into the transmit buffer
+///
+/// TXCIF is cleared after the data has been written, so it is only set again once
+/// this byte has been shifted out. Clearing it before writing the data could miss
+/// the completion of a previous byte in between and leave a stale flag behind.
+fn transmit<Usart>(usart: &Usart, transmitting: &mut bool, word: u8)
+where
+    Usart: Instance,
+{
+    usart.txdatal().write(|w| w.bits(word));
+    usart.status().write(|w| w.txcif().set_bit());
+    *transmitting = true;
+}
+
+/// Check if all written data has left the transmit shift register
+///
+/// The `transmitting` state is needed, as TXCIF is never set if nothing has been
+/// transmitted yet.
+fn is_transmission_complete<Usart>(usart: &Usart, transmitting: &mut bool) -> bool
+where
+    Usart: Instance,
+{
+    if *transmitting && usart.status().read().txcif().bit_is_set() {
+        *transmitting = false;
+    }
+
+    !*transmitting
+}
+
+/// Calculate the receiver mode and the BAUD register value for a baud rate
+///
+/// The normal mode is preferred over the double speed mode, as long as the
+/// deviation from the requested baud rate is within the tolerance, if there
+/// is one.
+fn calculate_baud(
+    f_per: u32,
+    baudrate: u32,
+    tolerance: Option<u16>,
+) -> Result<(RXMODE_A, u16), BaudRateError> {
+    // The BAUD register has 6 fractional bits, which results in 4 * f_per / baudrate
+    // with 16 samples per bit in normal mode and 8 * f_per / baudrate with 8 samples
+    // per bit in double speed mode.
+    let candidates = [(RXMODE_A::NORMAL, 4), (RXMODE_A::CLK2X, 8)];
+
+    let mut best: Option<u16> = None;
+    for (rxmode, factor) in candidates {
+        let clk = factor * f_per;
+        let brr = (clk + baudrate / 2) / baudrate;
+
+        if !(64..=u16::MAX as u32).contains(&brr) {
+            continue;
+        }
+
+        let achieved = clk / brr;
+        let deviation = (achieved.abs_diff(baudrate) as u64 * 1000 / baudrate as u64) as u16;
+
+        if tolerance.is_none_or(|tolerance| deviation <= tolerance) {
+            return Ok((rxmode, brr as u16));
+        }
+
+        best = Some(best.map_or(deviation, |b| b.min(deviation)));
+    }
+
+    match best {
+        Some(deviation) => Err(BaudRateError::Inaccurate { deviation }),
+        None => Err(BaudRateError::OutOfRange),
+    }
+}
+
+/// Enable or disable an interrupt in the shared CTRLA register
+///
+/// The read-modify-write is done in a critical section, as the [`Rx`] and [`Tx`]
+/// halves of a split [`Serial`] may live in different contexts and both
+/// modify this register.
+fn configure_interrupt<Usart>(usart: &Usart, interrupt: Interrupt, enable: impl Into<Toggle>)
+where
+    Usart: Instance,
+{
+    // Do a round way trip to be convert Into<Toggle> -> bool
+    let enable: Toggle = enable.into();
+    let enable: bool = enable.into();
+
+    avr_device::interrupt::free(|_| match interrupt {
+        Interrupt::ReceiveComplete => usart.ctrla().modify(|_, w| w.rxcie().bit(enable)),
+        Interrupt::TransmitComplete => usart.ctrla().modify(|_, w| w.txcie().bit(enable)),
+        Interrupt::DataRegisterEmpty => usart.ctrla().modify(|_, w| w.dreie().bit(enable)),
+        Interrupt::ReceiveStart => usart.ctrla().modify(|_, w| w.rxsie().bit(enable)),
+        Interrupt::AutoBaudError => usart.ctrla().modify(|_, w| w.abeie().bit(enable)),
+    });
+}
+
+impl<Usart, Pin> Rx<Usart, Pin>
+where
+    Usart: Instance,
+    Pin: RxPin<Usart>,
+{
+    /// Enable or disable the receive complete interrupt
+    ///
+    /// This allows to move the [`Rx`] half into an interrupt handler and read
+    /// each received byte there, while the [`Tx`] half stays in the main loop.
+    ///
+    /// ```
+    /// let (mut rx, tx) = serial.split();
+    /// rx.configure_receive_interrupt(true);
+    ///
+    /// // Move rx into a static used by the USART0_RXC interrupt handler
+    /// ```
+    #[doc(alias = "RXCIE")]
+    #[inline]
+    pub fn configure_receive_interrupt(&mut self, enable: impl Into<Toggle>) {
+        configure_interrupt(
+            unsafe { self.usart_mut() },
+            Interrupt::ReceiveComplete,
+            enable,
+        );
+    }
+
+    /// Read a received byte together with its error flags
+    ///
+    /// See [`Serial::read_with_status`].
+    #[inline]
+    pub fn read_with_status(&mut self) -> nb::Result<(u8, ReceiveStatus), Infallible> {
+        read_with_status(unsafe { self.usart_mut() })
+    }
+
+    /// Check if new data is available in the receive buffer
+    #[doc(alias = "RXCIF")]
+    #[inline]
+    pub fn is_data_available(&mut self) -> bool {
+        unsafe { self.usart_mut() }
+            .status()
+            .read()
+            .rxcif()
+            .bit_is_set()
+    }
+}
+
+impl<Usart, Pin> Tx<Usart, Pin>
+where
+    Usart: Instance,
+    Pin: TxPin<Usart>,
+{
+    /// Enable or disable the data register empty interrupt
+    ///
+    /// The interrupt fires as long as the transmit buffer can accept new data,
+    /// so it must be disabled again in the interrupt handler once there is
+    /// nothing left to send.
+    #[doc(alias = "DREIE")]
+    #[inline]
+    pub fn configure_data_register_empty_interrupt(&mut self, enable: impl Into<Toggle>) {
+        configure_interrupt(
+            unsafe { self.usart() },
+            Interrupt::DataRegisterEmpty,
+            enable,
+        );
+    }
+
+    /// Enable or disable the transmit complete interrupt
+    ///
+    /// As executing the interrupt clears TXCIF, `flush` can't be used while the
+    /// interrupt is enabled.
+    #[doc(alias = "TXCIE")]
+    #[inline]
+    pub fn configure_transmit_complete_interrupt(&mut self, enable: impl Into<Toggle>) {
+        configure_interrupt(unsafe { self.usart() }, Interrupt::TransmitComplete, enable);
+    }
+
+    /// Check if the transmit buffer can accept new data
+    #[doc(alias = "DREIF")]
+    #[inline]
+    pub fn is_ready(&self) -> bool {
+        unsafe { self.usart() }.status().read().dreif().bit_is_set()
+    }
+}
+
+/// Read a byte together with its error flags
+///
+/// Shared between [`Rx::read_with_status()`] and [`Serial::read_with_status()`]
+fn read_with_status<Usart>(usart: &Usart) -> nb::Result<(u8, ReceiveStatus), Infallible>
+where
+    Usart: Instance,
+{
+    // The error flags belong to the byte in RXDATAL and must be read before it
+    let rxdatah = usart.rxdatah().read();
+
+    if rxdatah.rxcif().bit_is_clear() {
+        return Err(nb::Error::WouldBlock);
+    }
+
+    let data = usart.rxdatal().read().bits() as u8;
+    let frame_error = rxdatah.ferr().bit_is_set();
+
+    let status = ReceiveStatus {
+        frame_error,
+        // A break holds the line low for the whole frame, so all data bits are zero
+        break_received: frame_error && data == 0 && rxdatah.data8().bit_is_clear(),
+        parity_error: rxdatah.perr().bit_is_set(),
+        buffer_overflow: rxdatah.bufovf().bit_is_set(),
+    };
+
+    Ok((data, status))
+}
+
 /// Implementation of the [`embedded_hal::serial::Read`] trait
 /// shared between [`Rx::read()`] and [`Serial::read()`]
 fn eh_read<Usart>(usart: &mut Usart) -> Result<Option<u8>, Error>
@@ -700,8 +1268,12 @@ where
         Err(Error::Parity)
     } else if rxdatah.ferr().bit_is_set() {
         // Read and remove the data that caused this error
-        let _ = usart.rxdatal().read();
-        Err(Error::Framing)
+        // A break holds the line low for the whole frame, so all data bits are zero
+        if usart.rxdatal().read().bits() == 0 && rxdatah.data8().bit_is_clear() {
+            Err(Error::Break)
+        } else {
+            Err(Error::Framing)
+        }
     } else if rxdatah.bufovf().bit_is_set() {
         // Flush the receive data
         //
@@ -806,6 +1378,17 @@ where
     }
 }
 
+impl<Usart, RX, TX> IoReadReady for Serial<Usart, UartPinset<Usart, RX, TX>>
+where
+    Usart: Instance,
+    RX: RxPin<Usart>,
+    TX: TxPin<Usart>,
+{
+    fn read_ready(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.usart.status().read().rxcif().bit_is_set())
+    }
+}
+
 impl<Usart, Pin> IoErrorType for Rx<Usart, Pin>
 where
     Usart: Instance,
@@ -853,6 +1436,16 @@ where
     }
 }
 
+impl<Usart, Pin> IoReadReady for Rx<Usart, Pin>
+where
+    Usart: Instance,
+    Pin: RxPin<Usart>,
+{
+    fn read_ready(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.is_data_available())
+    }
+}
+
 impl<Usart, RX, TX> IoWrite for Serial<Usart, UartPinset<Usart, RX, TX>>
 where
     Usart: Instance,
@@ -862,19 +1455,36 @@ where
     fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
         for b in buf {
             while self.usart.status().read().dreif().bit_is_clear() {}
-            self.usart.txdatal().write(|w| w.bits(*b));
+            transmit(&self.usart, &mut self.transmitting, *b);
         }
 
         Ok(buf.len())
     }
 
+    /// Waits until all data has been shifted out of the transmit shift register
+    ///
+    /// Afterwards the transmitter can be safely disabled or the device can enter
+    /// sleep without cutting off the last frame.
+    ///
+    /// TXCIF is cleared by hardware when the transmit complete interrupt is executed,
+    /// so this must not be used while [`Interrupt::TransmitComplete`] is enabled.
     fn flush(&mut self) -> Result<(), Self::Error> {
-        while self.usart.status().read().txcif().bit_is_clear() {}
-        self.usart.status().write(|w| w.txcif().clear_bit());
+        while !is_transmission_complete(&self.usart, &mut self.transmitting) {}
         Ok(())
     }
 }
 
+impl<Usart, RX, TX> IoWriteReady for Serial<Usart, UartPinset<Usart, RX, TX>>
+where
+    Usart: Instance,
+    RX: RxPin<Usart>,
+    TX: TxPin<Usart>,
+{
+    fn write_ready(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.usart.status().read().dreif().bit_is_set())
+    }
+}
+
 impl<Usart, RX, TX> NbWrite for Serial<Usart, UartPinset<Usart, RX, TX>>
 where
     Usart: Instance,
@@ -883,16 +1493,16 @@ where
 {
     fn write(&mut self, word: u8) -> embedded_hal_nb::nb::Result<(), Self::Error> {
         if self.usart.status().read().dreif().bit_is_set() {
-            self.usart.txdatal().write(|w| w.bits(word));
+            transmit(&self.usart, &mut self.transmitting, word);
             Ok(())
         } else {
             Err(nb::Error::WouldBlock)
         }
     }
 
+    /// This implementation shares the same effects as the [`Serial`]s [`embedded_io::Write`] implemenation.
     fn flush(&mut self) -> embedded_hal_nb::nb::Result<(), Self::Error> {
-        if self.usart.status().read().txcif().bit_is_set() {
-            self.usart.status().write(|w| w.txcif().set_bit());
+        if is_transmission_complete(&self.usart, &mut self.transmitting) {
             Ok(())
         } else {
             Err(nb::Error::WouldBlock)
@@ -954,46 +1564,55 @@ where
     Pin: TxPin<Usart>,
 {
     fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        let (usart, transmitting) = unsafe { self.usart_transmitting() };
+
         for b in buf {
-            unsafe {
-                while self.usart().status().read().dreif().bit_is_clear() {}
-                self.usart().txdatal().write(|w| w.bits(*b));
-            }
+            while usart.status().read().dreif().bit_is_clear() {}
+            transmit(usart, transmitting, *b);
         }
 
         Ok(buf.len())
     }
 
+    /// This implementation shares the same effects as the [`Serial`]s [`embedded_io::Write`] implemenation.
     fn flush(&mut self) -> Result<(), Self::Error> {
-        unsafe {
-            while self.usart().status().read().txcif().bit_is_clear() {}
-            self.usart().status().write(|w| w.txcif().clear_bit());
-        }
+        let (usart, transmitting) = unsafe { self.usart_transmitting() };
+        while !is_transmission_complete(usart, transmitting) {}
         Ok(())
     }
 }
 
+impl<Usart, Pin> IoWriteReady for Tx<Usart, Pin>
+where
+    Usart: Instance,
+    Pin: TxPin<Usart>,
+{
+    fn write_ready(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.is_ready())
+    }
+}
+
 impl<Usart, Pin> NbWrite for Tx<Usart, Pin>
 where
     Usart: Instance,
     Pin: TxPin<Usart>,
 {
     fn write(&mut self, word: u8) -> embedded_hal_nb::nb::Result<(), Self::Error> {
-        let status = unsafe { self.usart().status().read() };
+        let (usart, transmitting) = unsafe { self.usart_transmitting() };
 
-        if status.dreif().bit_is_set() {
-            unsafe { self.usart().txdatal().write(|w| w.bits(word)) };
+        if usart.status().read().dreif().bit_is_set() {
+            transmit(usart, transmitting, word);
             Ok(())
         } else {
             Err(nb::Error::WouldBlock)
         }
     }
 
+    /// This implementation shares the same effects as the [`Serial`]s [`embedded_io::Write`] implemenation.
     fn flush(&mut self) -> embedded_hal_nb::nb::Result<(), Self::Error> {
-        let status = unsafe { self.usart().status().read() };
+        let (usart, transmitting) = unsafe { self.usart_transmitting() };
 
-        if status.txcif().bit_is_set() {
-            unsafe { self.usart().status().write(|w| w.txcif().set_bit()) };
+        if is_transmission_complete(usart, transmitting) {
             Ok(())
         } else {
             Err(nb::Error::WouldBlock)
@@ -1042,6 +1661,7 @@ macro_rules! uart {
             {
                 tx: ($X_tx:ident/$x_tx:ident, $pin_tx:literal),
                 rx: ($X_rx:ident/$x_rx:ident, $pin_rx:literal),
+                xdir: ($X_xdir:ident/$x_xdir:ident, $pin_xdir:literal),
             },
         )+]
     }) => {
@@ -1082,7 +1702,7 @@ macro_rules! uart {
                         crate::pac::Peripherals::steal().$USART,
                     )
                 };
-                (split::Rx::new(rx, self.pinset.rx), split::Tx::new(tx, self.pinset.tx))
+                (split::Rx::new(rx, self.pinset.rx), split::Tx::new(tx, self.pinset.tx, self.transmitting, self.guard_time_ns))
             }
         }
 
@@ -1090,6 +1710,7 @@ macro_rules! uart {
             paste::paste! {
                 impl TxPin<$USART> for crate::gpio::[<port $x_tx>]::[<P $X_tx $pin_tx>]<Output<Stateless>> {}
                 impl RxPin<$USART> for crate::gpio::[<port $x_rx>]::[<P $X_rx $pin_rx>]<Input> {}
+                impl XdirPin<$USART> for crate::gpio::[<port $x_xdir>]::[<P $X_xdir $pin_xdir>]<Output<Stateless>> {}
             }
         )+
     };
@@ -1103,10 +1724,12 @@ uart!({
         {
             tx: (B/b, 2),
             rx: (B/b, 3),
+            xdir: (B/b, 0),
         },
         {
             tx: (A/a, 1),
             rx: (A/a, 2),
+            xdir: (A/a, 4),
         },
     ]
 });
@@ -1,5 +1,6 @@
 //! Types for configuring a serial interface.
 
+use crate::pac::usart0::ctrla::RS485_A;
 use crate::pac::usart0::ctrlc::{CHSIZE_A, PMODE_A, SBMODE_A};
 use crate::time::*;
 
@@ -102,6 +103,50 @@ impl From<CHSIZE_A> for CharacterSize {
     }
 }
 
+/// RS-485 mode of the USART
+///
+/// Wrapper around [`RS485_A`]
+///
+/// In both RS-485 modes the transmitter is only enabled while a frame is
+/// transmitted. It is enabled one bit period before the start bit and
+/// disabled again after the last stop bit has been shifted out. A longer
+/// guard time before answering on the bus is set using
+/// [`Config::guard_time`].
+#[derive(ufmt::derive::uDebug, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rs485Mode {
+    /// RS-485 mode disabled
+    Off,
+    /// Control the transmit enable input of an external line driver through
+    /// the XDIR pin
+    ///
+    /// This requires a pinset including the XDIR pin, see
+    /// [`ConfigError::MissingXdir`](super::ConfigError::MissingXdir).
+    External,
+    /// Control the output driver of the TX pin, which is only driven while
+    /// transmitting
+    Internal,
+}
+
+impl From<Rs485Mode> for RS485_A {
+    fn from(mode: Rs485Mode) -> Self {
+        match mode {
+            Rs485Mode::Off => RS485_A::OFF,
+            Rs485Mode::External => RS485_A::EXT,
+            Rs485Mode::Internal => RS485_A::INT,
+        }
+    }
+}
+
+impl From<RS485_A> for Rs485Mode {
+    fn from(mode: RS485_A) -> Self {
+        match mode {
+            RS485_A::OFF => Rs485Mode::Off,
+            RS485_A::EXT => Rs485Mode::External,
+            RS485_A::INT => Rs485Mode::Internal,
+        }
+    }
+}
+
 /// Configuration struct for [`Serial`](super::Serial) providing all
 /// communication-related / parameters. [`Serial`](super::Serial) always uses eight data
 /// bits plus the parity bit - if selected.
@@ -128,6 +173,17 @@ pub struct Config {
     pub parity: Parity,
     /// The number of stop bits to follow the last data bit or the parity bit
     pub stopbits: StopBits,
+    /// RS-485 mode of the transmitter
+    pub rs485: Rs485Mode,
+    /// Number of bit periods to wait before turning the bus around, see
+    /// [`Serial::wait_guard_time`](super::Serial::wait_guard_time)
+    pub guard_time: u8,
+    /// Maximum allowed deviation of the achieved from the requested baud
+    /// rate in per mille, the deviation isn't checked if `None`
+    pub baud_tolerance: Option<u16>,
+    /// Compensate the baud rate calculation for the frequency error of the
+    /// internal oscillator, if it is known by the [`Clocks`](crate::clkctrl::Clocks)
+    pub osc_error_compensation: bool,
 }
 
 impl Config {
@@ -154,6 +210,34 @@ impl Config {
         self.stopbits = stopbits;
         self
     }
+
+    /// Sets the maximum allowed baud rate deviation in per mille.
+    ///
+    /// By default the deviation isn't checked.
+    pub fn baud_tolerance(mut self, baud_tolerance: u16) -> Self {
+        self.baud_tolerance = Some(baud_tolerance);
+        self
+    }
+
+    /// Sets whether the frequency error of the internal oscillator is compensated.
+    ///
+    /// See [`ClkCtrl::osc20m_error`](crate::clkctrl::ClkCtrl::osc20m_error).
+    pub fn osc_error_compensation(mut self, enable: bool) -> Self {
+        self.osc_error_compensation = enable;
+        self
+    }
+
+    /// Sets the RS-485 mode to `rs485`.
+    pub fn rs485(mut self, rs485: Rs485Mode) -> Self {
+        self.rs485 = rs485;
+        self
+    }
+
+    /// Sets the guard time to `bits` bit periods.
+    pub fn guard_time(mut self, bits: u8) -> Self {
+        self.guard_time = bits;
+        self
+    }
 }
 
 impl Default for Config {
@@ -165,6 +249,10 @@ impl Default for Config {
             character_size: CharacterSize::Size8,
             parity: Parity::None,
             stopbits: StopBits::Stop1,
+            rs485: Rs485Mode::Off,
+            guard_time: 0,
+            baud_tolerance: None,
+            osc_error_compensation: true,
         }
     }
 }
@@ -0,0 +1,202 @@
+//! # XON/XOFF software flow control
+//!
+//! Hosts without spare handshake pins can still throttle a serial connection
+//! by sending the XOFF (`0x13`) and XON (`0x11`) control characters in band.
+//!
+//! [`SoftwareFlowControl`] wraps a [`Serial`] and stops transmitting as long
+//! as the other side has sent XOFF. Received data is moved into a receive
+//! buffer of `N` bytes. Once it is filled to three quarters, XOFF is sent to
+//! the other side, and XON once it has been drained to a quarter again.
+//!
+//! ```
+//! let serial = Serial::new(dp.USART0, usart_pair, 115200u32.bps(), clocks);
+//! let mut serial = SoftwareFlowControl::<_, _, _, 32>::new(serial);
+//!
+//! loop {
+//!     // Move received data into the buffer, e.g. from the receive interrupt
+//!     serial.poll().unwrap();
+//!
+//!     if let Ok(b) = serial.read() {
+//!         nb::block!(serial.write(b)).unwrap();
+//!     }
+//! }
+//! ```
+//!
+//! The data itself must not contain the control characters, so this is only
+//! suitable for text based protocols.
+
+use super::{
+    eh_read, is_transmission_complete, transmit, Error, Instance, RxPin, Serial, TxPin, UartPinset,
+};
+
+/// Control character resuming the transmission
+pub const XON: u8 = 0x11;
+
+/// Control character pausing the transmission
+pub const XOFF: u8 = 0x13;
+
+/// Serial with XON/XOFF software flow control
+///
+/// See the [module level documentation](self) for details.
+pub struct SoftwareFlowControl<Usart, RX, TX, const N: usize>
+where
+    RX: RxPin<Usart>,
+    TX: TxPin<Usart>,
+{
+    serial: Serial<Usart, UartPinset<Usart, RX, TX>>,
+    buffer: [u8; N],
+    head: usize,
+    len: usize,
+    /// XOFF has been received
+    paused: bool,
+    /// XOFF has been sent
+    throttled: bool,
+    /// Control character waiting for the transmitter
+    pending: Option<u8>,
+}
+
+impl<Usart, RX, TX, const N: usize> SoftwareFlowControl<Usart, RX, TX, N>
+where
+    Usart: Instance,
+    RX: RxPin<Usart>,
+    TX: TxPin<Usart>,
+{
+    /// Enables the flow control on the serial
+    ///
+    /// # Panics
+    ///
+    /// Panics if the receive buffer is smaller than 4 bytes.
+    pub fn new(serial: Serial<Usart, UartPinset<Usart, RX, TX>>) -> Self {
+        assert!(N >= 4, "receive buffer too small");
+
+        Self {
+            serial,
+            buffer: [0; N],
+            head: 0,
+            len: 0,
+            paused: false,
+            throttled: false,
+            pending: None,
+        }
+    }
+
+    /// Move the received data into the receive buffer
+    ///
+    /// Received control characters are handled and not stored in the buffer.
+    /// Call this often enough to not overrun the receiver of the USART, e.g.
+    /// from the receive complete interrupt.
+    ///
+    /// Returns [`Error::Overrun`] if the receive buffer is full, as the other
+    /// side didn't stop transmitting after the XOFF.
+    pub fn poll(&mut self) -> Result<(), Error> {
+        while let Some(b) = eh_read(&mut self.serial.usart)? {
+            match b {
+                XON => self.paused = false,
+                XOFF => self.paused = true,
+                _ if self.len == N => return Err(Error::Overrun),
+                _ => {
+                    self.buffer[(self.head + self.len) % N] = b;
+                    self.len += 1;
+                }
+            }
+        }
+
+        if !self.throttled && self.len >= N - N / 4 {
+            self.throttled = true;
+            self.pending = Some(XOFF);
+        }
+
+        self.send_pending();
+        Ok(())
+    }
+
+    /// Read a byte from the receive buffer
+    ///
+    /// XON is sent once enough of the buffer has been drained again.
+    pub fn read(&mut self) -> nb::Result<u8, Error> {
+        self.poll()?;
+
+        if self.len == 0 {
+            return Err(nb::Error::WouldBlock);
+        }
+
+        let b = self.buffer[self.head];
+        self.head = (self.head + 1) % N;
+        self.len -= 1;
+
+        if self.throttled && self.len <= N / 4 {
+            self.throttled = false;
+            self.pending = Some(XON);
+            self.send_pending();
+        }
+
+        Ok(b)
+    }
+
+    /// Send a byte, unless the other side has paused the transmission
+    pub fn write(&mut self, word: u8) -> nb::Result<(), Error> {
+        self.poll()?;
+
+        if self.pending.is_some() || self.paused {
+            return Err(nb::Error::WouldBlock);
+        }
+
+        if self.serial.usart.status().read().dreif().bit_is_set() {
+            transmit(&self.serial.usart, &mut self.serial.transmitting, word);
+            Ok(())
+        } else {
+            Err(nb::Error::WouldBlock)
+        }
+    }
+
+    /// Wait until all written data has been transmitted
+    pub fn flush(&mut self) -> nb::Result<(), Error> {
+        self.poll()?;
+
+        if self.pending.is_none()
+            && is_transmission_complete(&self.serial.usart, &mut self.serial.transmitting)
+        {
+            Ok(())
+        } else {
+            Err(nb::Error::WouldBlock)
+        }
+    }
+
+    /// Check if the other side has paused the transmission with XOFF
+    #[inline]
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Check if the other side has been asked to pause with XOFF
+    #[inline]
+    pub fn is_throttled(&self) -> bool {
+        self.throttled
+    }
+
+    /// Returns the number of bytes in the receive buffer
+    #[inline]
+    pub fn available(&self) -> usize {
+        self.len
+    }
+
+    /// Disables the flow control and returns the serial
+    ///
+    /// Data left in the receive buffer is lost.
+    pub fn free(self) -> Serial<Usart, UartPinset<Usart, RX, TX>> {
+        self.serial
+    }
+
+    /// Send a pending control character, if the transmitter is ready
+    ///
+    /// Control characters take precedence over the data written by the
+    /// application.
+    fn send_pending(&mut self) {
+        if let Some(b) = self.pending {
+            if self.serial.usart.status().read().dreif().bit_is_set() {
+                transmit(&self.serial.usart, &mut self.serial.transmitting, b);
+                self.pending = None;
+            }
+        }
+    }
+}
@@ -0,0 +1,141 @@
+//! # Multiprocessor communication mode
+//!
+//! Multiple nodes can share a single serial bus by prefixing the data sent to
+//! a node with an address frame. Frames are sent with 9 data bits, where the
+//! 9th bit marks a frame as an address frame.
+//!
+//! While waiting for an address, the receiver ignores all data frames in
+//! hardware. Once a matching address is received, the data frames are received
+//! until another address frame is seen. This way the nodes don't have to
+//! process data that isn't meant for them.
+//!
+//! ```
+//! let serial = Serial::new(dp.USART0, usart_pair, 115200u32.bps(), clocks);
+//! let mut node = Multiprocessor::new(serial, 0x42);
+//!
+//! // Send two bytes to the node with the address 0x23
+//! node.write_address(0x23);
+//! node.write(0xAB).unwrap();
+//! node.write(0xCD).unwrap();
+//!
+//! // Receive data sent to us
+//! let b = nb::block!(node.read()).unwrap();
+//! ```
+
+use super::{eh_read, transmit, Error, Instance, RxPin, Serial, TxPin, UartPinset};
+
+/// Serial in multiprocessor communication mode
+///
+/// See the [module level documentation](self) for details.
+pub struct Multiprocessor<Usart, RX, TX>
+where
+    RX: RxPin<Usart>,
+    TX: TxPin<Usart>,
+{
+    serial: Serial<Usart, UartPinset<Usart, RX, TX>>,
+    address: u8,
+}
+
+impl<Usart, RX, TX> Multiprocessor<Usart, RX, TX>
+where
+    Usart: Instance,
+    RX: RxPin<Usart>,
+    TX: TxPin<Usart>,
+{
+    /// Switches the serial to 9 bit frames in multiprocessor communication mode
+    ///
+    /// The receiver waits for a frame with the passed node address.
+    pub fn new(serial: Serial<Usart, UartPinset<Usart, RX, TX>>, address: u8) -> Self {
+        // The 9th bit has to be read and written before the low byte
+        serial.usart.ctrlc().modify(|_, w| w.chsize()._9bith());
+
+        let mut mpcm = Self { serial, address };
+        mpcm.listen_for_address();
+        mpcm
+    }
+
+    /// Set the node address
+    ///
+    /// The receiver waits for a frame with the new address.
+    pub fn set_address(&mut self, address: u8) {
+        self.address = address;
+        self.listen_for_address();
+    }
+
+    /// Returns the node address
+    #[inline]
+    pub fn address(&self) -> u8 {
+        self.address
+    }
+
+    /// Ignore all data frames until a frame with the node address is received
+    #[doc(alias = "MPCM")]
+    #[inline]
+    pub fn listen_for_address(&mut self) {
+        self.serial.set_multiprocessor_communication(true);
+    }
+
+    /// Check if data frames are currently received, because the node was addressed
+    #[inline]
+    pub fn is_addressed(&self) -> bool {
+        self.serial.usart.ctrlb().read().mpcm().bit_is_clear()
+    }
+
+    /// Read a data frame addressed to this node
+    ///
+    /// Address frames are handled internally. A matching address switches to
+    /// the reception of data frames, any other address switches back to waiting
+    /// for the node address.
+    pub fn read(&mut self) -> nb::Result<u8, Error> {
+        // The 9th bit has to be read before the data is removed by `eh_read`
+        let is_address = self.serial.usart.rxdatah().read().data8().bit_is_set();
+
+        match eh_read(&mut self.serial.usart)? {
+            Some(address) if is_address => {
+                self.serial
+                    .set_multiprocessor_communication(address != self.address);
+                Err(nb::Error::WouldBlock)
+            }
+            Some(b) => Ok(b),
+            None => Err(nb::Error::WouldBlock),
+        }
+    }
+
+    /// Send an address frame to select the node receiving the following data frames
+    ///
+    /// This blocks until the address frame has been moved into the transmit
+    /// shift register.
+    pub fn write_address(&mut self, address: u8) {
+        let usart = &self.serial.usart;
+
+        while usart.status().read().dreif().bit_is_clear() {}
+        usart.txdatah().write(|w| w.data8().set_bit());
+        transmit(usart, &mut self.serial.transmitting, address);
+
+        // Clear the 9th bit again once it has been consumed by the transmitter
+        while usart.status().read().dreif().bit_is_clear() {}
+        usart.txdatah().write(|w| w.data8().clear_bit());
+    }
+
+    /// Send a data frame to the previously addressed node
+    pub fn write(&mut self, word: u8) -> nb::Result<(), Error> {
+        let usart = &self.serial.usart;
+
+        if usart.status().read().dreif().bit_is_set() {
+            usart.txdatah().write(|w| w.data8().clear_bit());
+            transmit(usart, &mut self.serial.transmitting, word);
+            Ok(())
+        } else {
+            Err(nb::Error::WouldBlock)
+        }
+    }
+
+    /// Leaves the multiprocessor communication mode and returns the serial
+    ///
+    /// The serial is switched back to 8 bit frames.
+    pub fn free(mut self) -> Serial<Usart, UartPinset<Usart, RX, TX>> {
+        self.serial.set_multiprocessor_communication(false);
+        self.serial.usart.ctrlc().modify(|_, w| w.chsize()._8bit());
+        self.serial
+    }
+}
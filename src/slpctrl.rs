@@ -50,6 +50,35 @@ impl Slpctrl {
         unsafe { asm!("sleep") };
         ctrla.modify(|_, w| w.sen().clear_bit());
     }
+
+    /// Repeatedly enter the [previously configured](Slpctrl::set_sleep_mode)
+    /// sleep mode until `condition` returns `true`
+    ///
+    /// The condition is evaluated with interrupts disabled. Interrupts are
+    /// enabled again by the instruction right in front of the `sleep`
+    /// instruction, so an interrupt arriving between the check of the condition
+    /// and going to sleep can't be missed.
+    ///
+    /// Global interrupts need to be enabled when calling this function and are
+    /// enabled when it returns.
+    pub fn sleep_until(&mut self, mut condition: impl FnMut() -> bool) {
+        let ctrla = unsafe { &(*SLPCTRL::ptr()).ctrla() };
+
+        loop {
+            unsafe { avr_device::interrupt::disable() };
+
+            if condition() {
+                unsafe { avr_device::interrupt::enable() };
+                break;
+            }
+
+            ctrla.modify(|_, w| w.sen().set_bit());
+            // The instruction following SEI is always executed before any
+            // pending interrupt is serviced
+            unsafe { asm!("sei", "sleep") };
+            ctrla.modify(|_, w| w.sen().clear_bit());
+        }
+    }
 }
 
 /// The desired sleep mode that is to be entered when calling
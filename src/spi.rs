@@ -1,12 +1,25 @@
 //! # Serial Peripheral Interface (SPI) bus
+//!
+//! [`Spi`] implements [`SpiBus`]. Drivers expecting an
+//! [`embedded_hal::spi::SpiDevice`](crate::embedded_hal::spi::SpiDevice) which
+//! manages the chip select on its own can be given an
+//! [`ExclusiveDevice`](crate::embedded_hal_bus::spi::ExclusiveDevice) owning
+//! the bus and the chip select pin:
+//!
+//! ```
+//! let spi = Spi::new_unbuffered(dp.SPI0, spi_pair, 625_000.Hz(), clocks);
+//! let cs = c.pc3.into_stateless_push_pull_output();
+//!
+//! let mut device = ExclusiveDevice::new(spi, cs, NoDelay);
+//! device.transfer_in_place(&mut buf).unwrap();
+//! ```
 
-// TODO: support buffered mode
 // TODO: support slave mode both buffered and unbuffered
 // TODO: support dual role mode and switching between master and slave mode using the ~SS pin
 // TODO: interrupts
 
 use core::cmp::max;
-use core::{marker::PhantomData, ops::Deref};
+use core::{cell::Cell, marker::PhantomData, ops::Deref};
 
 use enumset::{EnumSet, EnumSetType};
 
@@ -14,28 +27,38 @@ use crate::embedded_hal::spi::{ErrorType, SpiBus, MODE_0, MODE_1, MODE_2, MODE_3
 
 use crate::{
     clkctrl::Clocks,
+    gpio::Release,
     pac::spi0::{ctrla::PRESC_A, ctrlb::MODE_A, RegisterBlock},
     time::*,
 };
 
+#[cfg(feature = "async")]
+pub mod asynch;
 pub mod config;
 use self::config::DataOrder;
 
 /// SPI error
 #[derive(ufmt::derive::uDebug, Debug, Copy, Clone, PartialEq, Eq)]
 pub enum Error {
-    // NOTE: only in buffered mode
-    // /// Overrun occurred
-    // Overrun,
+    /// Overrun occurred
+    ///
+    /// Only reported in buffered mode.
+    Overrun,
     /// Write collision occurred
     WriteCollision,
+    /// Another host pulled ~SS low and the SPI fell back to client mode
+    ///
+    /// Use [`Spi::reclaim_host`] to become the host again.
+    ModeFault,
 }
 
 impl crate::embedded_hal::spi::Error for Error {
     fn kind(&self) -> crate::embedded_hal::spi::ErrorKind {
         use crate::embedded_hal::spi::ErrorKind;
         match *self {
+            Error::Overrun => ErrorKind::Overrun,
             Error::WriteCollision => ErrorKind::Other,
+            Error::ModeFault => ErrorKind::ModeFault,
         }
     }
 }
@@ -49,29 +72,62 @@ pub trait MisoPin<SPI>: crate::private::Sealed {}
 /// MOSI (Master Out - Slave In) pin
 pub trait MosiPin<SPI>: crate::private::Sealed {}
 
-// Note: commented out because we don't support the slave mode right now
-// /// ~SS (Slave-Select) pin
-// pub trait SsPin<SPI>: crate::private::Sealed {}
+/// ~SS (Slave-Select) pin
+///
+/// In host mode, the pin is an input used to detect other hosts on the bus.
+pub trait SsPin<SPI>: OptionalSs<SPI> + crate::private::Sealed {}
+
+/// Placeholder for a pinset without a hardware ~SS pin
+pub struct NoSs;
+
+/// Either a [`SsPin`] or [`NoSs`]
+pub trait OptionalSs<SPI> {
+    #[doc(hidden)]
+    const ENABLED: bool;
+}
+
+impl<SPI> OptionalSs<SPI> for NoSs {
+    const ENABLED: bool = false;
+}
 
 // TODO: make some pins optional?
 /// Pin set for the port multiplexer
-pub struct SpiPinset<SPI, Sck: SckPin<SPI>, Miso: MisoPin<SPI>, Mosi: MosiPin<SPI>> {
+///
+/// With a hardware ~SS pin, the SPI falls back to client mode when another host
+/// pulls ~SS low, which is reported as [`Error::ModeFault`].
+pub struct SpiPinset<
+    SPI,
+    Sck: SckPin<SPI>,
+    Miso: MisoPin<SPI>,
+    Mosi: MosiPin<SPI>,
+    Ss: OptionalSs<SPI> = NoSs,
+> {
     _spi: PhantomData<SPI>,
     sck: Sck,
     miso: Miso,
     mosi: Mosi,
+    ss: Ss,
 }
 
-// FIXME: implement buffered mode
-// /// SPI in buffered mode (type state)
-// pub struct Buffered;
+/// SPI in buffered mode (type state)
+///
+/// The transmitter and the receiver are double buffered, so consecutive bytes
+/// are transferred without gaps in between.
+pub struct Buffered;
 
 /// SPI in unbuffered mode (type state)
 pub struct Unbuffered;
 
-pub trait ED {}
-//impl ED for Buffered {}
-impl ED for Unbuffered {}
+pub trait ED {
+    #[doc(hidden)]
+    const BUFFERED: bool;
+}
+impl ED for Buffered {
+    const BUFFERED: bool = true;
+}
+impl ED for Unbuffered {
+    const BUFFERED: bool = false;
+}
 
 impl<SPI, Sck, Miso, Mosi> SpiPinset<SPI, Sck, Miso, Mosi>
 where
@@ -85,13 +141,70 @@ where
             sck,
             miso,
             mosi,
+            ss: NoSs,
         }
     }
 
+    /// Returns the pins in the modes used by the peripheral
     pub fn free(self) -> (Sck, Miso, Mosi) {
         (self.sck, self.miso, self.mosi)
     }
 
+    /// Releases the pins back to GPIO
+    ///
+    /// The pins are converted back into floating inputs, so they can be
+    /// reused after the peripheral has been shut down.
+    pub fn release(self) -> (Sck::Released, Miso::Released, Mosi::Released)
+    where
+        Sck: Release,
+        Miso: Release,
+        Mosi: Release,
+    {
+        (self.sck.release(), self.miso.release(), self.mosi.release())
+    }
+}
+
+impl<SPI, Sck, Miso, Mosi, Ss> SpiPinset<SPI, Sck, Miso, Mosi, Ss>
+where
+    Sck: SckPin<SPI>,
+    Miso: MisoPin<SPI>,
+    Mosi: MosiPin<SPI>,
+    Ss: SsPin<SPI>,
+{
+    pub(crate) fn new_with_ss(sck: Sck, miso: Miso, mosi: Mosi, ss: Ss) -> Self {
+        SpiPinset {
+            _spi: PhantomData,
+            sck,
+            miso,
+            mosi,
+            ss,
+        }
+    }
+
+    /// Returns the pins in the modes used by the peripheral
+    pub fn free_with_ss(self) -> (Sck, Miso, Mosi, Ss) {
+        (self.sck, self.miso, self.mosi, self.ss)
+    }
+
+    /// Releases the pins back to GPIO
+    ///
+    /// The pins are converted back into floating inputs, so they can be
+    /// reused after the peripheral has been shut down.
+    pub fn release_with_ss(self) -> (Sck::Released, Miso::Released, Mosi::Released, Ss::Released)
+    where
+        Sck: Release,
+        Miso: Release,
+        Mosi: Release,
+        Ss: Release,
+    {
+        (
+            self.sck.release(),
+            self.miso.release(),
+            self.mosi.release(),
+            self.ss.release(),
+        )
+    }
+
     // TODO: allow retrieval of certain pins as inputs if we don't need them
     //       this might be handy when routing signals to CCL
 }
@@ -134,69 +247,28 @@ pub struct Spi<SPI, Mode, Pinset> {
     _mode: PhantomData<Mode>,
 }
 
-impl<SPI, SCK, MISO, MOSI> Spi<SPI, Unbuffered, SpiPinset<SPI, SCK, MISO, MOSI>>
+impl<SPI, SCK, MISO, MOSI, SS> Spi<SPI, Unbuffered, SpiPinset<SPI, SCK, MISO, MOSI, SS>>
 where
     SPI: Instance,
     SCK: SckPin<SPI>,
     MISO: MisoPin<SPI>,
     MOSI: MosiPin<SPI>,
+    SS: OptionalSs<SPI>,
 {
     /// Configures the SPI peripheral to work in unbuffered master mode
+    ///
+    /// The frequency is clamped to the range of the prescaler, see
+    /// [`Spi::reconfigure`].
     pub fn new_unbuffered<Config>(
         spi: SPI,
-        pinset: SpiPinset<SPI, SCK, MISO, MOSI>,
+        pinset: SpiPinset<SPI, SCK, MISO, MOSI, SS>,
         config: Config,
         clocks: Clocks,
     ) -> Self
     where
         Config: Into<config::Config>,
     {
-        let config = config.into();
-
-        let mode = match config.mode {
-            MODE_0 => MODE_A::_0,
-            MODE_1 => MODE_A::_1,
-            MODE_2 => MODE_A::_2,
-            MODE_3 => MODE_A::_3,
-        };
-
-        let (clk2x, div) = Self::compute_baud_rate(clocks, config.frequency);
-
-        // Disable the peripheral
-        spi.ctrla().modify(|_, w| w.enable().clear_bit());
-
-        // Configure SPI peripheral
-        spi.ctrlb().modify(|_, w| {
-            w.bufen()
-                .clear_bit()
-                .bufwr()
-                .clear_bit() // Disable buffer write mode (only valid for client mode anyway)
-                .mode()
-                .variant(mode)
-                .ssd()
-                .set_bit() // Disable slave select
-        });
-
-        spi.ctrla().modify(|_, w| {
-            w.dord()
-                .bit(config.order == DataOrder::LsbFirst)
-                .enable()
-                .set_bit() // Enable peripheral
-                .master()
-                .set_bit() // SPI Master
-                .clk2x()
-                .bit(clk2x) // Clock double speed
-                .presc()
-                .variant(div) // Clock prescaler
-        });
-
-        spi.intctrl().reset();
-
-        Self {
-            spi,
-            pinset,
-            _mode: PhantomData,
-        }
+        Self::configure(spi, pinset, config.into(), clocks)
     }
 
     /// Enable the interrupt.
@@ -244,18 +316,102 @@ where
     }
 }
 
-impl<SPI, MODE, SCK, MISO, MOSI> Spi<SPI, MODE, SpiPinset<SPI, SCK, MISO, MOSI>>
+impl<SPI, SCK, MISO, MOSI, SS> Spi<SPI, Buffered, SpiPinset<SPI, SCK, MISO, MOSI, SS>>
 where
     SPI: Instance,
     SCK: SckPin<SPI>,
     MISO: MisoPin<SPI>,
     MOSI: MosiPin<SPI>,
+    SS: OptionalSs<SPI>,
+{
+    /// Configures the SPI peripheral to work in buffered master mode
+    ///
+    /// In buffered mode, the next byte is written while the current one is
+    /// still shifted out, which removes the gaps between consecutive bytes.
+    ///
+    /// The frequency is clamped to the range of the prescaler, see
+    /// [`Spi::reconfigure`].
+    pub fn new_buffered<Config>(
+        spi: SPI,
+        pinset: SpiPinset<SPI, SCK, MISO, MOSI, SS>,
+        config: Config,
+        clocks: Clocks,
+    ) -> Self
+    where
+        Config: Into<config::Config>,
+    {
+        Self::configure(spi, pinset, config.into(), clocks)
+    }
+}
+
+impl<SPI, MODE, SCK, MISO, MOSI, SS> Spi<SPI, MODE, SpiPinset<SPI, SCK, MISO, MOSI, SS>>
+where
+    SPI: Instance,
+    SCK: SckPin<SPI>,
+    MISO: MisoPin<SPI>,
+    MOSI: MosiPin<SPI>,
+    SS: OptionalSs<SPI>,
     MODE: ED,
 {
+    fn configure(
+        spi: SPI,
+        pinset: SpiPinset<SPI, SCK, MISO, MOSI, SS>,
+        config: config::Config,
+        clocks: Clocks,
+    ) -> Self {
+        Self::apply_config(&spi, config, clocks);
+
+        spi.intctrl().reset();
+
+        Self {
+            spi,
+            pinset,
+            _mode: PhantomData,
+        }
+    }
+
+    fn apply_config(spi: &SPI, config: config::Config, clocks: Clocks) {
+        let mode = match config.mode {
+            MODE_0 => MODE_A::_0,
+            MODE_1 => MODE_A::_1,
+            MODE_2 => MODE_A::_2,
+            MODE_3 => MODE_A::_3,
+        };
+
+        let (clk2x, div) = Self::compute_baud_rate(clocks, config.frequency);
+
+        // Disable the peripheral
+        spi.ctrla().modify(|_, w| w.enable().clear_bit());
+
+        // Configure SPI peripheral
+        spi.ctrlb().modify(|_, w| {
+            w.bufen()
+                .bit(MODE::BUFFERED)
+                .bufwr()
+                .clear_bit() // Disable buffer write mode (only valid for client mode anyway)
+                .mode()
+                .variant(mode)
+                .ssd()
+                .bit(!SS::ENABLED) // Disable slave select without a hardware ~SS pin
+        });
+
+        spi.ctrla().modify(|_, w| {
+            w.dord()
+                .bit(config.order == DataOrder::LsbFirst)
+                .enable()
+                .set_bit() // Enable peripheral
+                .master()
+                .set_bit() // SPI Master
+                .clk2x()
+                .bit(clk2x) // Clock double speed
+                .presc()
+                .variant(div) // Clock prescaler
+        });
+    }
+
     fn compute_baud_rate(clocks: Clocks, freq: Hertz) -> (bool, PRESC_A) {
         match SPI::clock(&clocks).raw() / freq.raw() {
-            0 => unreachable!(),
-            1..=2 => (true, PRESC_A::DIV4),     // DIV_2
+            0..=2 => (true, PRESC_A::DIV4),     // DIV_2, clamps faster clocks
             3..=5 => (false, PRESC_A::DIV4),    // DIV_4
             6..=11 => (true, PRESC_A::DIV16),   // DIV_8
             12..=23 => (false, PRESC_A::DIV16), // DIV_16
@@ -279,72 +435,185 @@ where
         &mut self.spi
     }
 
+    /// Change the mode, data order and frequency of the bus
+    ///
+    /// This allows to talk to multiple devices with different requirements on
+    /// the same bus. The bus must be idle when calling this.
+    ///
+    /// The frequency is approximated by a prescaler of the peripheral clock
+    /// between 2 and 128. Frequencies outside of this range are clamped, e.g.
+    /// requesting 20MHz from a 20MHz peripheral clock results in 10MHz. Use
+    /// [`Spi::frequency`] to get the frequency actually used.
+    pub fn reconfigure<Config>(&mut self, config: Config, clocks: Clocks)
+    where
+        Config: Into<config::Config>,
+    {
+        Self::apply_config(&self.spi, config.into(), clocks);
+    }
+
+    /// Returns the actual frequency of the bus clock
+    ///
+    /// This is the requested frequency approximated by one of the possible
+    /// prescalers of the peripheral clock.
+    pub fn frequency(&self, clocks: Clocks) -> Hertz {
+        let ctrla = self.spi.ctrla().read();
+        let div = match ctrla.presc().variant() {
+            PRESC_A::DIV4 => 4,
+            PRESC_A::DIV16 => 16,
+            PRESC_A::DIV64 => 64,
+            PRESC_A::DIV128 => 128,
+        };
+        let div = if ctrla.clk2x().bit_is_set() {
+            div / 2
+        } else {
+            div
+        };
+
+        Hertz::from_raw(SPI::clock(&clocks).raw() / div)
+    }
+
+    /// Check if the SPI is still operating as the host of the bus
+    ///
+    /// This is only ever `false` with a hardware ~SS pin, after another host
+    /// pulled it low.
+    #[inline]
+    pub fn is_host(&self) -> bool {
+        self.spi.ctrla().read().master().bit_is_set()
+    }
+
+    /// Become the host of the bus again after a [`Error::ModeFault`]
+    ///
+    /// The other host must have released ~SS before.
+    #[inline]
+    pub fn reclaim_host(&mut self) {
+        self.spi.ctrla().modify(|_, w| w.master().set_bit());
+    }
+
     /// Releases the SPI peripheral and associated pins
-    pub fn free(self) -> (SPI, SpiPinset<SPI, SCK, MISO, MOSI>) {
+    pub fn free(self) -> (SPI, SpiPinset<SPI, SCK, MISO, MOSI, SS>) {
         (self.spi, self.pinset)
     }
 
+    /// Check if another host selected this SPI as a client
+    fn check_mode_fault(&self) -> Result<(), Error> {
+        if SS::ENABLED && !self.is_host() {
+            Err(Error::ModeFault)
+        } else {
+            Ok(())
+        }
+    }
+
     fn transfer_byte(&mut self, tx: u8) -> Result<u8, Error> {
+        self.check_mode_fault()?;
         self.spi.data().write(|w| w.bits(tx));
-        while self.spi.intflags().read().if_().bit_is_clear() {}
-        Ok(self.spi.data().read().bits())
+
+        // The flag is also set when the SPI fell back to client mode
+        let intflags = loop {
+            let intflags = self.spi.intflags().read();
+            if intflags.if_().bit_is_set() {
+                break intflags;
+            }
+        };
+
+        // Reading the data clears both flags
+        let rx = self.spi.data().read().bits();
+
+        self.check_mode_fault()?;
+        if intflags.wrcol().bit_is_set() {
+            return Err(Error::WriteCollision);
+        }
+
+        Ok(rx)
+    }
+
+    /// Transfer `len` bytes, which are fetched from `tx` and passed to `rx` by their index
+    fn exchange(
+        &mut self,
+        len: usize,
+        tx: impl Fn(usize) -> u8,
+        mut rx: impl FnMut(usize, u8),
+    ) -> Result<(), Error> {
+        if !MODE::BUFFERED {
+            for i in 0..len {
+                rx(i, self.transfer_byte(tx(i))?);
+            }
+
+            return Ok(());
+        }
+
+        // Keep at most two bytes in flight: One in the shift register and one in
+        // the transmit buffer. This way the two byte receive buffer can't overflow.
+        let (mut sent, mut received) = (0, 0);
+        while received < len {
+            self.check_mode_fault()?;
+            let intflags = self.spi.intflags().read();
+
+            if intflags.bufovf().bit_is_set() {
+                self.spi.intflags().write(|w| w.bufovf().set_bit());
+                return Err(Error::Overrun);
+            }
+
+            if sent < len && sent - received < 2 && intflags.dreif().bit_is_set() {
+                self.spi.data().write(|w| w.bits(tx(sent)));
+                sent += 1;
+            }
+
+            if intflags.rxcif().bit_is_set() {
+                rx(received, self.spi.data().read().bits());
+                received += 1;
+            }
+        }
+
+        Ok(())
     }
 }
 
-impl<SPI, MODE, SCK, MISO, MOSI> ErrorType for Spi<SPI, MODE, SpiPinset<SPI, SCK, MISO, MOSI>>
+impl<SPI, MODE, SCK, MISO, MOSI, SS> ErrorType
+    for Spi<SPI, MODE, SpiPinset<SPI, SCK, MISO, MOSI, SS>>
 where
     SPI: Instance,
     SCK: SckPin<SPI>,
     MISO: MisoPin<SPI>,
     MOSI: MosiPin<SPI>,
+    SS: OptionalSs<SPI>,
     MODE: ED,
 {
     type Error = Error;
 }
 
-impl<SPI, MODE, SCK, MISO, MOSI> SpiBus for Spi<SPI, MODE, SpiPinset<SPI, SCK, MISO, MOSI>>
+impl<SPI, MODE, SCK, MISO, MOSI, SS> SpiBus for Spi<SPI, MODE, SpiPinset<SPI, SCK, MISO, MOSI, SS>>
 where
     SPI: Instance,
     SCK: SckPin<SPI>,
     MISO: MisoPin<SPI>,
     MOSI: MosiPin<SPI>,
+    SS: OptionalSs<SPI>,
     MODE: ED,
 {
     fn read(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
-        for w in words.iter_mut() {
-            *w = self.transfer_byte(0xff)?;
-        }
-
-        Ok(())
+        self.exchange(words.len(), |_| 0xff, |i, b| words[i] = b)
     }
 
     fn write(&mut self, words: &[u8]) -> Result<(), Self::Error> {
-        for w in words.iter() {
-            self.transfer_byte(*w)?;
-        }
-
-        Ok(())
+        self.exchange(words.len(), |i| words[i], |_, _| {})
     }
 
     fn transfer(&mut self, read: &mut [u8], write: &[u8]) -> Result<(), Self::Error> {
-        for i in 0..max(read.len(), write.len()) {
-            let tx_byte = if i < write.len() { write[i] } else { 0xff };
-            let rx_byte = self.transfer_byte(tx_byte)?;
-            if i < read.len() {
-                read[i] = rx_byte
-            };
-        }
-
-        Ok(())
+        self.exchange(
+            max(read.len(), write.len()),
+            |i| write.get(i).copied().unwrap_or(0xff),
+            |i, b| {
+                if let Some(r) = read.get_mut(i) {
+                    *r = b;
+                }
+            },
+        )
     }
 
     fn transfer_in_place(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
-        for w in words.iter_mut() {
-            let d = self.transfer_byte(*w)?;
-            *w = d;
-        }
-
-        Ok(())
+        // Each byte is sent before its received replacement is stored
+        let words = Cell::from_mut(words).as_slice_of_cells();
+        self.exchange(words.len(), |i| words[i].get(), |i, b| words[i].set(b))
     }
 
     fn flush(&mut self) -> Result<(), Self::Error> {
@@ -358,6 +627,13 @@ where
 pub trait Instance: Deref<Target = RegisterBlock> + crate::private::Sealed {
     #[doc(hidden)]
     fn clock(clocks: &Clocks) -> Hertz;
+
+    #[doc(hidden)]
+    fn ptr() -> *const RegisterBlock;
+
+    #[doc(hidden)]
+    #[cfg(feature = "async")]
+    fn waker() -> &'static crate::asynch::WakerSlot;
 }
 
 macro_rules! spi {
@@ -368,7 +644,7 @@ macro_rules! spi {
                 sck: ($X_sck:ident/$x_sck:ident, $pin_sck:literal),
                 miso: ($X_miso:ident/$x_miso:ident, $pin_miso:literal),
                 mosi: ($X_mosi:ident/$x_mosi:ident, $pin_mosi:literal),
-                //ss: ($X_ss:ident/$x_ss:ident, $pin_ss:literal),
+                ss: ($X_ss:ident/$x_ss:ident, $pin_ss:literal),
             },
         )+]
     }) => {
@@ -378,6 +654,17 @@ macro_rules! spi {
             fn clock(clocks: &Clocks) -> Hertz {
                 clocks.per()
             }
+
+            fn ptr() -> *const RegisterBlock {
+                crate::pac::$SPI::ptr()
+            }
+
+            #[cfg(feature = "async")]
+            fn waker() -> &'static crate::asynch::WakerSlot {
+                static WAKER: crate::asynch::WakerSlot =
+                    avr_device::interrupt::Mutex::new(core::cell::RefCell::new(None));
+                &WAKER
+            }
         }
 
         impl crate::private::Sealed for crate::pac::$SPI {}
@@ -387,9 +674,12 @@ macro_rules! spi {
                 impl SckPin<$SPI> for crate::gpio::[<port $x_sck>]::[<P $X_sck $pin_sck>]<Output<Stateless>> {}
                 impl MisoPin<$SPI> for crate::gpio::[<port $x_miso>]::[<P $X_miso $pin_miso>]<Input> {}
                 impl MosiPin<$SPI> for crate::gpio::[<port $x_mosi>]::[<P $X_mosi $pin_mosi>]<Output<Stateless>> {}
-                // NOTE: should we ever use that pin, it is an output in master mode, but it needs to be an
-                //       input in slave mode, or when you want to dynamically switch between master and slave mode
-                //impl SsPin<$SPI> for crate::gpio::[<port $x_ss>]::[<P $X_ss $pin_ss>]<Output<Stateless>> {}
+                // NOTE: The pin is an input to detect other hosts in host mode. When the SPI should
+                //       select a single client, use an ordinary GPIO as chip select instead.
+                impl SsPin<$SPI> for crate::gpio::[<port $x_ss>]::[<P $X_ss $pin_ss>]<Input> {}
+                impl OptionalSs<$SPI> for crate::gpio::[<port $x_ss>]::[<P $X_ss $pin_ss>]<Input> {
+                    const ENABLED: bool = true;
+                }
             }
         )+
     };
@@ -404,13 +694,13 @@ spi!({
             sck: (A/a, 3),
             miso: (A/a, 2),
             mosi: (A/a, 1),
-            //ss: (A/a, 4),
+            ss: (A/a, 4),
         },
         {
             sck: (C/c, 0),
             miso: (C/c, 1),
             mosi: (C/c, 2),
-            //ss: (C/c, 3),
+            ss: (C/c, 3),
         },
     ]
 });
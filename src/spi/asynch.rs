@@ -0,0 +1,160 @@
+//! # Async SPI
+//!
+//! Implementation of the [`embedded_hal_async::spi::SpiBus`] trait for the
+//! [`Spi`] in [buffered](Buffered) mode.
+//!
+//! The task waiting for received data is woken up by the receive complete
+//! interrupt. Its interrupt handler has to call [`on_interrupt`] for the SPI
+//! instance:
+//!
+//! ```
+//! #[avr_device::interrupt(attiny817)]
+//! fn SPI0_INT() {
+//!     spi::asynch::on_interrupt::<pac::SPI0>();
+//! }
+//!
+//! let mut spi = Spi::new_buffered(dp.SPI0, spi_pair, 1_000_000.Hz(), clocks);
+//! spi.transfer_in_place(&mut buf).await.unwrap();
+//! ```
+//!
+//! Only the buffered mode is supported, as the interrupt flag of the
+//! unbuffered mode is cleared by hardware when the interrupt is executed.
+
+use core::cell::Cell;
+use core::cmp::max;
+use core::future::poll_fn;
+use core::task::Poll;
+
+use avr_device::interrupt;
+
+use crate::embedded_hal_async::spi::SpiBus;
+
+use super::{Buffered, Error, Instance, MisoPin, MosiPin, OptionalSs, SckPin, Spi, SpiPinset};
+
+/// Interrupt handler helper for the async [`SpiBus`] implementation
+///
+/// Disables the receive complete interrupt and wakes up the waiting task.
+/// Call this function from the SPI interrupt handler.
+#[inline]
+pub fn on_interrupt<SPI: Instance>() {
+    // NOTE(unsafe): Single read-modify-write from the interrupt handler while
+    // the task is waiting
+    let spi = unsafe { &*SPI::ptr() };
+    spi.intctrl().modify(|_, w| w.rxcie().clear_bit());
+
+    interrupt::free(|cs| {
+        if let Some(waker) = SPI::waker().borrow(cs).borrow_mut().take() {
+            waker.wake();
+        }
+    });
+}
+
+impl<SPI, SCK, MISO, MOSI, SS> Spi<SPI, Buffered, SpiPinset<SPI, SCK, MISO, MOSI, SS>>
+where
+    SPI: Instance,
+    SCK: SckPin<SPI>,
+    MISO: MisoPin<SPI>,
+    MOSI: MosiPin<SPI>,
+    SS: OptionalSs<SPI>,
+{
+    /// Wait until received data is available
+    async fn wait_for_data(&mut self) {
+        poll_fn(|cx| {
+            interrupt::free(|cs| {
+                if self.spi.intflags().read().rxcif().bit_is_set() {
+                    return Poll::Ready(());
+                }
+
+                let mut slot = SPI::waker().borrow(cs).borrow_mut();
+                match slot.as_ref() {
+                    Some(waker) if waker.will_wake(cx.waker()) => {}
+                    _ => *slot = Some(cx.waker().clone()),
+                }
+
+                // The flag is checked again in the critical section, so the
+                // interrupt can't be missed
+                self.spi.intctrl().modify(|_, w| w.rxcie().set_bit());
+                Poll::Pending
+            })
+        })
+        .await
+    }
+
+    /// Async version of `exchange` in buffered mode
+    async fn exchange_async(
+        &mut self,
+        len: usize,
+        tx: impl Fn(usize) -> u8,
+        mut rx: impl FnMut(usize, u8),
+    ) -> Result<(), Error> {
+        let (mut sent, mut received) = (0, 0);
+        while received < len {
+            self.check_mode_fault()?;
+            let intflags = self.spi.intflags().read();
+
+            if intflags.bufovf().bit_is_set() {
+                self.spi.intflags().write(|w| w.bufovf().set_bit());
+                return Err(Error::Overrun);
+            }
+
+            // Keep at most two bytes in flight, see `exchange`
+            if sent < len && sent - received < 2 && intflags.dreif().bit_is_set() {
+                self.spi.data().write(|w| w.bits(tx(sent)));
+                sent += 1;
+                continue;
+            }
+
+            if intflags.rxcif().bit_is_set() {
+                rx(received, self.spi.data().read().bits());
+                received += 1;
+            } else {
+                self.wait_for_data().await;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<SPI, SCK, MISO, MOSI, SS> SpiBus for Spi<SPI, Buffered, SpiPinset<SPI, SCK, MISO, MOSI, SS>>
+where
+    SPI: Instance,
+    SCK: SckPin<SPI>,
+    MISO: MisoPin<SPI>,
+    MOSI: MosiPin<SPI>,
+    SS: OptionalSs<SPI>,
+{
+    async fn read(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
+        self.exchange_async(words.len(), |_| 0xff, |i, b| words[i] = b)
+            .await
+    }
+
+    async fn write(&mut self, words: &[u8]) -> Result<(), Self::Error> {
+        self.exchange_async(words.len(), |i| words[i], |_, _| {})
+            .await
+    }
+
+    async fn transfer(&mut self, read: &mut [u8], write: &[u8]) -> Result<(), Self::Error> {
+        self.exchange_async(
+            max(read.len(), write.len()),
+            |i| write.get(i).copied().unwrap_or(0xff),
+            |i, b| {
+                if let Some(r) = read.get_mut(i) {
+                    *r = b;
+                }
+            },
+        )
+        .await
+    }
+
+    async fn transfer_in_place(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
+        // Each byte is sent before its received replacement is stored
+        let words = Cell::from_mut(words).as_slice_of_cells();
+        self.exchange_async(words.len(), |i| words[i].get(), |i, b| words[i].set(b))
+            .await
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
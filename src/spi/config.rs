@@ -1,9 +1,27 @@
+//! Types for configuring a SPI bus.
+
 use core::fmt;
 
 use crate::embedded_hal::spi::{self, Mode};
 use crate::pac::spi0::ctrla::DORD_A;
 use crate::time::*;
 
+/// Configuration struct for [`Spi`](super::Spi) providing the SPI mode, the
+/// data order and the bus clock frequency.
+///
+/// Create a configuration by using `default` in combination with the
+/// builder methods. The following snippet shows creating a configuration
+/// for a mode 3 device transmitting the least significant bit first at 2MHz:
+/// ```
+/// # use crate::spi::config::*;
+/// let config = Config::default()
+///     .frequency(2_000_000.Hz())
+///     .mode(embedded_hal::spi::MODE_3)
+///     .data_order(DataOrder::LsbFirst);
+/// ```
+///
+/// The frequency is approximated by one of the possible prescalers of the
+/// peripheral clock.
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub struct Config {
     /// SPI bus clock frequency
@@ -14,6 +32,7 @@ pub struct Config {
     pub order: DataOrder,
 }
 
+/// Order in which the bits of a byte are transmitted
 #[derive(ufmt::derive::uDebug, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DataOrder {
     /// Transmit the most significant bit first
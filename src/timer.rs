@@ -14,6 +14,7 @@ pub mod rtc;
 pub mod tca;
 pub mod tcb;
 pub mod tcb_8bit;
+pub mod tcd;
 
 use crate::time::*;
 
@@ -123,12 +124,17 @@ mod sealed {
         fn get_compare_match(channel: u8) -> bool;
     }
 
+    pub trait RunModes: General {
+        fn configure_run_in_standby(&mut self, enable: impl Into<Toggle>);
+        fn configure_debug_run(&mut self, enable: impl Into<Toggle>);
+    }
+
     // FIXME: we need a working event system for TCB single shot mode, I think
     //pub trait SingleShotMode: General {
     //    fn set_single_shot_mode(&mut self);
     //}
 }
-pub(crate) use sealed::{AsClockSource, General, PeriodicMode, WithPwm};
+pub(crate) use sealed::{AsClockSource, General, PeriodicMode, RunModes, WithPwm};
 
 /// A trait describing one or multiple clock inputs for a timer
 pub trait TimerClock {
@@ -4,12 +4,17 @@
 use enumset::EnumSetType;
 
 use crate::{
+    clkctrl::XOsc32K,
     pac::{rtc::ctrla, RTC},
     time::*,
     Toggle,
 };
 
-use super::{General, Instance, PeriodicMode, TimerClock};
+use core::cell::Cell;
+
+use avr_device::interrupt::{self, Mutex};
+
+use super::{Error, General, Instance, PeriodicMode, RunModes, TimerClock};
 
 /// Interrupts for RTC
 #[derive(ufmt::derive::uDebug, Debug)]
@@ -34,7 +39,7 @@ pub enum Event {
 pub enum RTCClockSource {
     OSCULP32K_32K,
     OSCULP32K_1K,
-    //XOSC32K,          // FIXME: retrieve an object for this from CLKCTRL and enable it when doing so
+    XOSC32K(XOsc32K),
     TOSC1(Hertz),
 }
 
@@ -48,7 +53,7 @@ impl TimerClock for RTC {
         match clk {
             RTCClockSource::OSCULP32K_32K => 32_768.Hz(),
             RTCClockSource::OSCULP32K_1K => 1_024.Hz(),
-            //RTCClockSource::XOSC32K => 32_768.Hz(),
+            RTCClockSource::XOSC32K(_) => XOsc32K::FREQUENCY.Hz(),
             RTCClockSource::TOSC1(h) => h,
         }
     }
@@ -58,7 +63,7 @@ impl TimerClock for RTC {
         match clk {
             RTCClockSource::OSCULP32K_32K => self.clksel().write(|w| w.clksel().int32k()),
             RTCClockSource::OSCULP32K_1K => self.clksel().write(|w| w.clksel().int1k()),
-            //RTCClockSource::XOSC32K => self.clksel().write(|w| w.clksel().tosc32k()),
+            RTCClockSource::XOSC32K(_) => self.clksel().write(|w| w.clksel().tosc32k()),
             RTCClockSource::TOSC1(_) => self.clksel().write(|w| w.clksel().extclk()),
         }
     }
@@ -198,6 +203,172 @@ impl PeriodicMode for RTC {
     }
 }
 
+impl RunModes for RTC {
+    #[inline(always)]
+    fn configure_run_in_standby(&mut self, enable: impl Into<Toggle>) {
+        // Do a round way trip to be convert Into<Toggle> -> bool
+        let enable: Toggle = enable.into();
+        let enable: bool = enable.into();
+
+        while self.status().read().ctrlabusy().bit_is_set() {}
+        self.ctrla().modify(|_, w| w.runstdby().bit(enable));
+    }
+
+    #[inline(always)]
+    fn configure_debug_run(&mut self, enable: impl Into<Toggle>) {
+        // Do a round way trip to be convert Into<Toggle> -> bool
+        let enable: Toggle = enable.into();
+        let enable: bool = enable.into();
+
+        self.dbgctrl().write(|w| w.dbgrun().bit(enable));
+    }
+}
+
+/// Tick rate of the [`Wallclock`]
+pub const WALLCLOCK_TICK_RATE: u32 = 1024;
+
+/// Point in time measured by the [`Wallclock`]
+pub type WallclockInstant = fugit::TimerInstantU64<WALLCLOCK_TICK_RATE>;
+
+/// Seconds counted by the [`Wallclock`] in the overflow interrupt
+static WALLCLOCK_SECONDS: Mutex<Cell<u32>> = Mutex::new(Cell::new(0));
+
+/// Wallclock based on the RTC
+///
+/// The RTC is configured to count with [`WALLCLOCK_TICK_RATE`] and to overflow
+/// once per second. The overflow interrupt extends the 16 bit counter by a 32
+/// bit seconds counter, so the wallclock covers more than 136 years.
+/// The interrupt handler of the RTC overflow interrupt needs to call
+/// [`Wallclock::on_overflow`]:
+///
+/// ```
+/// #[avr_device::interrupt(attiny817)]
+/// fn RTC_CNT() {
+///     Wallclock::on_overflow();
+/// }
+///
+/// let clock = Wallclock::new(dp.RTC, RTCClockSource::OSCULP32K_32K).unwrap();
+/// unsafe { avr_device::interrupt::enable() };
+///
+/// let timestamp = Wallclock::now();
+/// ```
+///
+/// The time is counted since the wallclock was created, or since the epoch
+/// that was set using [`Wallclock::set_seconds`], e.g. the UNIX epoch.
+pub struct Wallclock {
+    rtc: RTC,
+}
+
+impl Wallclock {
+    /// Configure the RTC as wallclock and start it
+    ///
+    /// Returns [`Error::ImpossiblePrescaler`] if the clock source can't be
+    /// divided down to [`WALLCLOCK_TICK_RATE`].
+    pub fn new(mut rtc: RTC, clk: RTCClockSource) -> Result<Self, Error> {
+        let rate = RTC::get_input_clock_rate(clk).raw();
+        if rate % WALLCLOCK_TICK_RATE != 0 {
+            return Err(Error::ImpossiblePrescaler);
+        }
+
+        let psc = rate / WALLCLOCK_TICK_RATE;
+        if psc > u16::MAX as u32 || !RTC::is_prescaler_valid(psc as u16, clk) {
+            return Err(Error::ImpossiblePrescaler);
+        }
+
+        rtc.disable_counter();
+        rtc.prepare_clock_source(clk);
+        rtc.set_prescaler(psc as u16);
+        rtc.set_period((WALLCLOCK_TICK_RATE - 1) as u16)?;
+        rtc.reset_count();
+        rtc.clear_overflow();
+        rtc.configure_interrupt(Interrupt::Overflow, Toggle::On);
+
+        interrupt::free(|cs| WALLCLOCK_SECONDS.borrow(cs).set(0));
+
+        rtc.enable_counter();
+
+        Ok(Self { rtc })
+    }
+
+    /// Advance the wallclock by one second
+    ///
+    /// Has to be called from the RTC overflow interrupt handler.
+    #[inline]
+    pub fn on_overflow() {
+        // NOTE(unsafe): Single write to a flag register
+        let rtc = unsafe { &*RTC::ptr() };
+        rtc.intflags().write(|w| w.ovf().set_bit());
+
+        interrupt::free(|cs| {
+            let seconds = WALLCLOCK_SECONDS.borrow(cs);
+            seconds.set(seconds.get().wrapping_add(1));
+        });
+    }
+
+    /// Returns the current time
+    ///
+    /// This function can be called from any context, including interrupt
+    /// handlers. An overflow that is pending, but not yet handled by the
+    /// interrupt, is taken into account.
+    pub fn now() -> WallclockInstant {
+        // NOTE(unsafe): Only reads without side effects
+        let rtc = unsafe { &*RTC::ptr() };
+
+        interrupt::free(|cs| {
+            let mut seconds = WALLCLOCK_SECONDS.borrow(cs).get();
+            let mut ticks = rtc.cnt().read().bits();
+
+            // The counter might have wrapped after the seconds were read
+            if rtc.intflags().read().ovf().bit_is_set() {
+                seconds = seconds.wrapping_add(1);
+                ticks = rtc.cnt().read().bits();
+            }
+
+            WallclockInstant::from_ticks(seconds as u64 * WALLCLOCK_TICK_RATE as u64 + ticks as u64)
+        })
+    }
+
+    /// Returns the whole seconds of the current time
+    #[inline]
+    pub fn seconds() -> u32 {
+        (Self::now().ticks() / WALLCLOCK_TICK_RATE as u64) as u32
+    }
+
+    /// Set the current time in seconds, e.g. relative to the UNIX epoch
+    ///
+    /// The fraction of the current second is not changed.
+    pub fn set_seconds(&mut self, seconds: u32) {
+        interrupt::free(|cs| WALLCLOCK_SECONDS.borrow(cs).set(seconds));
+    }
+
+    /// Enable or disable counting while the device is in standby sleep mode
+    ///
+    /// By default the RTC stops counting in standby, which stops the
+    /// wallclock as well.
+    #[doc(alias = "RUNSTDBY")]
+    #[inline]
+    pub fn set_run_in_standby(&mut self, enable: impl Into<Toggle>) {
+        self.rtc.configure_run_in_standby(enable);
+    }
+
+    /// Enable or disable counting while the CPU is halted by a debugger
+    ///
+    /// By default the RTC stops counting when the CPU is halted.
+    #[doc(alias = "DBGRUN")]
+    #[inline]
+    pub fn set_debug_run(&mut self, enable: impl Into<Toggle>) {
+        self.rtc.configure_debug_run(enable);
+    }
+
+    /// Stops the wallclock and releases the RTC peripheral
+    pub fn release(mut self) -> RTC {
+        self.rtc
+            .configure_interrupt(Interrupt::Overflow, Toggle::Off);
+        self.rtc.disable_counter();
+        self.rtc
+    }
+}
+
 // FIXME: implement compare mode for RTC
 // FIXME: implement PIT in RTC
 
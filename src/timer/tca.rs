@@ -6,7 +6,7 @@
 #[cfg(feature = "enumset")]
 use enumset::EnumSetType;
 
-use crate::{clkctrl::Clocks, pac::tca0::*, time::*, Toggle};
+use crate::{clkctrl::Clocks, gpio::Release, pac::tca0::*, time::*, Toggle};
 
 /// Enum for waveform genreation modes
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -171,11 +171,12 @@ impl super::General for TCA0 {
 
     #[inline(always)]
     fn clear_event(&mut self, event: Self::Event) {
+        // Writing a one clears a flag, so the other flags must not be written back
         match event {
-            Event::Overflow => self.single_intflags().modify(|_, w| w.ovf().set_bit()),
-            Event::CompareChannel0 => self.single_intflags().modify(|_, w| w.cmp0().set_bit()),
-            Event::CompareChannel1 => self.single_intflags().modify(|_, w| w.cmp1().set_bit()),
-            Event::CompareChannel2 => self.single_intflags().modify(|_, w| w.cmp2().set_bit()),
+            Event::Overflow => self.single_intflags().write(|w| w.ovf().set_bit()),
+            Event::CompareChannel0 => self.single_intflags().write(|w| w.cmp0().set_bit()),
+            Event::CompareChannel1 => self.single_intflags().write(|w| w.cmp1().set_bit()),
+            Event::CompareChannel2 => self.single_intflags().write(|w| w.cmp2().set_bit()),
         }
     }
 }
@@ -210,7 +211,7 @@ impl super::PeriodicMode for TCA0 {
 
     #[inline(always)]
     fn clear_overflow(&mut self) {
-        self.single_intflags().modify(|_, w| w.ovf().set_bit());
+        self.single_intflags().write(|w| w.ovf().set_bit());
     }
 
     #[inline(always)]
@@ -263,9 +264,9 @@ impl super::WithPwm for TCA0 {
     fn clear_compare_match(channel: u8) {
         let tim = unsafe { &*TCA0::ptr() };
         match channel {
-            0 => tim.single_intflags().modify(|_, w| w.cmp0().set_bit()),
-            1 => tim.single_intflags().modify(|_, w| w.cmp1().set_bit()),
-            2 => tim.single_intflags().modify(|_, w| w.cmp2().set_bit()),
+            0 => tim.single_intflags().write(|w| w.cmp0().set_bit()),
+            1 => tim.single_intflags().write(|w| w.cmp1().set_bit()),
+            2 => tim.single_intflags().write(|w| w.cmp2().set_bit()),
             _ => panic!("invalid channel number"),
         }
     }
@@ -337,9 +338,21 @@ where
         }
     }
 
+    /// Returns the pin in the mode used by the peripheral
     pub fn free(self) -> WaveformOutput {
         self.output
     }
+
+    /// Releases the pin back to GPIO
+    ///
+    /// The pin is converted back into a floating input, so it can be reused
+    /// after the peripheral has been shut down.
+    pub fn release(self) -> WaveformOutput::Released
+    where
+        WaveformOutput: Release,
+    {
+        self.output.release()
+    }
 }
 
 impl<WaveformOutput: WaveformOutputPin<TCA0, CHAN>, const CHAN: u8> WaveformOutputPinset<TCA0, CHAN>
@@ -362,3 +375,184 @@ impl WaveformOutputPin<TCA0, C3> for crate::gpio::portb::PB5<Output<Stateless>>
 //impl WaveformOutputPin<TCA0, C4> for crate::gpio::portc::PC3<Output<Stateless>> {}
 //impl WaveformOutputPin<TCA0, C5> for crate::gpio::portc::PC4<Output<Stateless>> {}
 //impl WaveformOutputPin<TCA0, C6> for crate::gpio::portc::PC5<Output<Stateless>> {}
+
+use super::pwm::PwmChannel;
+use crate::evsys::ChannelConfigurator;
+use crate::evsys::{Channel, EventGenerator, GeneratorAssigned, Unconfigured};
+
+/// Compare match interrupts of the single PWM channels
+///
+/// The channels can be handled independently after splitting the PWM, e.g. to
+/// update the duty cycle at a defined point of the PWM period.
+impl<const C: u8> PwmChannel<TCA0, C> {
+    /// Enable or disable the compare match interrupt of this channel
+    #[doc(alias = "CMP0")]
+    #[doc(alias = "CMP1")]
+    #[doc(alias = "CMP2")]
+    pub fn configure_interrupt(&mut self, enable: impl Into<Toggle>) {
+        let enable: Toggle = enable.into();
+        let enable: bool = enable.into();
+        let tim = unsafe { &*TCA0::ptr() };
+
+        // INTCTRL is shared with the other channels and the timer
+        avr_device::interrupt::free(|_| {
+            tim.single_intctrl().modify(|_, w| match C {
+                0 => w.cmp0().bit(enable),
+                1 => w.cmp1().bit(enable),
+                2 => w.cmp2().bit(enable),
+                _ => panic!("invalid channel number"),
+            })
+        });
+    }
+
+    /// Check if the compare match interrupt of this channel is enabled
+    pub fn is_interrupt_configured(&self) -> bool {
+        let intctrl = unsafe { &*TCA0::ptr() }.single_intctrl().read();
+        match C {
+            0 => intctrl.cmp0().bit(),
+            1 => intctrl.cmp1().bit(),
+            2 => intctrl.cmp2().bit(),
+            _ => panic!("invalid channel number"),
+        }
+    }
+
+    /// Check if the counter has matched the compare value of this channel
+    #[inline]
+    pub fn is_compare_match(&self) -> bool {
+        <TCA0 as super::WithPwm>::get_compare_match(C)
+    }
+
+    /// Clear the compare match flag of this channel
+    #[inline]
+    pub fn clear_compare_match(&mut self) {
+        <TCA0 as super::WithPwm>::clear_compare_match(C)
+    }
+}
+
+/// The compare match of a PWM channel can be used as a synchronous event,
+/// e.g. to start an ADC conversion at a defined point of the PWM period.
+impl<Evsys, Index, const C: u8> EventGenerator<Evsys, crate::evsys::Sync, Index>
+    for PwmChannel<TCA0, C>
+where
+    Evsys: crate::evsys::marker::Evsys,
+    Index: crate::evsys::marker::Index,
+{
+    type EventSource = ();
+
+    fn connect_event_generator(
+        &mut self,
+        mut channel: Channel<Evsys, crate::evsys::Sync, Index, Unconfigured>,
+        _source: (),
+    ) -> Channel<Evsys, crate::evsys::Sync, Index, GeneratorAssigned> {
+        // TCA0_CMP0 is generator 0x04, followed by CMP1 and CMP2
+        channel.set_generator(0x04 + C);
+        channel.into_state()
+    }
+}
+
+/// Edges of the event counted by a [`PulseCounter`]
+#[derive(ufmt::derive::uDebug, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CountEdge {
+    /// Count rising edges
+    Rising,
+
+    /// Count rising and falling edges
+    Both,
+}
+
+/// Counter of external pulses routed through the event system
+///
+/// The TCB of the 1-series can't be clocked from an event, so the counting is
+/// done by TCA0, which counts the edges of a synchronous event channel
+/// instead of its clock. Pulses on a pin can be counted by connecting the pin
+/// as the generator and the counter as the user of a synchronous channel:
+///
+/// ```
+/// let mut counter = PulseCounter::new(dp.TCA0, CountEdge::Rising);
+///
+/// let mut sensor = a.pa3.into_floating_input();
+/// let _channel = evsys
+///     .channel_sync0
+///     .route(&mut sensor, ())
+///     .to(&counter)
+///     .apply();
+///
+/// let pulses = counter.count();
+/// ```
+///
+/// As the event is synchronized to CLK_PER, pulses and pauses have to be
+/// longer than one CLK_PER cycle.
+pub struct PulseCounter {
+    tim: TCA0,
+}
+
+impl PulseCounter {
+    /// Configures TCA0 to count the edges of its event input
+    pub fn new(tim: TCA0, edge: CountEdge) -> Self {
+        tim.single_ctrla().write(|w| w.enable().clear_bit());
+        tim.single_ctrlb()
+            .write(|w| w.wgmode().variant(single_ctrlb::WGMODE_A::NORMAL));
+        tim.single_per().write(|w| w.bits(u16::MAX));
+        tim.single_cnt().reset();
+        tim.single_intflags().write(|w| w.ovf().set_bit());
+        tim.single_evctrl().write(|w| match edge {
+            CountEdge::Rising => w.evact().posedge().cntei().set_bit(),
+            CountEdge::Both => w.evact().anyedge().cntei().set_bit(),
+        });
+        tim.single_ctrla().write(|w| w.enable().set_bit());
+
+        Self { tim }
+    }
+
+    /// Returns the number of pulses counted since the last reset
+    #[inline]
+    pub fn count(&self) -> u16 {
+        self.tim.single_cnt().read().bits()
+    }
+
+    /// Resets the count to zero
+    #[inline]
+    pub fn reset(&mut self) {
+        self.tim.single_cnt().reset();
+        self.tim.single_intflags().write(|w| w.ovf().set_bit());
+    }
+
+    /// Returns the count and resets it to zero
+    ///
+    /// Pulses arriving between reading and resetting the count are lost.
+    pub fn take(&mut self) -> u16 {
+        avr_device::interrupt::free(|_| {
+            let count = self.count();
+            self.reset();
+            count
+        })
+    }
+
+    /// Check if the count wrapped around since the last reset
+    #[inline]
+    pub fn has_overflowed(&self) -> bool {
+        self.tim.single_intflags().read().ovf().bit_is_set()
+    }
+
+    /// Enables or disables the interrupt on a wrap around of the count
+    pub fn configure_overflow_interrupt(&mut self, enable: impl Into<Toggle>) {
+        let enable: Toggle = enable.into();
+        let enable: bool = enable.into();
+        self.tim.single_intctrl().modify(|_, w| w.ovf().bit(enable));
+    }
+
+    /// Stops counting and releases TCA0
+    ///
+    /// The event channel still has to be freed separately.
+    pub fn release(self) -> TCA0 {
+        self.tim.single_ctrla().write(|w| w.enable().clear_bit());
+        self.tim.single_evctrl().reset();
+        self.tim.single_intctrl().reset();
+        self.tim
+    }
+}
+
+/// TCA0 is the synchronous event user 0
+impl crate::evsys::EventUser<crate::evsys::Evsys, crate::evsys::Sync> for PulseCounter {
+    const MULTIPLEXER_INDEX: u8 = 0;
+}
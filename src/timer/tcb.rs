@@ -4,7 +4,7 @@
 use enumset::EnumSetType;
 
 use crate::pac::{TCA0, TCB0};
-use crate::{clkctrl::Clocks, pac::tcb0::ctrla, time::*, Toggle};
+use crate::{clkctrl::Clocks, gpio::Release, pac::tcb0::ctrla, time::*, Toggle};
 
 use super::tcb_8bit::TCB8Bit;
 
@@ -223,6 +223,26 @@ impl super::PeriodicMode for TCB0 {
     }
 }
 
+impl super::RunModes for TCB0 {
+    #[inline(always)]
+    fn configure_run_in_standby(&mut self, enable: impl Into<Toggle>) {
+        // Do a round way trip to be convert Into<Toggle> -> bool
+        let enable: Toggle = enable.into();
+        let enable: bool = enable.into();
+
+        self.ctrla().modify(|_, w| w.runstdby().bit(enable));
+    }
+
+    #[inline(always)]
+    fn configure_debug_run(&mut self, enable: impl Into<Toggle>) {
+        // Do a round way trip to be convert Into<Toggle> -> bool
+        let enable: Toggle = enable.into();
+        let enable: bool = enable.into();
+
+        self.dbgctrl().write(|w| w.dbgrun().bit(enable));
+    }
+}
+
 fn into_clksrc(prescaler: u16) -> ctrla::CLKSEL_A {
     use ctrla::CLKSEL_A::*;
     match prescaler {
@@ -267,9 +287,21 @@ where
         }
     }
 
+    /// Returns the pin in the mode used by the peripheral
     pub fn free(self) -> WaveformOutput {
         self.output
     }
+
+    /// Releases the pin back to GPIO
+    ///
+    /// The pin is converted back into a floating input, so it can be reused
+    /// after the peripheral has been shut down.
+    pub fn release(self) -> WaveformOutput::Released
+    where
+        WaveformOutput: Release,
+    {
+        self.output.release()
+    }
 }
 
 // TCB 8 Bit PWM mode outputs
@@ -3,6 +3,7 @@ use avr_device::attiny817::TCB0;
 use crate::{time::*, Toggle};
 
 use super::tcb::{Event, Interrupt};
+use super::RunModes;
 
 pub struct TCB8Bit {
     pub(crate) tim: TCB0,
@@ -137,6 +138,18 @@ impl super::PeriodicMode for TCB8Bit {
     }
 }
 
+impl RunModes for TCB8Bit {
+    #[inline(always)]
+    fn configure_run_in_standby(&mut self, enable: impl Into<Toggle>) {
+        self.tim.configure_run_in_standby(enable);
+    }
+
+    #[inline(always)]
+    fn configure_debug_run(&mut self, enable: impl Into<Toggle>) {
+        self.tim.configure_debug_run(enable);
+    }
+}
+
 impl super::WithPwm for TCB8Bit {
     const CH_NUMBER: u8 = 1;
     type GenerationMode = ();
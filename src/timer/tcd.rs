@@ -0,0 +1,1005 @@
+//! # 12-bit Timer/Counter Type D
+//!
+//! The TCD runs in its own clock domain. Most of its registers can only be
+//! written while the timer is disabled and the double buffered compare
+//! registers need an explicit synchronization command to be transferred into
+//! the timer domain. The implementation takes care of waiting for the
+//! `ENRDY` and `CMDRDY` status flags before enabling the timer or issuing
+//! commands, so the TCD can be used like any other timer.
+
+// TODO: support WOC and WOD
+
+#[cfg(feature = "enumset")]
+use enumset::EnumSetType;
+
+use core::convert::Infallible;
+
+use avr_device::ccp::ProtectedWritable;
+
+use super::{Error, FTimer, Timer};
+use crate::{clkctrl::Clocks, gpio::Release, pac::tcd0::*, time::*, Toggle};
+
+/// Enum for waveform generation modes
+///
+/// The meaning of the four compare registers depends on the selected mode.
+/// The duty cycle based PWM API only supports [`OneRamp`] mode. The other
+/// modes are configured by [setting the compare values](Timer::set_compare_values)
+/// directly.
+///
+/// [`OneRamp`]: WaveformGenerationMode::OneRamp
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WaveformGenerationMode {
+    /// One ramp mode
+    ///
+    /// The counter counts up to `CMPBCLR` once per cycle. WOA is active
+    /// between `CMPASET` and `CMPACLR`, WOB between `CMPBSET` and `CMPBCLR`.
+    OneRamp,
+
+    /// Two ramp mode
+    ///
+    /// The counter counts up to `CMPACLR` in the first ramp and up to `CMPBCLR`
+    /// in the second ramp. WOA is active from `CMPASET` to the end of the first
+    /// ramp, WOB from `CMPBSET` to the end of the second ramp. This allows
+    /// to generate non-overlapping signals with individual dead times.
+    TwoRamp,
+
+    /// Four ramp mode
+    ///
+    /// The counter counts up to `CMPASET`, `CMPACLR`, `CMPBSET` and `CMPBCLR`
+    /// in four consecutive ramps. WOA is active during the second and WOB during
+    /// the fourth ramp, while the first and third ramp act as dead times.
+    FourRamp,
+
+    /// Dual slope mode
+    ///
+    /// The counter counts down from `CMPBCLR` to zero and back up again. WOA is
+    /// active while the counter is below `CMPASET` and WOB while it is above
+    /// `CMPBSET`, which results in center-aligned signals.
+    DualSlope,
+}
+
+impl From<WaveformGenerationMode> for ctrlb::WGMODE_A {
+    fn from(value: WaveformGenerationMode) -> Self {
+        use ctrlb::WGMODE_A::*;
+        match value {
+            WaveformGenerationMode::OneRamp => ONERAMP,
+            WaveformGenerationMode::TwoRamp => TWORAMP,
+            WaveformGenerationMode::FourRamp => FOURRAMP,
+            WaveformGenerationMode::DualSlope => DS,
+        }
+    }
+}
+
+/// Values of the four compare registers of the TCD
+///
+/// See [`WaveformGenerationMode`] for their meaning in the different modes.
+/// All values are 12 bits wide.
+#[derive(ufmt::derive::uDebug, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CompareValues {
+    /// Value of the `CMPASET` register
+    pub a_set: u16,
+    /// Value of the `CMPACLR` register
+    pub a_clear: u16,
+    /// Value of the `CMPBSET` register
+    pub b_set: u16,
+    /// Value of the `CMPBCLR` register
+    pub b_clear: u16,
+}
+
+/// Action taken by the TCD when an input event occurs
+#[derive(ufmt::derive::uDebug, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputMode {
+    /// The input has no effect
+    None = 0x0,
+    /// Stop the output, jump to the opposite compare cycle and wait
+    JumpWait = 0x1,
+    /// Stop the output, execute the opposite compare cycle and wait
+    ExecuteWait = 0x2,
+    /// Stop the output, execute the opposite compare cycle while the fault
+    /// is active
+    ExecuteFault = 0x3,
+    /// Stop all outputs, maintain the frequency
+    Frequency = 0x4,
+    /// Stop all outputs, execute a dead time while the fault is active
+    ExecuteDeadTime = 0x5,
+    /// Stop all outputs, jump to the next compare cycle and wait for the
+    /// fault to go away
+    Wait = 0x6,
+    /// Stop all outputs, wait for a software restart
+    WaitSoftware = 0x7,
+    /// Stop the output on the edge, jump to the next compare cycle
+    EdgeTrigger = 0x8,
+    /// Stop the output on the edge, maintain the frequency
+    EdgeTriggerFrequency = 0x9,
+    /// Stop the output at the level, maintain the frequency
+    LevelTriggerFrequency = 0xA,
+}
+
+/// Active edge or level of an input event
+#[derive(ufmt::derive::uDebug, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputEdge {
+    /// Falling edge or low level
+    FallingLow,
+    /// Rising edge or high level
+    RisingHigh,
+}
+
+/// Filtering of an input event
+#[derive(ufmt::derive::uDebug, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputFilter {
+    /// The event is used as is
+    None = 0x0,
+    /// The event is filtered by a digital filter
+    Digital = 0x1,
+    /// The event is detected asynchronously, which allows to react on it
+    /// even faster than one TCD clock cycle
+    Async = 0x2,
+}
+
+/// Selects which time is extended by the dither accumulator
+#[derive(ufmt::derive::uDebug, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DitherSelection {
+    /// On-time of WOB
+    OnTimeB = 0x0,
+    /// On-time of WOA and WOB
+    OnTimeAB = 0x1,
+    /// Dead time before WOB
+    DeadTimeB = 0x2,
+    /// Dead time before WOA and WOB
+    DeadTimeAB = 0x3,
+}
+
+/// Configuration of the event inputs A and B of the TCD
+#[derive(ufmt::derive::uDebug, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InputConfig {
+    /// Action taken when the event occurs
+    pub mode: InputMode,
+    /// Active edge or level of the event
+    pub edge: InputEdge,
+    /// Filtering of the event
+    pub filter: InputFilter,
+}
+
+impl InputConfig {
+    /// Create a new input configuration without any filtering
+    pub fn new(mode: InputMode, edge: InputEdge) -> Self {
+        Self {
+            mode,
+            edge,
+            filter: InputFilter::None,
+        }
+    }
+
+    /// Set the filtering of the input event
+    pub fn filter(mut self, filter: InputFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+}
+
+/// Event input A or B of a TCD
+///
+/// Acquired by calling [`Timer::event_input_a`] or [`Timer::event_input_b`]
+/// and used to connect the input to an event channel.
+pub struct EventInput<TIM, const X: u8> {
+    _tim: PhantomData<TIM>,
+}
+
+impl<TIM, const X: u8> crate::private::Sealed for EventInput<TIM, X> {}
+
+use crate::evsys::{Async, EventUser, Evsys};
+
+impl EventUser<Evsys, Async> for EventInput<TCD0, 0> {
+    const MULTIPLEXER_INDEX: u8 = 6;
+}
+
+impl EventUser<Evsys, Async> for EventInput<TCD0, 1> {
+    const MULTIPLEXER_INDEX: u8 = 7;
+}
+
+/// Interrupts for TCD
+#[derive(ufmt::derive::uDebug, Debug)]
+#[cfg_attr(feature = "enumset", derive(EnumSetType))]
+#[cfg_attr(not(feature = "enumset"), derive(Copy, Clone, PartialEq, Eq))]
+pub enum Interrupt {
+    /// Overflow interrupt
+    Overflow,
+
+    /// Trigger A interrupt
+    TriggerA,
+
+    /// Trigger B interrupt
+    TriggerB,
+}
+
+/// Status events for TCD
+#[derive(ufmt::derive::uDebug, Debug)]
+#[cfg_attr(feature = "enumset", derive(EnumSetType))]
+#[cfg_attr(not(feature = "enumset"), derive(Copy, Clone, PartialEq, Eq))]
+pub enum Event {
+    /// Overflow interrupt
+    Overflow,
+
+    /// Trigger A interrupt
+    TriggerA,
+
+    /// Trigger B interrupt
+    TriggerB,
+}
+
+use crate::pac::TCD0;
+
+/// Wait until the TCD is ready to accept a new command
+#[inline(always)]
+fn wait_cmdrdy(tim: &RegisterBlock) {
+    while tim.status().read().cmdrdy().bit_is_clear() {}
+}
+
+/// Execute `f` with the TCD disabled
+///
+/// The enable-protected registers of the TCD can only be written while the
+/// timer is disabled. The timer is enabled again afterwards, if it was
+/// running before.
+fn with_disabled<R>(tim: &RegisterBlock, f: impl FnOnce(&RegisterBlock) -> R) -> R {
+    let enabled = tim.ctrla().read().enable().bit_is_set();
+    if enabled {
+        tim.ctrla().modify(|_, w| w.enable().clear_bit());
+    }
+
+    let r = f(tim);
+
+    if enabled {
+        while tim.status().read().enrdy().bit_is_clear() {}
+        tim.ctrla().modify(|_, w| w.enable().set_bit());
+    }
+
+    r
+}
+
+/// Transfer the double buffered compare registers at the end of the cycle
+#[inline(always)]
+fn synchronize(tim: &RegisterBlock) {
+    if tim.ctrla().read().enable().bit_is_set() {
+        wait_cmdrdy(tim);
+        tim.ctrle().write(|w| w.synceoc().set_bit());
+    }
+}
+
+impl super::Instance for TCD0 {}
+
+/// Clock sources of the TCD
+///
+/// The TCD runs in its own clock domain that can be clocked independently from
+/// the peripheral clock. It has a synchronization prescaler and a counter
+/// prescaler, which are combined into a single prescaler by the timer API.
+#[derive(Clone, Copy)]
+pub enum TCDClockSource {
+    /// The peripheral clock (`SYSCLK`)
+    Peripheral(Clocks),
+    /// The internal 16/20MHz oscillator running at the passed frequency
+    ///
+    /// The frequency depends on the `OSCCFG` fuse. The oscillator is started
+    /// automatically when requested by the TCD.
+    Osc20M(Hertz),
+    /// The external clock input `EXTCLK` running at the passed frequency
+    External(Hertz),
+}
+
+impl ufmt::uDebug for TCDClockSource {
+    fn fmt<W>(&self, f: &mut ufmt::Formatter<'_, W>) -> Result<(), W::Error>
+    where
+        W: ufmt::uWrite + ?Sized,
+    {
+        match self {
+            TCDClockSource::Peripheral(_) => f.write_str("SYSCLK"),
+            TCDClockSource::Osc20M(c) => {
+                f.debug_struct("OSC20M")?.field("Rate", &c.raw())?.finish()
+            }
+            TCDClockSource::External(c) => {
+                f.debug_struct("EXTCLK")?.field("Rate", &c.raw())?.finish()
+            }
+        }
+    }
+}
+
+impl From<Clocks> for TCDClockSource {
+    fn from(clocks: Clocks) -> Self {
+        TCDClockSource::Peripheral(clocks)
+    }
+}
+
+impl super::TimerClock for TCD0 {
+    type ClockSource = TCDClockSource;
+
+    #[inline(always)]
+    fn get_input_clock_rate(clk: Self::ClockSource) -> Hertz {
+        match clk {
+            TCDClockSource::Peripheral(clocks) => clocks.per(),
+            TCDClockSource::Osc20M(clk) => clk,
+            TCDClockSource::External(clk) => clk,
+        }
+    }
+
+    #[inline(always)]
+    fn prepare_clock_source(&mut self, clk: Self::ClockSource) {
+        let clksel = match clk {
+            TCDClockSource::Osc20M(_) => 0x0,
+            TCDClockSource::External(_) => 0x2,
+            TCDClockSource::Peripheral(_) => 0x3,
+        };
+
+        // NOTE(unsafe): The clock selections are all valid
+        with_disabled(self, |tim| {
+            tim.ctrla()
+                .modify(|_, w| unsafe { w.clksel().bits(clksel) })
+        });
+    }
+
+    #[inline(always)]
+    fn set_prescaler(&mut self, psc: u16) {
+        let (syncpres, cntpres) = into_prescalers(psc);
+        // NOTE(unsafe): The prescaler bits are always valid
+        with_disabled(self, |tim| {
+            tim.ctrla()
+                .modify(|_, w| unsafe { w.syncpres().bits(syncpres).cntpres().bits(cntpres) })
+        });
+    }
+
+    #[inline(always)]
+    fn read_prescaler(&self) -> u16 {
+        let ctrla = self.ctrla().read();
+        from_prescalers(ctrla.syncpres().bits(), ctrla.cntpres().bits())
+    }
+
+    #[inline(always)]
+    fn get_valid_prescalers(_clk: Self::ClockSource) -> &'static [u16] {
+        &[1, 2, 4, 8, 16, 32, 64, 128, 256]
+    }
+}
+
+impl super::General for TCD0 {
+    const TIMER_WIDTH_BITS: u8 = 12;
+    type CounterValue = u16;
+    type Interrupt = Interrupt;
+    type Event = Event;
+
+    #[inline(always)]
+    fn reset_counter_peripheral(&mut self) {
+        self.ctrla().modify(|_, w| w.enable().clear_bit());
+    }
+
+    #[inline(always)]
+    fn enable_counter(&mut self) {
+        while self.status().read().enrdy().bit_is_clear() {}
+        self.ctrla().modify(|_, w| w.enable().set_bit());
+    }
+
+    #[inline(always)]
+    fn disable_counter(&mut self) {
+        self.ctrla().modify(|_, w| w.enable().clear_bit());
+    }
+
+    #[inline(always)]
+    fn is_counter_enabled(&self) -> bool {
+        self.ctrla().read().enable().bit_is_set()
+    }
+
+    #[inline(always)]
+    fn reset_count(&mut self) {
+        if self.is_counter_enabled() {
+            wait_cmdrdy(self);
+            self.ctrle().write(|w| w.restart().set_bit());
+        }
+    }
+
+    #[inline(always)]
+    fn read_count(&self) -> Self::CounterValue {
+        // The counter can't be read directly, it needs to be captured first
+        wait_cmdrdy(self);
+        self.ctrle().write(|w| w.scapturea().set_bit());
+        wait_cmdrdy(self);
+        self.capturea().read().bits()
+    }
+
+    #[inline(always)]
+    fn configure_interrupt(&mut self, interrupt: Self::Interrupt, enable: impl Into<Toggle>) {
+        let enable: Toggle = enable.into();
+        let enable: bool = enable.into();
+        match interrupt {
+            Interrupt::Overflow => self.intctrl().modify(|_, w| w.ovf().bit(enable)),
+            Interrupt::TriggerA => self.intctrl().modify(|_, w| w.triga().bit(enable)),
+            Interrupt::TriggerB => self.intctrl().modify(|_, w| w.trigb().bit(enable)),
+        }
+    }
+
+    #[inline(always)]
+    fn is_interrupt_configured(&self, interrupt: Self::Interrupt) -> bool {
+        let intctrl = self.intctrl().read();
+        match interrupt {
+            Interrupt::Overflow => intctrl.ovf().bit(),
+            Interrupt::TriggerA => intctrl.triga().bit(),
+            Interrupt::TriggerB => intctrl.trigb().bit(),
+        }
+    }
+
+    #[inline(always)]
+    fn is_event_triggered(&self, event: Self::Event) -> bool {
+        let intflags = self.intflags().read();
+        match event {
+            Event::Overflow => intflags.ovf().bit(),
+            Event::TriggerA => intflags.triga().bit(),
+            Event::TriggerB => intflags.trigb().bit(),
+        }
+    }
+
+    #[inline(always)]
+    fn clear_event(&mut self, event: Self::Event) {
+        match event {
+            Event::Overflow => self.intflags().write(|w| w.ovf().set_bit()),
+            Event::TriggerA => self.intflags().write(|w| w.triga().set_bit()),
+            Event::TriggerB => self.intflags().write(|w| w.trigb().set_bit()),
+        }
+    }
+}
+
+impl super::PeriodicMode for TCD0 {
+    #[inline(always)]
+    fn set_periodic_mode(&mut self) {
+        with_disabled(self, |tim| {
+            tim.ctrlb()
+                .modify(|_, w| w.wgmode().variant(ctrlb::WGMODE_A::ONERAMP))
+        });
+    }
+
+    #[inline(always)]
+    unsafe fn set_period_unchecked(&mut self, period: u16) {
+        self.cmpbclr().write(|w| w.bits(period));
+    }
+
+    #[inline(always)]
+    fn read_period() -> Self::CounterValue {
+        let tim = unsafe { &*TCD0::ptr() };
+        tim.cmpbclr().read().bits()
+    }
+
+    #[inline(always)]
+    fn trigger_update(&mut self) {
+        synchronize(self);
+    }
+
+    #[inline(always)]
+    fn max_period() -> u16 {
+        0x0FFF
+    }
+
+    #[inline(always)]
+    fn clear_overflow(&mut self) {
+        self.intflags().write(|w| w.ovf().set_bit());
+    }
+
+    #[inline(always)]
+    fn get_overflow(&self) -> bool {
+        self.intflags().read().ovf().bit_is_set()
+    }
+}
+
+impl super::WithPwm for TCD0 {
+    const CH_NUMBER: u8 = 2;
+    type GenerationMode = WaveformGenerationMode;
+    type CompareValue = u16;
+
+    fn set_pwm_mode(&mut self, mode: Self::GenerationMode) {
+        with_disabled(self, |tim| {
+            tim.ctrlb().modify(|_, w| w.wgmode().variant(mode.into()));
+        });
+
+        if mode == WaveformGenerationMode::OneRamp {
+            // WOA starts at the beginning of the cycle, the duty cycle of WOA is
+            // set by CMPACLR and the one of WOB by CMPBSET
+            self.cmpaset().write(|w| w.bits(0));
+            self.cmpaclr().write(|w| w.bits(0));
+            self.cmpbset().write(|w| w.bits(Self::read_period()));
+            synchronize(self);
+        }
+    }
+
+    fn enable_channel(channel: u8, b: bool) {
+        let tim = unsafe { &*TCD0::ptr() };
+        let faultctrl = tim.faultctrl().read();
+        let (cmpaen, cmpben) = match channel {
+            0 => (b, faultctrl.cmpben().bit()),
+            1 => (faultctrl.cmpaen().bit(), b),
+            _ => panic!("invalid channel number"),
+        };
+
+        let (cmpa, cmpb) = (faultctrl.cmpa().bit(), faultctrl.cmpb().bit());
+
+        // FAULTCTRL is protected by the CCP and enable-protected
+        with_disabled(tim, |tim| {
+            tim.faultctrl().write_protected(|w| {
+                w.cmpaen()
+                    .bit(cmpaen)
+                    .cmpben()
+                    .bit(cmpben)
+                    .cmpa()
+                    .bit(cmpa)
+                    .cmpb()
+                    .bit(cmpb)
+            })
+        });
+    }
+
+    fn set_compare_value(channel: u8, value: Self::CompareValue) {
+        let tim = unsafe { &*TCD0::ptr() };
+        match channel {
+            0 => tim.cmpaclr().write(|w| w.bits(value)),
+            1 => {
+                let period = tim.cmpbclr().read().bits();
+                tim.cmpbset()
+                    .write(|w| w.bits(period.saturating_sub(value)))
+            }
+            _ => panic!("invalid channel number"),
+        }
+
+        synchronize(tim);
+    }
+
+    fn read_compare_value(channel: u8) -> Self::CompareValue {
+        let tim = unsafe { &*TCD0::ptr() };
+        match channel {
+            0 => tim.cmpaclr().read().bits(),
+            1 => tim
+                .cmpbclr()
+                .read()
+                .bits()
+                .saturating_sub(tim.cmpbset().read().bits()),
+            _ => panic!("invalid channel number"),
+        }
+    }
+
+    #[inline(always)]
+    fn clear_compare_match(channel: u8) {
+        let tim = unsafe { &*TCD0::ptr() };
+        match channel {
+            0 => tim.intflags().write(|w| w.triga().set_bit()),
+            1 => tim.intflags().write(|w| w.trigb().set_bit()),
+            _ => panic!("invalid channel number"),
+        }
+    }
+
+    #[inline(always)]
+    fn get_compare_match(channel: u8) -> bool {
+        let tim = unsafe { &*TCD0::ptr() };
+        match channel {
+            0 => tim.intflags().read().triga().bit_is_set(),
+            1 => tim.intflags().read().trigb().bit_is_set(),
+            _ => panic!("invalid channel number"),
+        }
+    }
+}
+
+macro_rules! tcd_timer {
+    ($($timer:ty $(, $FREQ:ident)?;)+) => {
+        $(
+            impl$(<const $FREQ: u32>)? $timer {
+                /// Select the [`WaveformGenerationMode`] of the TCD
+                ///
+                /// The timer is disabled shortly while the mode is changed.
+                pub fn set_waveform_mode(&mut self, mode: WaveformGenerationMode) {
+                    with_disabled(&self.tim, |tim| {
+                        tim.ctrlb().modify(|_, w| w.wgmode().variant(mode.into()));
+                    });
+                }
+
+                /// Returns the currently selected [`WaveformGenerationMode`]
+                pub fn waveform_mode(&self) -> WaveformGenerationMode {
+                    use ctrlb::WGMODE_A::*;
+                    match self.tim.ctrlb().read().wgmode().variant() {
+                        ONERAMP => WaveformGenerationMode::OneRamp,
+                        TWORAMP => WaveformGenerationMode::TwoRamp,
+                        FOURRAMP => WaveformGenerationMode::FourRamp,
+                        DS => WaveformGenerationMode::DualSlope,
+                    }
+                }
+
+                /// Set all four compare registers at once
+                ///
+                /// The new values are taken over at the end of the current cycle.
+                pub fn set_compare_values(&mut self, values: CompareValues) {
+                    self.tim.cmpaset().write(|w| w.bits(values.a_set));
+                    self.tim.cmpaclr().write(|w| w.bits(values.a_clear));
+                    self.tim.cmpbset().write(|w| w.bits(values.b_set));
+                    self.tim.cmpbclr().write(|w| w.bits(values.b_clear));
+                    synchronize(&self.tim);
+                }
+
+                /// Read back all four compare registers
+                pub fn compare_values(&self) -> CompareValues {
+                    CompareValues {
+                        a_set: self.tim.cmpaset().read().bits(),
+                        a_clear: self.tim.cmpaclr().read().bits(),
+                        b_set: self.tim.cmpbset().read().bits(),
+                        b_clear: self.tim.cmpbclr().read().bits(),
+                    }
+                }
+
+                /// Set the `CMPASET` register
+                #[inline]
+                pub fn set_compare_a_set(&mut self, value: u16) {
+                    self.tim.cmpaset().write(|w| w.bits(value));
+                    synchronize(&self.tim);
+                }
+
+                /// Set the `CMPACLR` register
+                #[inline]
+                pub fn set_compare_a_clear(&mut self, value: u16) {
+                    self.tim.cmpaclr().write(|w| w.bits(value));
+                    synchronize(&self.tim);
+                }
+
+                /// Set the `CMPBSET` register
+                #[inline]
+                pub fn set_compare_b_set(&mut self, value: u16) {
+                    self.tim.cmpbset().write(|w| w.bits(value));
+                    synchronize(&self.tim);
+                }
+
+                /// Set the `CMPBCLR` register
+                #[inline]
+                pub fn set_compare_b_clear(&mut self, value: u16) {
+                    self.tim.cmpbclr().write(|w| w.bits(value));
+                    synchronize(&self.tim);
+                }
+
+                /// Returns the event input A to connect it to an event channel
+                pub fn event_input_a(&self) -> EventInput<TCD0, 0> {
+                    EventInput { _tim: PhantomData }
+                }
+
+                /// Returns the event input B to connect it to an event channel
+                pub fn event_input_b(&self) -> EventInput<TCD0, 1> {
+                    EventInput { _tim: PhantomData }
+                }
+
+                /// Configure the action of the event input A
+                ///
+                /// Input A affects WOA in the modes that stop a single output.
+                /// Passing `None` disables the input. The timer is disabled
+                /// shortly while the input is reconfigured.
+                pub fn configure_input_a(&mut self, config: Option<InputConfig>) {
+                    with_disabled(&self.tim, |tim| match config {
+                        // NOTE(unsafe): The fields come from valid enums
+                        Some(config) => {
+                            tim.evctrla().write(|w| unsafe {
+                                w.trigei()
+                                    .set_bit()
+                                    .edge()
+                                    .bit(config.edge == InputEdge::RisingHigh)
+                                    .cfg()
+                                    .bits(config.filter as u8)
+                            });
+                            tim.inputctrla()
+                                .write(|w| unsafe { w.inputmode().bits(config.mode as u8) });
+                        }
+                        None => {
+                            tim.evctrla().reset();
+                            tim.inputctrla().reset();
+                        }
+                    });
+                }
+
+                /// Configure the action of the event input B
+                ///
+                /// Input B affects WOB in the modes that stop a single output.
+                /// Passing `None` disables the input. The timer is disabled
+                /// shortly while the input is reconfigured.
+                pub fn configure_input_b(&mut self, config: Option<InputConfig>) {
+                    with_disabled(&self.tim, |tim| match config {
+                        // NOTE(unsafe): The fields come from valid enums
+                        Some(config) => {
+                            tim.evctrlb().write(|w| unsafe {
+                                w.trigei()
+                                    .set_bit()
+                                    .edge()
+                                    .bit(config.edge == InputEdge::RisingHigh)
+                                    .cfg()
+                                    .bits(config.filter as u8)
+                            });
+                            tim.inputctrlb()
+                                .write(|w| unsafe { w.inputmode().bits(config.mode as u8) });
+                        }
+                        None => {
+                            tim.evctrlb().reset();
+                            tim.inputctrlb().reset();
+                        }
+                    });
+                }
+
+                /// Capture the counter value into `CAPTUREA` on every event on input A
+                ///
+                /// The event input A has to be connected to an event channel
+                /// using [`event_input_a`](Self::event_input_a). The captured
+                /// value is read using [`capture_a`](Self::capture_a). Capturing
+                /// replaces any [`InputConfig`] of the input. The timer is disabled
+                /// shortly while the input is reconfigured.
+                pub fn configure_capture_a(&mut self, edge: InputEdge, filter: InputFilter) {
+                    with_disabled(&self.tim, |tim| {
+                        // NOTE(unsafe): The filter comes from a valid enum
+                        tim.evctrla().write(|w| unsafe {
+                            w.trigei()
+                                .set_bit()
+                                .action()
+                                .set_bit()
+                                .edge()
+                                .bit(edge == InputEdge::RisingHigh)
+                                .cfg()
+                                .bits(filter as u8)
+                        });
+                        tim.inputctrla().reset();
+                    });
+                }
+
+                /// Capture the counter value into `CAPTUREB` on every event on input B
+                ///
+                /// See [`configure_capture_a`](Self::configure_capture_a).
+                pub fn configure_capture_b(&mut self, edge: InputEdge, filter: InputFilter) {
+                    with_disabled(&self.tim, |tim| {
+                        // NOTE(unsafe): The filter comes from a valid enum
+                        tim.evctrlb().write(|w| unsafe {
+                            w.trigei()
+                                .set_bit()
+                                .action()
+                                .set_bit()
+                                .edge()
+                                .bit(edge == InputEdge::RisingHigh)
+                                .cfg()
+                                .bits(filter as u8)
+                        });
+                        tim.inputctrlb().reset();
+                    });
+                }
+
+                /// Returns the counter value captured by the last event on input A
+                ///
+                /// Returns [`nb::Error::WouldBlock`] until a new value was captured.
+                /// The value is synchronized from the TCD clock domain once the
+                /// trigger A flag is set. Reading it clears the flag.
+                pub fn capture_a(&mut self) -> nb::Result<u16, Infallible> {
+                    if self.tim.intflags().read().triga().bit_is_clear() {
+                        return Err(nb::Error::WouldBlock);
+                    }
+
+                    // The low byte has to be read first to latch the high byte
+                    let value = self.tim.capturea().read().bits();
+                    self.tim.intflags().write(|w| w.triga().set_bit());
+                    Ok(value)
+                }
+
+                /// Returns the counter value captured by the last event on input B
+                ///
+                /// See [`capture_a`](Self::capture_a).
+                pub fn capture_b(&mut self) -> nb::Result<u16, Infallible> {
+                    if self.tim.intflags().read().trigb().bit_is_clear() {
+                        return Err(nb::Error::WouldBlock);
+                    }
+
+                    // The low byte has to be read first to latch the high byte
+                    let value = self.tim.captureb().read().bits();
+                    self.tim.intflags().write(|w| w.trigb().set_bit());
+                    Ok(value)
+                }
+
+                /// Set the levels of WOA and WOB while a fault is active
+                ///
+                /// The outputs are forced to these levels by the input modes
+                /// that stop the outputs, e.g. to switch off a power stage when
+                /// an overcurrent comparator triggers. The timer is disabled
+                /// shortly while the levels are changed.
+                pub fn set_fault_levels(&mut self, woa: bool, wob: bool) {
+                    with_disabled(&self.tim, |tim| {
+                        let faultctrl = tim.faultctrl().read();
+                        let (cmpaen, cmpben) = (faultctrl.cmpaen().bit(), faultctrl.cmpben().bit());
+
+                        // FAULTCTRL is protected by the CCP
+                        tim.faultctrl().write_protected(|w| {
+                            w.cmpaen()
+                                .bit(cmpaen)
+                                .cmpben()
+                                .bit(cmpben)
+                                .cmpa()
+                                .bit(woa)
+                                .cmpb()
+                                .bit(wob)
+                        });
+                    });
+                }
+
+                /// Convert a duration into ticks of the timer clock, rounded up
+                pub fn duration_to_ticks(&self, duration: NanosDuration) -> u32 {
+                    let rate = self.tick_rate().raw() as u64;
+                    ((duration.ticks() as u64 * rate + 999_999_999) / 1_000_000_000) as u32
+                }
+
+                /// Drive WOA and WOB as complementary outputs with dead time
+                ///
+                /// WOA is active for `duty` ticks of the period and WOB for the
+                /// rest of it. Both outputs are inactive for `dead_time` at each
+                /// transition, so the two switches of a half-bridge are never on
+                /// at the same time. The timer has to be in
+                /// [`OneRamp`](WaveformGenerationMode::OneRamp) mode. The dead
+                /// time is inserted by delaying the set compare points of both
+                /// outputs, so only the on-time of WOB is reduced by the two
+                /// dead times.
+                ///
+                /// DLYCTRL/DLYVAL isn't used for the dead time, as the delay
+                /// unit of the TCD only delays the input blanking or the trigger
+                /// output event, but not the waveform outputs.
+                ///
+                /// Returns [`Error::ImpossiblePeriod`] if the duty cycle and the
+                /// dead time don't fit into the period.
+                pub fn set_complementary_duty(
+                    &mut self,
+                    duty: u16,
+                    dead_time: NanosDuration,
+                ) -> Result<(), Error> {
+                    let top = self.tim.cmpbclr().read().bits() as u32;
+                    let dead_time = self.duration_to_ticks(dead_time);
+                    let duty = duty as u32;
+
+                    if duty + 2 * dead_time > top {
+                        return Err(Error::ImpossiblePeriod);
+                    }
+
+                    self.set_compare_values(CompareValues {
+                        a_set: dead_time as u16,
+                        a_clear: (duty + dead_time) as u16,
+                        b_set: (duty + 2 * dead_time) as u16,
+                        b_clear: top as u16,
+                    });
+
+                    Ok(())
+                }
+
+                /// Enable dithering of the selected time
+                ///
+                /// Every cycle `value` is added to a 4 bit accumulator. Whenever
+                /// it overflows, the selected time is extended by one clock
+                /// cycle. On average this adds `value / 16` cycles to the selected
+                /// time, which increases the resolution of the duty cycle or the
+                /// frequency by 4 bits, e.g. for dimming LEDs or fine-tuning
+                /// power supplies. Values above 15 are truncated.
+                pub fn configure_dithering(&mut self, selection: DitherSelection, value: u8) {
+                    // NOTE(unsafe): The selection comes from a valid enum
+                    with_disabled(&self.tim, |tim| {
+                        tim.ditctrl()
+                            .write(|w| unsafe { w.dithersel().bits(selection as u8) });
+                    });
+
+                    self.set_dither_value(value);
+                }
+
+                /// Set the dither value without changing the [`DitherSelection`]
+                ///
+                /// The new value is taken over at the end of the current cycle.
+                #[inline]
+                pub fn set_dither_value(&mut self, value: u8) {
+                    // NOTE(unsafe): The value is masked to the width of the field
+                    self.tim
+                        .ditval()
+                        .write(|w| unsafe { w.dither().bits(value & 0x0F) });
+                    synchronize(&self.tim);
+                }
+
+                /// Disable dithering
+                #[inline]
+                pub fn disable_dithering(&mut self) {
+                    self.set_dither_value(0);
+                }
+
+                /// Restart the timer after it stopped waiting for a software restart
+                ///
+                /// See [`InputMode::WaitSoftware`].
+                #[inline]
+                pub fn restart(&mut self) {
+                    wait_cmdrdy(&self.tim);
+                    self.tim.ctrle().write(|w| w.restart().set_bit());
+                }
+            }
+        )+
+    };
+}
+
+impl Timer<TCD0> {
+    fn tick_rate(&self) -> Hertz {
+        use super::TimerClock;
+        TCD0::get_input_clock_rate(self.clk) / self.tim.read_prescaler() as u32
+    }
+}
+
+impl<const FREQ: u32> FTimer<TCD0, FREQ> {
+    fn tick_rate(&self) -> Hertz {
+        Hertz::from_raw(FREQ)
+    }
+}
+
+tcd_timer!(
+    Timer<TCD0>;
+    FTimer<TCD0, FREQ>, FREQ;
+);
+
+/// Split a prescaler into the synchronization and counter prescaler bits
+fn into_prescalers(prescaler: u16) -> (u8, u8) {
+    match prescaler {
+        1 => (0x0, 0x0),
+        2 => (0x1, 0x0),
+        4 => (0x2, 0x0),
+        8 => (0x3, 0x0),
+        16 => (0x2, 0x1),
+        32 => (0x0, 0x2),
+        64 => (0x1, 0x2),
+        128 => (0x2, 0x2),
+        256 => (0x3, 0x2),
+        _ => panic!("Invalid prescaler"),
+    }
+}
+
+fn from_prescalers(syncpres: u8, cntpres: u8) -> u16 {
+    let cntpres = match cntpres {
+        0x0 => 1,
+        0x1 => 4,
+        _ => 32,
+    };
+
+    (1 << syncpres) * cntpres
+}
+
+impl crate::private::Sealed for crate::pac::TCD0 {}
+
+use super::pwm::{WaveformOutputPinset, C1, C2};
+use crate::gpio::{Output, Stateless};
+use core::marker::PhantomData;
+
+/// A pin can be marked with this when it can be used as a waveform output pin
+pub trait WaveformOutputPin<TCD, const CHAN: u8> {}
+
+/// Pin set for the port multiplexer
+pub struct TcdPinset<TIM, WaveformOutput: WaveformOutputPin<TIM, CHAN>, const CHAN: u8> {
+    _tim: PhantomData<TIM>,
+    output: WaveformOutput,
+}
+
+impl<TIM, WaveformOutput, const CHAN: u8> TcdPinset<TIM, WaveformOutput, CHAN>
+where
+    WaveformOutput: WaveformOutputPin<TIM, CHAN>,
+{
+    pub(crate) fn new(output: WaveformOutput) -> Self {
+        TcdPinset {
+            _tim: PhantomData,
+            output,
+        }
+    }
+
+    /// Returns the pin in the mode used by the peripheral
+    pub fn free(self) -> WaveformOutput {
+        self.output
+    }
+
+    /// Releases the pin back to GPIO
+    ///
+    /// The pin is converted back into a floating input, so it can be reused
+    /// after the peripheral has been shut down.
+    pub fn release(self) -> WaveformOutput::Released
+    where
+        WaveformOutput: Release,
+    {
+        self.output.release()
+    }
+}
+
+impl<WaveformOutput: WaveformOutputPin<TCD0, CHAN>, const CHAN: u8> WaveformOutputPinset<TCD0, CHAN>
+    for TcdPinset<TCD0, WaveformOutput, CHAN>
+{
+}
+
+impl WaveformOutputPin<TCD0, C1> for crate::gpio::porta::PA4<Output<Stateless>> {}
+impl WaveformOutputPin<TCD0, C2> for crate::gpio::porta::PA5<Output<Stateless>> {}
@@ -1,4 +1,6 @@
-use super::{AsClockSource, Counter, CounterHz, Delay, Error, General, Instance, PeriodicMode};
+use super::{
+    AsClockSource, Counter, CounterHz, Delay, Error, General, Instance, PeriodicMode, RunModes,
+};
 use crate::time::*;
 use crate::Toggle;
 
@@ -105,6 +107,26 @@ impl<TIM: Instance + General + PeriodicMode> Timer<TIM> {
     }
 }
 
+impl<TIM: Instance + RunModes> Timer<TIM> {
+    /// Enable or disable counting while the device is in standby sleep mode
+    ///
+    /// By default the timer stops counting in standby.
+    #[doc(alias = "RUNSTDBY")]
+    #[inline]
+    pub fn set_run_in_standby(&mut self, enable: impl Into<Toggle>) {
+        self.tim.configure_run_in_standby(enable);
+    }
+
+    /// Enable or disable counting while the CPU is halted by a debugger
+    ///
+    /// By default the timer stops counting when the CPU is halted.
+    #[doc(alias = "DBGRUN")]
+    #[inline]
+    pub fn set_debug_run(&mut self, enable: impl Into<Toggle>) {
+        self.tim.configure_debug_run(enable);
+    }
+}
+
 // // FIXME: add this for tcb sync feature?
 // impl<TIM: Instance + MasterTimer> Timer<TIM> {
 //     pub fn set_master_mode(&mut self, mode: TIM::Mms) {
@@ -244,6 +266,26 @@ impl<TIM: Instance + General + PeriodicMode, const FREQ: u32> FTimer<TIM, FREQ>
     }
 }
 
+impl<TIM: Instance + RunModes, const FREQ: u32> FTimer<TIM, FREQ> {
+    /// Enable or disable counting while the device is in standby sleep mode
+    ///
+    /// By default the timer stops counting in standby.
+    #[doc(alias = "RUNSTDBY")]
+    #[inline]
+    pub fn set_run_in_standby(&mut self, enable: impl Into<Toggle>) {
+        self.tim.configure_run_in_standby(enable);
+    }
+
+    /// Enable or disable counting while the CPU is halted by a debugger
+    ///
+    /// By default the timer stops counting when the CPU is halted.
+    #[doc(alias = "DBGRUN")]
+    #[inline]
+    pub fn set_debug_run(&mut self, enable: impl Into<Toggle>) {
+        self.tim.configure_debug_run(enable);
+    }
+}
+
 impl<TIM: AsClockSource, const FREQ: u32> FTimer<TIM, FREQ> {
     pub fn use_as_clock_source(&self) -> TIM::OutputClock {
         self.tim.use_as_clock_source(Hertz::from_raw(FREQ))
@@ -1,23 +1,64 @@
 //! # Two-wire interface (TWI) bus
 
-// TODO: client mode
-
 use core::{marker::PhantomData, ops::Deref};
 
-use embedded_hal::i2c::{ErrorType, I2c, Operation};
+use embedded_hal::{
+    delay::DelayNs,
+    i2c::{ErrorType, I2c, Operation},
+};
 
-use crate::{clkctrl::Clocks, pac::twi0::RegisterBlock, time::*, Toggle};
+use crate::{
+    clkctrl::Clocks,
+    gpio::{marker, Pin, Release},
+    pac::twi0::RegisterBlock,
+    time::*,
+    Toggle,
+};
 
 #[cfg(feature = "enumset")]
 use enumset::{EnumSet, EnumSetType};
 
+#[cfg(feature = "async")]
+pub mod asynch;
+pub mod client;
 pub mod config;
+pub mod smbus;
+pub mod transfer;
 
 /// SCL pin
-pub trait SclPin<TWI>: crate::private::Sealed {}
+pub trait SclPin<TWI>: BusLine + crate::private::Sealed {}
 
 /// SDA pin
-pub trait SdaPin<TWI>: crate::private::Sealed {}
+pub trait SdaPin<TWI>: BusLine + crate::private::Sealed {}
+
+/// Bus line which can be driven by software for the bus recovery
+#[doc(hidden)]
+pub trait BusLine {
+    fn set_line(&mut self, high: bool);
+    fn is_line_low(&self) -> bool;
+    fn restore_peripheral(&mut self);
+}
+
+impl<Gpio, Index, PER> BusLine for Pin<Gpio, Index, Peripheral<PER>>
+where
+    Gpio: marker::Gpio,
+    Index: marker::Index,
+{
+    #[inline]
+    fn set_line(&mut self, high: bool) {
+        Pin::set_open_drain(self, high)
+    }
+
+    #[inline]
+    fn is_line_low(&self) -> bool {
+        Pin::is_line_low(self)
+    }
+
+    #[inline]
+    fn restore_peripheral(&mut self) {
+        Pin::restore_peripheral(self)
+    }
+}
 
 /// Pin set for the port multiplexer
 pub struct TwiPinset<TWI, Scl: SclPin<TWI>, Sda: SdaPin<TWI>> {
@@ -39,26 +80,90 @@ where
         }
     }
 
+    /// Returns the pins in the modes used by the peripheral
     pub fn free(self) -> (Scl, Sda) {
         (self.scl, self.sda)
     }
+
+    /// Releases the pins back to GPIO
+    ///
+    /// The pins are converted back into floating inputs, so they can be
+    /// reused after the peripheral has been shut down.
+    pub fn release(self) -> (Scl::Released, Sda::Released)
+    where
+        Scl: Release,
+        Sda: Release,
+    {
+        (self.scl.release(), self.sda.release())
+    }
 }
 
 /// TWI error
-#[derive(ufmt::derive::uDebug, Debug, PartialEq, Eq, PartialOrd, Ord)]
+///
+/// The host is always brought back into a usable state before an error is
+/// returned, so a new transaction can be started right away.
+#[derive(ufmt::derive::uDebug, Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Error {
     /// Arbitration loss
+    ///
+    /// Another host took over the bus, which becomes idle again once the other
+    /// host issues a STOP condition.
     Arbitration,
     /// Bus error
+    ///
+    /// An illegal START or STOP condition was detected. The bus state is
+    /// forced to idle afterwards.
     Bus,
     /// Bus busy
+    ///
+    /// Another host owns the bus, nothing was sent.
     Busy,
     /// Not Acknowledge received
+    ///
+    /// The transaction was ended with a STOP condition.
     Nack(NackSource),
+    /// The bus was inactive for longer than the configured
+    /// [`BusTimeout`](config::BusTimeout)
+    ///
+    /// The bus state has been forced to idle by the hardware.
+    Timeout,
+}
+
+/// Error returned when the requested bus clock frequency can't be generated
+#[derive(ufmt::derive::uDebug, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BaudRateError {
+    /// The frequency is too high for the peripheral clock and rise time
+    TooHigh,
+    /// The frequency is too low for the peripheral clock
+    TooLow,
+    /// Frequencies above 400kHz need the fast mode plus
+    FastModePlusRequired,
+}
+
+/// Addresses of the clients found by [`Twi::scan`]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct ScanResult {
+    found: [u8; 16],
+}
+
+impl ScanResult {
+    /// The probed addresses, excluding the reserved ones
+    pub const ADDRESSES: core::ops::RangeInclusive<u8> = 0x08..=0x77;
+
+    /// Check if a client acknowledged the address
+    #[inline]
+    pub fn is_present(&self, address: u8) -> bool {
+        address < 0x80 && self.found[address as usize / 8] & (1 << (address % 8)) != 0
+    }
+
+    /// Iterate over the addresses of all found clients
+    pub fn iter(&self) -> impl Iterator<Item = u8> + '_ {
+        Self::ADDRESSES.filter(|address| self.is_present(*address))
+    }
 }
 
 /// TWI NACK error source
-#[derive(ufmt::derive::uDebug, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(ufmt::derive::uDebug, Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum NackSource {
     /// NACK received during Address phase
     Address,
@@ -201,6 +306,10 @@ where
     SDA: SdaPin<TWI>,
 {
     /// Configures the TWI peripheral to work in master mode
+    ///
+    /// # Panics
+    ///
+    /// Panics if the bus clock frequency can't be generated, see [`Twi::try_new`].
     pub fn new<Config>(
         twi: TWI,
         pinset: TwiPinset<TWI, SCL, SDA>,
@@ -210,24 +319,48 @@ where
     where
         Config: Into<config::Config>,
     {
-        let config = config.into();
-
-        // FIXME: What about CTRLA.FMPEN? What exactly does this do? Do we need
-        //        to set this bit for 1MHz? What about 500KHz?
-        let frequency = config.frequency.raw();
-        let rise_time = config.rise_time.ticks();
-        let f_per = TWI::clock(&clocks).raw();
+        match Self::try_new(twi, pinset, config, clocks) {
+            Ok(twi) => twi,
+            Err(_) => panic!("impossible TWI frequency"),
+        }
+    }
 
-        let baudrate: u8 =
-            (((f_per / frequency) - ((f_per * rise_time) / 1000000000) - 10) / 2) as u8;
+    /// Configures the TWI peripheral to work in master mode
+    ///
+    /// The baud rate divider is calculated from the peripheral clock, taking
+    /// the rise time of the bus lines into account. Frequencies above 400kHz
+    /// need the fast mode plus to be enabled in the [`Config`](config::Config).
+    pub fn try_new<Config>(
+        twi: TWI,
+        pinset: TwiPinset<TWI, SCL, SDA>,
+        config: Config,
+        clocks: Clocks,
+    ) -> Result<Self, BaudRateError>
+    where
+        Config: Into<config::Config>,
+    {
+        let config = config.into();
+        let baudrate = calculate_baud(TWI::clock(&clocks).raw(), &config)?;
 
+        // Fast mode plus increases the drive strength of the pins and changes
+        // the timing of the bus to reach 1MHz
         twi.ctrla()
             .modify(|_, w| w.fmpen().variant(config.fast_mode_plus));
 
         // Set the baud rate divider and enable the peripheral
         twi.mctrla().modify(|_, w| w.enable().clear_bit());
         twi.mbaud().write(|w| w.bits(baudrate));
-        twi.mctrla().modify(|_, w| w.enable().set_bit());
+        twi.dbgctrl().write(|w| w.dbgrun().bit(config.debug_run));
+        twi.mctrla().modify(|_, w| {
+            w.timeout()
+                .variant(config.timeout.into())
+                .smen()
+                .bit(config.smart_mode)
+                .qcen()
+                .bit(config.quick_command)
+                .enable()
+                .set_bit()
+        });
 
         // Force the state-machine into IDLE state
         twi.mstatus().modify(|_, w| w.busstate().idle());
@@ -236,7 +369,7 @@ where
         twi.mstatus()
             .modify(|_, w| w.rif().set_bit().wif().set_bit().buserr().set_bit());
 
-        Self { twi, pinset }
+        Ok(Self { twi, pinset })
     }
 
     /// Get access to the underlying register block.
@@ -358,6 +491,91 @@ where
         events
     }
 
+    /// Recover a bus which is stuck with SDA held low by a client
+    ///
+    /// A client keeps SDA low if a transfer was interrupted in the middle of a
+    /// byte it sends, e.g. by a reset of the host. The peripheral is disabled
+    /// and the pins are driven as GPIO to clock out up to 9 SCL pulses until
+    /// the client releases SDA, followed by a STOP condition. The peripheral is
+    /// enabled again afterwards.
+    ///
+    /// The pulses are generated with 100kHz using the passed delay.
+    ///
+    /// Returns [`Error::Bus`] if SDA is still held low after the recovery.
+    pub fn recover_bus(&mut self, delay: &mut impl DelayNs) -> Result<(), Error> {
+        const HALF_PERIOD_NS: u32 = 5_000;
+
+        let scl = &mut self.pinset.scl;
+        let sda = &mut self.pinset.sda;
+
+        // Hand the pins over from the peripheral to the port
+        self.twi.mctrla().modify(|_, w| w.enable().clear_bit());
+        scl.set_line(true);
+        sda.set_line(true);
+        delay.delay_ns(HALF_PERIOD_NS);
+
+        // Clock out the remaining bits of the byte the client is sending
+        for _ in 0..9 {
+            if !sda.is_line_low() {
+                break;
+            }
+
+            scl.set_line(false);
+            delay.delay_ns(HALF_PERIOD_NS);
+            scl.set_line(true);
+            delay.delay_ns(HALF_PERIOD_NS);
+        }
+
+        let released = !sda.is_line_low();
+
+        // Generate a STOP condition: SDA rises while SCL is high
+        scl.set_line(false);
+        delay.delay_ns(HALF_PERIOD_NS);
+        sda.set_line(false);
+        delay.delay_ns(HALF_PERIOD_NS);
+        scl.set_line(true);
+        delay.delay_ns(HALF_PERIOD_NS);
+        sda.set_line(true);
+        delay.delay_ns(HALF_PERIOD_NS);
+
+        scl.restore_peripheral();
+        sda.restore_peripheral();
+
+        // Enable the peripheral again and force the state-machine into IDLE state
+        self.twi.mctrla().modify(|_, w| w.enable().set_bit());
+        self.twi.mstatus().modify(|_, w| w.busstate().idle());
+        self.clear_events();
+
+        if released {
+            Ok(())
+        } else {
+            Err(Error::Bus)
+        }
+    }
+
+    /// Bring the host back into a usable state after an error
+    fn recover_from_error(&mut self, error: Error) {
+        match error {
+            // Nothing happened on the bus
+            Error::Busy => return,
+            // The bus state is unknown after an illegal bus condition
+            Error::Bus => self.twi.mstatus().modify(|_, w| w.busstate().idle()),
+            // The STOP condition has already been issued on a NACK, another
+            // host owns the bus after an arbitration loss and the hardware
+            // already forced the IDLE state on a timeout
+            Error::Nack(_) | Error::Arbitration | Error::Timeout => {}
+        }
+
+        // Don't leave any flags behind for the next transaction
+        self.clear_events();
+    }
+
+    /// Disables the host and releases the TWI peripheral and associated pins
+    pub fn release(self) -> (TWI, TwiPinset<TWI, SCL, SDA>) {
+        self.twi.mctrla().modify(|_, w| w.enable().clear_bit());
+        self.free()
+    }
+
     /// Releases the TWI peripheral and associated pins
     pub fn free(self) -> (TWI, TwiPinset<TWI, SCL, SDA>) {
         (self.twi, self.pinset)
@@ -384,44 +602,162 @@ where
     }
 }
 
-macro_rules! busy_wait {
-    ($i2c:expr, $nacksource:expr) => {
-        loop {
-            let mstatus = $i2c.mstatus().read();
-
-            if mstatus.arblost().bit_is_set() {
-                // ARBLOST gets cleared on the next MADDR write
-                return Err(Error::Arbitration);
-            } else if mstatus.buserr().bit_is_set() {
-                // BUSERR gets cleared on the next MADDR write
-                return Err(Error::Bus);
-            } else if (mstatus.wif().bit_is_set() || mstatus.rif().bit_is_set()) {
-                // Received NACK
-                if mstatus.rxack().bit_is_set() {
-                    $i2c.mctrlb().modify(|_, w| w.mcmd().stop());
-                    return Err(Error::Nack($nacksource));
-                } else {
-                    break;
+/// Progress of the operations of a host transaction
+///
+/// The blocking, async and interrupt driven transfers share this state
+/// machine. [`step`](Self::step) issues everything up to the next byte on the
+/// bus and the caller then waits until [`poll`](Self::poll) reports the byte
+/// as transferred.
+pub(crate) struct HostTransaction {
+    address: u8,
+    /// Index of the current operation and of the next byte within it
+    operation: usize,
+    byte: usize,
+    /// Adjacent operations of the same kind are merged into a single phase
+    /// on the bus, a repeated START is only generated when the direction
+    /// changes
+    last_was_read: Option<bool>,
+    /// A received byte is waiting in MDATA, the host holds SCL low until
+    /// the ACK action for it is executed
+    pending: bool,
+    /// In smart mode, reading MDATA sends the ACK action right away and
+    /// receives the next byte after an ACK
+    smart_mode: bool,
+    /// The bus was owned by the host, so an IDLE bus is a timeout from now on
+    owned: bool,
+    /// The byte the transaction is waiting for
+    waiting: NackSource,
+}
+
+impl HostTransaction {
+    pub(crate) fn new(twi: &RegisterBlock, address: u8) -> Self {
+        Self {
+            address,
+            operation: 0,
+            byte: 0,
+            last_was_read: None,
+            pending: false,
+            smart_mode: twi.mctrla().read().smen().bit_is_set(),
+            owned: false,
+            waiting: NackSource::Address,
+        }
+    }
+
+    /// Execute the operations up to the next byte transferred on the bus
+    ///
+    /// Returns `true` once the final STOP has been issued.
+    pub(crate) fn step(&mut self, twi: &RegisterBlock, operations: &mut [Operation<'_>]) -> bool {
+        while self.operation < operations.len() {
+            let read_continues = read_continues(operations, self.operation);
+
+            match &mut operations[self.operation] {
+                Operation::Read(buffer) => {
+                    if self.last_was_read != Some(true) {
+                        self.last_was_read = Some(true);
+
+                        // Write the address and read-bit
+                        // This kicks off a START or repeated START condition on the bus
+                        // After an ACK, the first byte is received automatically
+                        twi.maddr().write(|w| w.bits(self.address << 1 | 1));
+                        self.pending = true;
+                        self.waiting = NackSource::Address;
+                        return false;
+                    }
+
+                    let len = buffer.len();
+                    if let Some(b) = buffer.get_mut(self.byte) {
+                        if !self.pending {
+                            // ACK the previous byte and receive the next one
+                            if !self.smart_mode {
+                                twi.mctrlb()
+                                    .modify(|_, w| w.ackact().clear_bit().mcmd().recvtrans());
+                            }
+                            self.pending = true;
+                            self.waiting = NackSource::Data;
+                            return false;
+                        }
+
+                        if self.smart_mode {
+                            let last = self.byte + 1 == len && !read_continues;
+                            twi.mctrlb().modify(|_, w| w.ackact().bit(last));
+                        }
+
+                        *b = twi.mdata().read().bits();
+                        self.pending = false;
+                        self.byte += 1;
+                        continue;
+                    }
+                }
+
+                Operation::Write(buffer) => {
+                    if self.last_was_read != Some(false) {
+                        self.last_was_read = Some(false);
+
+                        // NACK the last byte of a preceding read. The NACK is
+                        // sent by the repeated START triggered by the MADDR write.
+                        twi.mctrlb().modify(|_, w| w.ackact().set_bit());
+
+                        // Write the address and ~read-bit
+                        // This kicks off a START or repeated START condition on the bus
+                        twi.maddr().write(|w| w.bits(self.address << 1));
+                        self.waiting = NackSource::Address;
+                        return false;
+                    }
+
+                    if let Some(&b) = buffer.get(self.byte) {
+                        twi.mdata().write(|w| w.bits(b));
+                        self.byte += 1;
+                        self.waiting = NackSource::Data;
+                        return false;
+                    }
                 }
             }
+
+            self.operation += 1;
+            self.byte = 0;
         }
-    };
-}
 
-macro_rules! wait_ownership {
-    ($i2c:expr) => {
-        loop {
-            let mstatus = $i2c.mstatus().read();
+        // Send the final STOP, NACKing the last byte of a final read
+        twi.mctrlb()
+            .modify(|_, w| w.ackact().set_bit().mcmd().stop());
 
-            if mstatus.arblost().bit_is_set() {
-                return Err(Error::Arbitration);
-            }
+        true
+    }
 
-            if mstatus.busstate().is_owner() {
-                break;
+    /// Check the host status after an address or data byte
+    ///
+    /// Returns [`nb::Error::WouldBlock`] until the byte has been transferred and
+    /// issues a STOP condition if it was NACKed.
+    pub(crate) fn poll(&mut self, twi: &RegisterBlock) -> nb::Result<(), Error> {
+        let mstatus = twi.mstatus().read();
+        self.owned |= mstatus.busstate().is_owner();
+
+        if mstatus.arblost().bit_is_set() {
+            // ARBLOST gets cleared on the next MADDR write
+            Err(nb::Error::Other(Error::Arbitration))
+        } else if mstatus.buserr().bit_is_set() {
+            // BUSERR gets cleared on the next MADDR write
+            Err(nb::Error::Other(Error::Bus))
+        } else if mstatus.busstate().is_idle() {
+            if self.owned {
+                // The inactive bus timeout forced the bus into the IDLE state
+                Err(nb::Error::Other(Error::Timeout))
+            } else {
+                // The START condition hasn't been sent yet
+                Err(nb::Error::WouldBlock)
+            }
+        } else if mstatus.wif().bit_is_set() || mstatus.rif().bit_is_set() {
+            // Received NACK, a NACKed address of a read also sets WIF
+            if mstatus.rxack().bit_is_set() {
+                twi.mctrlb().modify(|_, w| w.mcmd().stop());
+                Err(nb::Error::Other(Error::Nack(self.waiting)))
+            } else {
+                Ok(())
             }
+        } else {
+            Err(nb::Error::WouldBlock)
         }
-    };
+    }
 }
 
 impl<TWI, SCL, SDA> ErrorType for Twi<TWI, TwiPinset<TWI, SCL, SDA>>
@@ -440,6 +776,43 @@ where
     SDA: SdaPin<TWI>,
 {
     fn transaction(&mut self, address: u8, operations: &mut [Operation<'_>]) -> Result<(), Error> {
+        let result = self.execute(address, operations);
+        if let Err(e) = result {
+            self.recover_from_error(e);
+        }
+        result
+    }
+}
+
+impl<TWI, SCL, SDA> Twi<TWI, TwiPinset<TWI, SCL, SDA>>
+where
+    TWI: Instance,
+    SCL: SclPin<TWI>,
+    SDA: SdaPin<TWI>,
+{
+    /// Probe all non-reserved addresses from 0x08 to 0x77 for clients
+    ///
+    /// Every address is probed with a zero-length write and the clients that
+    /// acknowledge their address are reported in the [`ScanResult`]. This is
+    /// useful for the bring-up of a board.
+    ///
+    /// Errors other than a NACK abort the scan.
+    pub fn scan(&mut self) -> Result<ScanResult, Error> {
+        let mut result = ScanResult::default();
+
+        for address in ScanResult::ADDRESSES {
+            match I2c::transaction(self, address, &mut [Operation::Write(&[])]) {
+                Ok(()) => result.found[address as usize / 8] |= 1 << (address % 8),
+                Err(Error::Nack(_)) => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Execute the operations of a blocking transaction
+    fn execute(&mut self, address: u8, operations: &mut [Operation<'_>]) -> Result<(), Error> {
         // Detect Bus busy
         if self.twi.mstatus().read().busstate().is_busy() {
             return Err(Error::Busy);
@@ -449,77 +822,61 @@ where
             return Ok(());
         }
 
-        for operation in operations {
-            match operation {
-                Operation::Read(buffer) => {
-                    // Write the address and read-bit
-                    // This kicks off a START or repeated START condition on the bus
-                    self.twi.maddr().write(|w| w.bits(address << 1 | 1));
-
-                    // Wait for the bus state to transition into OWNED
-                    wait_ownership!(self.twi);
-
-                    // Wait for the address to be ACKed or NACKed
-                    busy_wait!(self.twi, NackSource::Address);
-
-                    // Special case for zero-length receive buffers
-                    // Just set the ACK action to NACK. The next write to MADDR or
-                    // the STOP action that is executed at the end of this function
-                    // then performs the NACK and the appropriate action like a STOP or
-                    // repeated START
-                    self.twi.mctrlb().modify(|_, w| w.ackact().set_bit());
-
-                    let mut it = buffer.iter_mut().peekable();
-                    while let Some(b) = it.next() {
-                        // Wait for data
-                        busy_wait!(self.twi, NackSource::Data);
-
-                        // Not the last byte we expect? ACK it, otherwise NACK it
-                        // The following read from MDATA triggers the RECVTRANS action automatically
-                        if it.peek().is_some() {
-                            self.twi.mctrlb().modify(|_, w| w.ackact().clear_bit());
-                        } else {
-                            self.twi.mctrlb().modify(|_, w| w.ackact().set_bit());
-                        }
+        let mut host = HostTransaction::new(&self.twi, address);
+        while !host.step(&self.twi, operations) {
+            nb::block!(host.poll(&self.twi))?;
+        }
 
-                        // Read data and trigger ACK/NACK
-                        *b = self.twi.mdata().read().bits();
-                    }
-                }
+        Ok(())
+    }
+}
 
-                Operation::Write(buffer) => {
-                    // Write the address and ~read-bit
-                    // This kicks off a START or repeated START condition on the bus
-                    self.twi.maddr().write(|w| w.bits(address << 1 | 0));
-
-                    // Wait for the bus state to transition into OWNED
-                    wait_ownership!(self.twi);
-
-                    // Wait for the address to be ACKed or NACKed
-                    busy_wait!(self.twi, NackSource::Address);
-
-                    // Send bytes in the buffer
-                    // Should the sent byte be NACKed, the busy_wait! macro will
-                    // return and issue a STOP condition on the bus
-                    for b in buffer.iter() {
-                        self.twi.mdata().write(|w| w.bits(*b));
-                        busy_wait!(self.twi, NackSource::Data);
-                    }
-                }
-            }
-        }
+/// Check if the read phase of the operation at `index` continues with more
+/// bytes to read in the following operations
+fn read_continues(operations: &[Operation<'_>], index: usize) -> bool {
+    operations[index + 1..]
+        .iter()
+        .take_while(|op| matches!(op, Operation::Read(_)))
+        .any(|op| matches!(op, Operation::Read(buffer) if !buffer.is_empty()))
+}
 
-        // Send the final STOP
-        self.twi.mctrlb().modify(|_, w| w.mcmd().stop());
+/// Calculate the MBAUD value for the bus clock frequency
+///
+/// The bus clock frequency is f_per / (10 + 2 * MBAUD + f_per * t_rise).
+fn calculate_baud(f_per: u32, config: &config::Config) -> Result<u8, BaudRateError> {
+    let frequency = config.frequency.raw();
 
-        Ok(())
+    if frequency > 1_000_000 {
+        return Err(BaudRateError::TooHigh);
     }
+
+    if frequency > 400_000 && !config.fast_mode_plus {
+        return Err(BaudRateError::FastModePlusRequired);
+    }
+
+    // Calculate the rise time in peripheral clock cycles. Scale the clock down
+    // to kHz first, so that this doesn't overflow.
+    let rise_cycles = (f_per / 1000) * config.rise_time.ticks() / 1_000_000;
+
+    let baud = (f_per / frequency)
+        .checked_sub(10 + rise_cycles)
+        .ok_or(BaudRateError::TooHigh)?
+        / 2;
+
+    u8::try_from(baud).map_err(|_| BaudRateError::TooLow)
 }
 
 /// TWI instance
 pub trait Instance: Deref<Target = RegisterBlock> + crate::private::Sealed {
     #[doc(hidden)]
     fn clock(clocks: &Clocks) -> Hertz;
+
+    #[doc(hidden)]
+    fn ptr() -> *const RegisterBlock;
+
+    #[doc(hidden)]
+    #[cfg(feature = "async")]
+    fn waker() -> &'static crate::asynch::WakerSlot;
 }
 
 macro_rules! twi {
@@ -538,6 +895,17 @@ macro_rules! twi {
             fn clock(clocks: &Clocks) -> Hertz {
                 clocks.per()
             }
+
+            fn ptr() -> *const RegisterBlock {
+                crate::pac::$TWI::ptr()
+            }
+
+            #[cfg(feature = "async")]
+            fn waker() -> &'static crate::asynch::WakerSlot {
+                static WAKER: crate::asynch::WakerSlot =
+                    avr_device::interrupt::Mutex::new(core::cell::RefCell::new(None));
+                &WAKER
+            }
         }
 
         impl crate::private::Sealed for crate::pac::$TWI {}
@@ -0,0 +1,127 @@
+//! # Async TWI
+//!
+//! Implementation of the [`embedded_hal_async::i2c::I2c`] trait for the
+//! [`Twi`] in host mode.
+//!
+//! The task waiting for the transfer of a byte is woken up by the host read
+//! and write interrupts. The interrupt handler has to call
+//! [`on_interrupt`] for the TWI instance:
+//!
+//! ```
+//! #[avr_device::interrupt(attiny817)]
+//! fn TWI0_TWIM() {
+//!     twi::asynch::on_interrupt::<pac::TWI0>();
+//! }
+//!
+//! let mut twi = Twi::new(dp.TWI0, twi_pair, 100000.Hz(), clocks);
+//! twi.write_read(0x50, &[0x00, 0x00], &mut buf).await.unwrap();
+//! ```
+
+use core::future::poll_fn;
+use core::task::Poll;
+
+use avr_device::interrupt;
+
+use crate::embedded_hal_async::i2c::{I2c, Operation};
+
+use super::{Error, HostTransaction, Instance, SclPin, SdaPin, Twi, TwiPinset};
+
+/// Interrupt handler helper for the async [`I2c`] implementation
+///
+/// Disables the host read and write interrupts and wakes up the waiting
+/// task. Call this function from the TWI host interrupt handler.
+#[inline]
+pub fn on_interrupt<TWI: Instance>() {
+    // NOTE(unsafe): Single read-modify-write from the interrupt handler while
+    // the task is waiting
+    let twi = unsafe { &*TWI::ptr() };
+    twi.mctrla()
+        .modify(|_, w| w.rien().clear_bit().wien().clear_bit());
+
+    interrupt::free(|cs| {
+        if let Some(waker) = TWI::waker().borrow(cs).borrow_mut().take() {
+            waker.wake();
+        }
+    });
+}
+
+impl<TWI, SCL, SDA> Twi<TWI, TwiPinset<TWI, SCL, SDA>>
+where
+    TWI: Instance,
+    SCL: SclPin<TWI>,
+    SDA: SdaPin<TWI>,
+{
+    /// Wait until the address or data byte has been transferred
+    async fn wait_status(&mut self, host: &mut HostTransaction) -> Result<(), Error> {
+        poll_fn(|cx| {
+            interrupt::free(|cs| match host.poll(&self.twi) {
+                Ok(()) => Poll::Ready(Ok(())),
+                Err(nb::Error::Other(e)) => Poll::Ready(Err(e)),
+                Err(nb::Error::WouldBlock) => {
+                    let mut slot = TWI::waker().borrow(cs).borrow_mut();
+                    match slot.as_ref() {
+                        Some(waker) if waker.will_wake(cx.waker()) => {}
+                        _ => *slot = Some(cx.waker().clone()),
+                    }
+
+                    // The flags are checked again in the critical section, so
+                    // the interrupt can't be missed
+                    self.twi
+                        .mctrla()
+                        .modify(|_, w| w.rien().set_bit().wien().set_bit());
+                    Poll::Pending
+                }
+            })
+        })
+        .await
+    }
+}
+
+impl<TWI, SCL, SDA> I2c for Twi<TWI, TwiPinset<TWI, SCL, SDA>>
+where
+    TWI: Instance,
+    SCL: SclPin<TWI>,
+    SDA: SdaPin<TWI>,
+{
+    async fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        let result = self.execute_async(address, operations).await;
+        if let Err(e) = result {
+            self.recover_from_error(e);
+        }
+        result
+    }
+}
+
+impl<TWI, SCL, SDA> Twi<TWI, TwiPinset<TWI, SCL, SDA>>
+where
+    TWI: Instance,
+    SCL: SclPin<TWI>,
+    SDA: SdaPin<TWI>,
+{
+    /// Async version of `execute`
+    async fn execute_async(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Error> {
+        // Detect Bus busy
+        if self.twi.mstatus().read().busstate().is_busy() {
+            return Err(Error::Busy);
+        }
+
+        if operations.is_empty() {
+            return Ok(());
+        }
+
+        let mut host = HostTransaction::new(&self.twi, address);
+        while !host.step(&self.twi, operations) {
+            self.wait_status(&mut host).await?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,199 @@
+//! # TWI client mode
+//!
+//! The client responds to a host addressing it on the bus. Besides a single
+//! address, it can respond to a range of addresses selected by an address
+//! mask, to two distinct addresses and to the general call address 0. This
+//! allows to emulate multiple devices with a single client.
+//!
+//! ```
+//! let address = ClientAddress::Mask { address: 0x50, mask: 0x07 };
+//! let mut client = TwiClient::new(dp.TWI0, twi_pair, address, false);
+//!
+//! loop {
+//!     match nb::block!(client.poll()) {
+//!         // Emulate the EEPROMs at 0x50..=0x57
+//!         Ok(ClientEvent::AddressMatch { .. }) => client.ack(),
+//!         Ok(ClientEvent::DataReceived(_)) => client.ack(),
+//!         Ok(ClientEvent::ReadRequest) => client.write(0x55),
+//!         Ok(ClientEvent::Stop) | Err(_) => {}
+//!     }
+//! }
+//! ```
+
+use super::{Error, Instance, SclPin, SdaPin, TwiPinset};
+
+/// Addresses the client responds to
+#[derive(ufmt::derive::uDebug, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ClientAddress {
+    /// A single 7 bit address
+    Single(u8),
+    /// All addresses matching `address` in the bits which are cleared in `mask`
+    #[doc(alias = "ADDRMASK")]
+    Mask {
+        /// 7 bit address
+        address: u8,
+        /// 7 bit mask of the address bits to ignore
+        mask: u8,
+    },
+    /// Two distinct 7 bit addresses
+    #[doc(alias = "ADDREN")]
+    Dual(u8, u8),
+}
+
+/// Events on the bus which need to be handled by the client
+#[derive(ufmt::derive::uDebug, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ClientEvent {
+    /// The client was addressed by the host
+    ///
+    /// Has to be answered by [`TwiClient::ack`] or [`TwiClient::nack`].
+    AddressMatch {
+        /// The received address, 0 for a general call
+        address: u8,
+        /// The host wants to read from the client
+        read: bool,
+    },
+    /// The host wrote a byte
+    ///
+    /// Has to be answered by [`TwiClient::ack`] or [`TwiClient::nack`].
+    DataReceived(u8),
+    /// The host wants to read a byte
+    ///
+    /// Has to be answered by [`TwiClient::write`].
+    ReadRequest,
+    /// The host ended the transfer with a STOP condition
+    Stop,
+}
+
+/// TWI abstraction in client mode
+///
+/// See the [module level documentation](self) for details.
+pub struct TwiClient<TWI, Pinset> {
+    twi: TWI,
+    pinset: Pinset,
+    // The host read at least one byte of the current transfer
+    reading: bool,
+}
+
+impl<TWI, SCL, SDA> TwiClient<TWI, TwiPinset<TWI, SCL, SDA>>
+where
+    TWI: Instance,
+    SCL: SclPin<TWI>,
+    SDA: SdaPin<TWI>,
+{
+    /// Configures the TWI peripheral to work in client mode
+    ///
+    /// The client responds to the given `address` and, if `general_call` is
+    /// set, to the general call address 0.
+    pub fn new(
+        twi: TWI,
+        pinset: TwiPinset<TWI, SCL, SDA>,
+        address: ClientAddress,
+        general_call: bool,
+    ) -> Self {
+        let mut client = Self {
+            twi,
+            pinset,
+            reading: false,
+        };
+        client.set_address(address, general_call);
+
+        // Also flag STOP conditions to be able to detect the end of a transfer
+        client
+            .twi
+            .sctrla()
+            .modify(|_, w| w.pien().set_bit().enable().set_bit());
+
+        client
+    }
+
+    /// Change the addresses the client responds to
+    #[doc(alias = "SADDR")]
+    #[doc(alias = "SADDRMASK")]
+    pub fn set_address(&mut self, address: ClientAddress, general_call: bool) {
+        let (address, mask, second_address) = match address {
+            ClientAddress::Single(address) => (address, 0, false),
+            ClientAddress::Mask { address, mask } => (address, mask, false),
+            ClientAddress::Dual(first, second) => (first, second, true),
+        };
+
+        // The general call recognition is enabled by the LSB of SADDR
+        self.twi
+            .saddr()
+            .write(|w| w.bits(address << 1 | general_call as u8));
+        self.twi
+            .saddrmask()
+            .write(|w| w.addrmask().bits(mask).addren().bit(second_address));
+    }
+
+    /// Wait for the next event on the bus
+    pub fn poll(&mut self) -> nb::Result<ClientEvent, Error> {
+        let sstatus = self.twi.sstatus().read();
+
+        if sstatus.buserr().bit_is_set() {
+            self.twi.sstatus().write(|w| w.buserr().set_bit());
+            return Err(nb::Error::Other(Error::Bus));
+        } else if sstatus.coll().bit_is_set() {
+            self.twi.sstatus().write(|w| w.coll().set_bit());
+            return Err(nb::Error::Other(Error::Arbitration));
+        }
+
+        if sstatus.apif().bit_is_set() {
+            if sstatus.ap().bit_is_set() {
+                self.reading = false;
+                return Ok(ClientEvent::AddressMatch {
+                    address: self.twi.sdata().read().bits() >> 1,
+                    read: sstatus.dir().bit_is_set(),
+                });
+            }
+
+            self.twi.sstatus().write(|w| w.apif().set_bit());
+            return Ok(ClientEvent::Stop);
+        }
+
+        if sstatus.dif().bit_is_set() {
+            if sstatus.dir().bit_is_clear() {
+                return Ok(ClientEvent::DataReceived(self.twi.sdata().read().bits()));
+            }
+
+            // The host NACKed the previous byte and doesn't want to read any more
+            if self.reading && sstatus.rxack().bit_is_set() {
+                self.twi.sctrlb().write(|w| w.scmd().comptrans());
+                return Err(nb::Error::WouldBlock);
+            }
+
+            return Ok(ClientEvent::ReadRequest);
+        }
+
+        Err(nb::Error::WouldBlock)
+    }
+
+    /// Acknowledge the address or data byte and continue the transfer
+    #[inline]
+    pub fn ack(&mut self) {
+        self.twi
+            .sctrlb()
+            .write(|w| w.ackact().clear_bit().scmd().response());
+    }
+
+    /// Not-acknowledge the address or data byte
+    #[inline]
+    pub fn nack(&mut self) {
+        self.twi
+            .sctrlb()
+            .write(|w| w.ackact().set_bit().scmd().response());
+    }
+
+    /// Send a byte to the host after a [`ClientEvent::ReadRequest`]
+    #[inline]
+    pub fn write(&mut self, byte: u8) {
+        self.reading = true;
+        self.twi.sdata().write(|w| w.bits(byte));
+        self.twi.sctrlb().write(|w| w.scmd().response());
+    }
+
+    /// Disables the client and releases the TWI peripheral and associated pins
+    pub fn free(self) -> (TWI, TwiPinset<TWI, SCL, SDA>) {
+        self.twi.sctrla().modify(|_, w| w.enable().clear_bit());
+        (self.twi, self.pinset)
+    }
+}
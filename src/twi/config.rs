@@ -1,5 +1,36 @@
+use crate::pac::twi0::mctrla::TIMEOUT_A;
 use crate::time::*;
 
+/// Inactive bus timeout
+///
+/// Wrapper around [`TIMEOUT_A`]
+///
+/// The bus state logic is forced into the idle state if the bus is inactive
+/// for the selected time. This is required by SMBus and allows to recover from
+/// hung buses, where a transfer would otherwise wait forever.
+#[derive(ufmt::derive::uDebug, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BusTimeout {
+    /// Timeout disabled (I2C)
+    Disabled,
+    /// 50µs (SMBus)
+    Us50,
+    /// 100µs
+    Us100,
+    /// 200µs
+    Us200,
+}
+
+impl From<BusTimeout> for TIMEOUT_A {
+    fn from(timeout: BusTimeout) -> Self {
+        match timeout {
+            BusTimeout::Disabled => TIMEOUT_A::DISABLED,
+            BusTimeout::Us50 => TIMEOUT_A::_50US,
+            BusTimeout::Us100 => TIMEOUT_A::_100US,
+            BusTimeout::Us200 => TIMEOUT_A::_200US,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Config {
     /// TWI bus clock frequency
@@ -8,6 +39,14 @@ pub struct Config {
     pub rise_time: NanosDuration,
     /// Fast Mode Plus enabled (400kHz to 1Mhz)
     pub fast_mode_plus: bool,
+    /// Inactive bus timeout
+    pub timeout: BusTimeout,
+    /// Keep the peripheral running while the CPU is halted by the debugger
+    pub debug_run: bool,
+    /// Send the ACK action automatically when a received byte is read
+    pub smart_mode: bool,
+    /// Complete a transaction right after the address has been acknowledged
+    pub quick_command: bool,
 }
 
 impl Config {
@@ -23,6 +62,48 @@ impl Config {
         self
     }
 
+    /// Enables or disables the fast mode plus
+    ///
+    /// This is needed for frequencies above 400kHz.
+    pub fn fast_mode_plus(mut self, enable: bool) -> Self {
+        self.fast_mode_plus = enable;
+        self
+    }
+
+    /// Sets the inactive bus timeout.
+    pub fn timeout(mut self, timeout: BusTimeout) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Enables or disables running in debug mode.
+    #[doc(alias = "DBGRUN")]
+    pub fn debug_run(mut self, enable: bool) -> Self {
+        self.debug_run = enable;
+        self
+    }
+
+    /// Enables or disables the smart mode.
+    ///
+    /// In smart mode, reading a received byte sends the ACK or NACK right
+    /// away. The drivers in this HAL handle both modes.
+    #[doc(alias = "SMEN")]
+    pub fn smart_mode(mut self, enable: bool) -> Self {
+        self.smart_mode = enable;
+        self
+    }
+
+    /// Enables or disables the SMBus quick command.
+    ///
+    /// With the quick command, the R/W bit of the address is the only data
+    /// transferred. A zero-length write or read completes right after the
+    /// address has been acknowledged.
+    #[doc(alias = "QCEN")]
+    pub fn quick_command(mut self, enable: bool) -> Self {
+        self.quick_command = enable;
+        self
+    }
+
     pub fn default_for_frequency(frequency: impl Into<Hertz>) -> Option<Self> {
         // Maximum rise-times according to datasheet:
         // fscl <= 100KHz  -> trise = 1000ns
@@ -35,18 +116,30 @@ impl Config {
                 frequency,
                 rise_time: 1000.nanos(),
                 fast_mode_plus: false,
+                timeout: BusTimeout::Disabled,
+                debug_run: false,
+                smart_mode: false,
+                quick_command: false,
             })
         } else if frequency.raw() <= 400_000 {
             Some(Self {
                 frequency,
                 rise_time: 300.nanos(),
                 fast_mode_plus: false,
+                timeout: BusTimeout::Disabled,
+                debug_run: false,
+                smart_mode: false,
+                quick_command: false,
             })
         } else if frequency.raw() <= 1_000_000 {
             Some(Self {
                 frequency,
                 rise_time: 120.nanos(),
                 fast_mode_plus: true,
+                timeout: BusTimeout::Disabled,
+                debug_run: false,
+                smart_mode: false,
+                quick_command: false,
             })
         } else {
             None
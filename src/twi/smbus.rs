@@ -0,0 +1,102 @@
+//! # SMBus helpers
+//!
+//! The SMBus is built on top of I²C. Besides the bus timeout in the
+//! [`Config`](super::config::Config), it adds a few transfers with a special
+//! meaning:
+//!
+//! * The quick command transfers a single bit of data in the R/W bit of the
+//!   address, without any data bytes.
+//! * A client asserting the optional SMBALERT# line is asked for its address
+//!   by a read from the Alert Response Address (ARA). The alerting client
+//!   answers with its own address, if multiple clients are alerting, the one
+//!   with the lowest address wins the arbitration. The other clients get an
+//!   [`Error::Arbitration`] from [`TwiClient::poll`](super::client::TwiClient::poll).
+//!
+//! ```
+//! // Host: find out which client asserted SMBALERT#
+//! if let Some(address) = twi.alert_response().unwrap() {
+//!     twi.write(address, &[0x00]).unwrap();
+//! }
+//!
+//! // Client: respond to the ARA while an alert is pending
+//! let mut client = TwiClient::new(dp.TWI0, twi_pair, ClientAddress::with_alert_response(0x42), false);
+//! match nb::block!(client.poll()) {
+//!     Ok(event) if event.is_alert_response() => client.ack(),
+//!     Ok(ClientEvent::ReadRequest) => client.write(0x42 << 1),
+//!     // ...
+//! }
+//! ```
+
+use embedded_hal::i2c::{I2c, Operation};
+
+use super::client::{ClientAddress, ClientEvent};
+use super::{Error, Instance, NackSource, SclPin, SdaPin, Twi, TwiPinset};
+
+/// SMBus Alert Response Address
+#[doc(alias = "ARA")]
+pub const ALERT_RESPONSE_ADDRESS: u8 = 0x0C;
+
+impl<TWI, SCL, SDA> Twi<TWI, TwiPinset<TWI, SCL, SDA>>
+where
+    TWI: Instance,
+    SCL: SclPin<TWI>,
+    SDA: SdaPin<TWI>,
+{
+    /// Send a SMBus quick command
+    ///
+    /// The `bit` is sent in the R/W bit of the address. Without the
+    /// [`quick_command`](method@super::config::Config::quick_command) mode enabled,
+    /// a quick command with the bit set clocks a data byte out of the client,
+    /// which is then NACKed.
+    #[doc(alias = "QCEN")]
+    pub fn quick_command(&mut self, address: u8, bit: bool) -> Result<(), Error> {
+        let mut buffer: [u8; 0] = [];
+        let operation = if bit {
+            Operation::Read(&mut buffer)
+        } else {
+            Operation::Write(&[])
+        };
+
+        I2c::transaction(self, address, &mut [operation])
+    }
+
+    /// Ask the client asserting SMBALERT# for its address
+    ///
+    /// Returns `None` if no client responds to the Alert Response Address.
+    #[doc(alias = "ARA")]
+    pub fn alert_response(&mut self) -> Result<Option<u8>, Error> {
+        let mut buffer = [0];
+
+        match I2c::read(self, ALERT_RESPONSE_ADDRESS, &mut buffer) {
+            // The R/W bit sent along with the address is ignored
+            Ok(()) => Ok(Some(buffer[0] >> 1)),
+            Err(Error::Nack(NackSource::Address)) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl ClientAddress {
+    /// Respond to the `address` and to the SMBus Alert Response Address
+    pub const fn with_alert_response(address: u8) -> Self {
+        ClientAddress::Dual(address, ALERT_RESPONSE_ADDRESS)
+    }
+}
+
+impl ClientEvent {
+    /// Check if the host reads from the SMBus Alert Response Address
+    ///
+    /// A client with a pending alert has to [`ack`](super::client::TwiClient::ack)
+    /// the address and [`write`](super::client::TwiClient::write) its own
+    /// address in the upper 7 bits of the following byte. All other clients
+    /// have to [`nack`](super::client::TwiClient::nack) the address.
+    pub fn is_alert_response(&self) -> bool {
+        matches!(
+            self,
+            ClientEvent::AddressMatch {
+                address: ALERT_RESPONSE_ADDRESS,
+                read: true,
+            }
+        )
+    }
+}
@@ -0,0 +1,174 @@
+//! # Non-blocking TWI transfers
+//!
+//! A [`Transfer`] is started by [`Twi::start_transfer`] and then progressed
+//! byte by byte by [`Twi::progress`], either by polling it from the main loop
+//! or from the TWI interrupt handler. This way slow devices don't stall the
+//! rest of the application while the transfer is running.
+//!
+//! ```
+//! static TWI: Mutex<RefCell<Option<(Twi<pac::TWI0, Pins>, Transfer<'static>)>>> = Mutex::new(RefCell::new(None));
+//!
+//! #[avr_device::interrupt(attiny817)]
+//! fn TWI0_TWIM() {
+//!     interrupt::free(|cs| {
+//!         if let Some((twi, transfer)) = TWI.borrow(cs).borrow_mut().as_mut() {
+//!             let _ = twi.progress(transfer);
+//!         }
+//!     });
+//! }
+//!
+//! static DATA: [u8; 3] = [0x00, 0x00, 0x55];
+//! let mut transfer = Transfer::write(0x50, &DATA);
+//! twi.enable_interrupt(Interrupt::Read);
+//! twi.enable_interrupt(Interrupt::Write);
+//! twi.start_transfer(&mut transfer).unwrap();
+//! ```
+//!
+//! The transfer is finished once [`Transfer::result`] returns a result.
+
+use embedded_hal::i2c::Operation;
+
+use super::{Error, HostTransaction, Instance, SclPin, SdaPin, Twi, TwiPinset};
+
+/// State of a [`Transfer`]
+enum State {
+    /// Not started yet
+    Idle,
+    /// Waiting for the current byte to be transferred
+    Running(HostTransaction),
+    /// The transfer is finished
+    Done(Result<(), Error>),
+}
+
+/// A write, read or write-read transfer for [`Twi::progress`]
+///
+/// See the [module level documentation](self) for details.
+pub struct Transfer<'a> {
+    address: u8,
+    write: &'a [u8],
+    read: &'a mut [u8],
+    state: State,
+}
+
+impl<'a> Transfer<'a> {
+    /// Creates a transfer writing `bytes` to the client with the given address
+    pub fn write(address: u8, bytes: &'a [u8]) -> Self {
+        Self::write_read(address, bytes, &mut [])
+    }
+
+    /// Creates a transfer reading into `buffer` from the client with the given address
+    pub fn read(address: u8, buffer: &'a mut [u8]) -> Self {
+        Self::write_read(address, &[], buffer)
+    }
+
+    /// Creates a transfer writing `bytes` and then reading into `buffer` after
+    /// a repeated START
+    pub fn write_read(address: u8, bytes: &'a [u8], buffer: &'a mut [u8]) -> Self {
+        Self {
+            address,
+            write: bytes,
+            read: buffer,
+            state: State::Idle,
+        }
+    }
+
+    /// Returns the result of the transfer if it is finished
+    #[inline]
+    pub fn result(&self) -> Option<Result<(), Error>> {
+        match self.state {
+            State::Done(result) => Some(result),
+            _ => None,
+        }
+    }
+
+    /// Execute the next step of the transfer, returns `true` once it is finished
+    ///
+    /// Transfers without any data to read are a single write, which allows to
+    /// probe a client with an empty write.
+    fn step(&mut self, twi: &super::RegisterBlock) -> bool {
+        let State::Running(host) = &mut self.state else {
+            return true;
+        };
+
+        let (write_only, read_only) = (self.read.is_empty(), self.write.is_empty());
+        let mut operations = [
+            Operation::Write(self.write),
+            Operation::Read(&mut *self.read),
+        ];
+        let operations = if write_only {
+            &mut operations[..1]
+        } else if read_only {
+            &mut operations[1..]
+        } else {
+            &mut operations[..]
+        };
+
+        host.step(twi, operations)
+    }
+
+    /// Check if the transfer is finished
+    #[inline]
+    pub fn is_done(&self) -> bool {
+        self.result().is_some()
+    }
+
+    /// Releases the buffers of the transfer
+    pub fn free(self) -> (&'a [u8], &'a mut [u8]) {
+        (self.write, self.read)
+    }
+}
+
+impl<TWI, SCL, SDA> Twi<TWI, TwiPinset<TWI, SCL, SDA>>
+where
+    TWI: Instance,
+    SCL: SclPin<TWI>,
+    SDA: SdaPin<TWI>,
+{
+    /// Starts a transfer by sending the address of the client
+    pub fn start_transfer(&mut self, transfer: &mut Transfer) -> Result<(), Error> {
+        if self.twi.mstatus().read().busstate().is_busy() {
+            transfer.state = State::Done(Err(Error::Busy));
+            return Err(Error::Busy);
+        }
+
+        transfer.state = State::Running(HostTransaction::new(&self.twi, transfer.address));
+        transfer.step(&self.twi);
+
+        Ok(())
+    }
+
+    /// Progresses a running transfer
+    ///
+    /// This has to be called whenever the read or write interrupt flag is set,
+    /// e.g. from the TWI host interrupt handler with the [`Interrupt::Read`]
+    /// and [`Interrupt::Write`] interrupts enabled. Returns
+    /// [`nb::Error::WouldBlock`] as long as the transfer isn't finished.
+    ///
+    /// [`Interrupt::Read`]: super::Interrupt::Read
+    /// [`Interrupt::Write`]: super::Interrupt::Write
+    pub fn progress(&mut self, transfer: &mut Transfer) -> nb::Result<(), Error> {
+        let result = match &mut transfer.state {
+            State::Idle => return Err(nb::Error::WouldBlock),
+            State::Done(result) => return (*result).map_err(nb::Error::Other),
+            State::Running(host) => match host.poll(&self.twi) {
+                Err(nb::Error::WouldBlock) => return Err(nb::Error::WouldBlock),
+                Err(nb::Error::Other(e)) => Err(e),
+                Ok(()) if transfer.step(&self.twi) => Ok(()),
+                Ok(()) => return Err(nb::Error::WouldBlock),
+            },
+        };
+
+        transfer.state = State::Done(result);
+
+        // Don't leave a flag behind that keeps firing the interrupt
+        match result {
+            Ok(()) => self
+                .twi
+                .mstatus()
+                .write(|w| w.rif().set_bit().wif().set_bit()),
+            Err(e) => self.recover_from_error(e),
+        }
+
+        result.map_err(nb::Error::Other)
+    }
+}
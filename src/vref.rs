@@ -1,62 +1,115 @@
 //! # Voltage reference
+//!
+//! The VREF peripheral generates the internal reference voltages of the ADC0
+//! and the DAC0. The reference of DAC0 is also the internal reference of AC0.
+//! Splitting the peripheral hands out exactly one token per reference, which
+//! the peripherals using the reference take when they are constrained:
+//!
+//! * the [`ADCReferenceVoltage`] selects the internal reference of the ADC
+//! * the [`DACReferenceVoltage`] is needed to constrain the DAC or can be
+//!   used as a negative input of the comparator
+//!
+//! The voltage can only be selected through the token, so it can't change
+//! under a peripheral which owns it. The DAC and AC0 share their reference,
+//! and as there is only one [`DACReferenceVoltage`], only one of them can use
+//! it. The comparator can still compare against the DAC output instead.
+//!
+//! ```
+//! let mut vref = dp.VREF.split();
+//! let dac_ref = vref.dac0.with_voltage(&mut vref.vref, ReferenceVoltage::_2V50);
+//!
+//! assert_eq!(vref.vref.dac0_voltage(), Some(ReferenceVoltage::_2V50));
+//! assert_eq!(ReferenceVoltage::_2V50.millivolts(), 2500);
+//! ```
 
 // TODO: macros for different CPUs which have different peripherals
 // FIXME: move this into the DAC and ADC modules? DAC and AC share the channel though
 
-use crate::{pac::VREF, Toggle};
+use crate::{embedded_hal::delay::DelayNs, pac::VREF, Toggle};
 
-/// Extension trait that constrains the [`VREF`] peripheral
+/// Time the internal reference needs to settle after it was powered up
+pub const SETTLING_TIME_US: u32 = 32;
+
+/// Extension trait that splits the [`VREF`] peripheral
 pub trait VrefExt {
-    /// Constrains the [`VREF`] peripheral.
+    /// Splits the [`VREF`] peripheral into its reference tokens.
     ///
-    /// Consumes the [`pac::VREF`] peripheral and converts it to a [`HAL`] internal type
+    /// Consumes the [`pac::VREF`] peripheral and converts it to [`HAL`] internal types
     /// constraining it's public access surface to fit the design of the `HAL`.
+    /// Every token is only handed out once.
     ///
     /// [`pac::VREF`]: `crate::pac::VREF`
     /// [`HAL`]: `crate`
-    fn constrain(self) -> Vref;
+    fn split(self) -> Parts;
 }
 
-/// Constrained VREF peripheral
-///
-/// An instance of this struct is acquired by calling the [`constrain`](VrefExt::constrain) function
-/// on the [`VREF`] struct.
+/// The parts of the split VREF peripheral
 ///
 /// ```
 /// let dp = pac::Peripherals::take().unwrap();
-/// let vref = dp.VREF.constrain();
+/// let vref = dp.VREF.split();
 /// ```
+pub struct Parts {
+    /// The registers shared by all references
+    pub vref: Vref,
+    /// The reference of ADC0
+    pub adc0: ADCReferenceVoltage<0>,
+    /// The reference of DAC0, which is shared with AC0
+    pub dac0: DACReferenceVoltage<0>,
+}
+
+/// Constrained VREF peripheral
+///
+/// Part of the [`Parts`] returned by [`split`](VrefExt::split). The
+/// selected voltages are changed through the reference tokens, which borrow
+/// it to access the shared registers.
 pub struct Vref {
     vref: VREF,
 }
 
 impl VrefExt for VREF {
-    fn constrain(self) -> Vref {
-        Vref { vref: self }
+    fn split(self) -> Parts {
+        Parts {
+            vref: Vref { vref: self },
+            adc0: ADCReferenceVoltage { _private: () },
+            dac0: DACReferenceVoltage { _private: () },
+        }
     }
 }
 
 /// Reference voltage for an ADC
-#[derive(Copy, Clone, Eq, PartialEq)]
-pub struct ADCReferenceVoltage<const IDX: u8>;
+///
+/// There is only one token per ADC, so the ADC owning it can rely on the
+/// voltage to stay the same.
+#[derive(Eq, PartialEq)]
+pub struct ADCReferenceVoltage<const IDX: u8> {
+    _private: (),
+}
 
 /// Reference voltage for a DAC
-#[derive(Copy, Clone, Eq, PartialEq)]
-pub struct DACReferenceVoltage<const IDX: u8>;
+///
+/// There is only one token per DAC, which is either owned by the DAC or used
+/// as the negative input of the comparator sharing the reference.
+#[derive(Eq, PartialEq)]
+pub struct DACReferenceVoltage<const IDX: u8> {
+    _private: (),
+}
 
 impl<const IDX: u8> crate::private::Sealed for ADCReferenceVoltage<IDX> {}
 impl<const IDX: u8> crate::private::Sealed for DACReferenceVoltage<IDX> {}
 
 macro_rules! impl_reference_voltage {
-    ($name:ident, $periphname:ident, $structret:ident, $refstruct:ty, $refvolttype:ty, $refselreg:ident, $refselbits:ident, $forceenreg:ident, $forceenbit:ident) => {
+    ($name:ident, $periphname:ident, $refstruct:ty, $refvolttype:ty, $refselreg:ident, $refselbits:ident, $forceenreg:ident, $forceenbit:ident) => {
         impl Vref {
-            #[doc = "Retrieve the reference voltage for the peripheral "]
-            #[doc = stringify!($periphname)]
-            pub fn $name(&mut self, voltage: $refvolttype) -> $refstruct {
-                self.vref
-                    .$refselreg()
-                    .modify(|_, w| unsafe { w.$refselbits().bits(voltage as u8) });
-                $structret
+            paste::paste! {
+                #[doc = "Returns the reference voltage currently selected for the peripheral "]
+                #[doc = stringify!($periphname)]
+                ///
+                /// `None` is returned if a reserved value has been written
+                /// to the register.
+                pub fn [<$name _voltage>](&self) -> Option<ReferenceVoltage> {
+                    ReferenceVoltage::from_bits(self.vref.$refselreg().read().$refselbits().bits())
+                }
             }
         }
 
@@ -64,16 +117,32 @@ macro_rules! impl_reference_voltage {
         #[doc = stringify!($periphname)]
         impl $refstruct {
             /// Set the reference voltage to the specified level.
-            pub fn voltage(vref: &mut Vref, voltage: $refvolttype) {
+            pub fn voltage(&mut self, vref: &mut Vref, voltage: $refvolttype) {
                 vref.vref
                     .$refselreg()
                     .modify(|_, w| unsafe { w.$refselbits().bits(voltage as u8) });
             }
 
+            /// Set the reference voltage to the specified level and return the token
+            ///
+            /// Like [`voltage`](Self::voltage), but to be used when passing
+            /// the token to a peripheral right away.
+            pub fn with_voltage(mut self, vref: &mut Vref, voltage: $refvolttype) -> Self {
+                self.voltage(vref, voltage);
+                self
+            }
+
             /// Force-enable the reference voltage.
             ///
             /// Usually the peripherals that use the reference voltage enable it
-            /// automatically. Using this method it can be force-enabled.
+            /// automatically and it is powered down again when they don't need
+            /// it anymore. This draws the least current, but the reference has
+            /// to settle for up to [`SETTLING_TIME_US`] each time it is powered
+            /// up again, e.g. for every ADC conversion, which has to be covered
+            /// by the [`InitDelay`](crate::adc::InitDelay) of the ADC.
+            ///
+            /// Force-enabling keeps the reference powered, so it is ready
+            /// without any delay at the cost of its supply current.
             pub fn force(vref: &mut Vref, force: impl Into<Toggle>) {
                 let force: Toggle = force.into();
                 let force: bool = force.into();
@@ -81,6 +150,22 @@ macro_rules! impl_reference_voltage {
                     .$forceenreg()
                     .modify(|_, w| w.$forceenbit().bit(force));
             }
+
+            /// Force-enable the reference voltage and wait until it has settled
+            ///
+            /// See [`force`](Self::force) for the tradeoff.
+            pub fn force_settled(vref: &mut Vref, delay: &mut impl DelayNs) {
+                let forced = Self::is_forced(vref);
+                Self::force(vref, Toggle::On);
+                if !forced {
+                    delay.delay_us(SETTLING_TIME_US);
+                }
+            }
+
+            /// Check if the reference voltage is force-enabled
+            pub fn is_forced(vref: &Vref) -> bool {
+                vref.vref.$forceenreg().read().$forceenbit().bit()
+            }
         }
     };
 }
@@ -104,10 +189,33 @@ pub enum ReferenceVoltage {
     _1V50 = 0x04,
 }
 
+impl ReferenceVoltage {
+    /// Returns the reference voltage in millivolts
+    pub const fn millivolts(self) -> u16 {
+        match self {
+            ReferenceVoltage::_0V55 => 550,
+            ReferenceVoltage::_1V10 => 1100,
+            ReferenceVoltage::_2V50 => 2500,
+            ReferenceVoltage::_4V34 => 4340,
+            ReferenceVoltage::_1V50 => 1500,
+        }
+    }
+
+    pub(crate) fn from_bits(bits: u8) -> Option<Self> {
+        match bits {
+            0x00 => Some(ReferenceVoltage::_0V55),
+            0x01 => Some(ReferenceVoltage::_1V10),
+            0x02 => Some(ReferenceVoltage::_2V50),
+            0x03 => Some(ReferenceVoltage::_4V34),
+            0x04 => Some(ReferenceVoltage::_1V50),
+            _ => None,
+        }
+    }
+}
+
 impl_reference_voltage!(
     adc0,
     ADC0,
-    ADCReferenceVoltage,
     ADCReferenceVoltage<0>,
     ReferenceVoltage,
     ctrla,
@@ -119,7 +227,6 @@ impl_reference_voltage!(
 impl_reference_voltage!(
     dac0,
     DAC0,
-    DACReferenceVoltage,
     DACReferenceVoltage<0>,
     ReferenceVoltage,
     ctrla,